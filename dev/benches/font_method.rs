@@ -5,6 +5,8 @@ use approx::assert_relative_eq;
 use criterion::{criterion_group, criterion_main, Criterion};
 
 const OPENS_SANS_ITALIC: &[u8] = include_bytes!("../fonts/OpenSans-Italic.ttf");
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+const DEJA_VU_MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
 
 fn bench_font_glyph_id(c: &mut Criterion) {
     let font = FontRef::try_from_slice(OPENS_SANS_ITALIC).unwrap();
@@ -39,13 +41,107 @@ fn bench_font_glyph_id(c: &mut Criterion) {
     });
 }
 
+fn bench_font_load(c: &mut Criterion) {
+    c.bench_function("load:FontRef", |b| {
+        b.iter(|| FontRef::try_from_slice(OPENS_SANS_ITALIC).unwrap());
+    });
+
+    // GPOS kerning, no `kern` table
+    c.bench_function("load:FontRef:gpos", |b| {
+        b.iter(|| FontRef::try_from_slice(EXO2_OTF).unwrap());
+    });
+}
+
+fn bench_gpos_kern(c: &mut Criterion) {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let (a, v) = (font.glyph_id('A'), font.glyph_id('V'));
+    let (a_lower, b_lower) = (font.glyph_id('a'), font.glyph_id('b'));
+
+    c.bench_function("method:Font::kern_unscaled:gpos", |b| {
+        let mut kern = 0.0;
+
+        b.iter(|| kern = font.kern_unscaled(a, v));
+
+        assert!(kern < 0.0);
+    });
+
+    c.bench_function("method:Font::kern_unscaled:gpos:none", |b| {
+        let mut kern = 1.0;
+
+        b.iter(|| kern = font.kern_unscaled(a_lower, b_lower));
+
+        assert_relative_eq!(kern, 0.0);
+    });
+}
+
+fn bench_hinted_outline(c: &mut Criterion) {
+    let font = FontRef::try_from_slice(DEJA_VU_MONO).unwrap();
+    let glyph = font.glyph_id('a');
+
+    c.bench_function("method:Font::hinted_outline", |b| {
+        let mut outline = None;
+
+        b.iter(|| outline = font.hinted_outline(glyph, 12.0));
+
+        assert!(outline.is_some());
+    });
+}
+
+fn bench_glyph_id_cache(c: &mut Criterion) {
+    let caches = [
+        ("Default", GlyphIdCache::default()),
+        ("Disabled", GlyphIdCache::Disabled),
+        ("Chars", GlyphIdCache::Chars("xyz".into())),
+        ("Bmp", GlyphIdCache::Bmp),
+        ("Lazy", GlyphIdCache::Lazy),
+    ];
+
+    for (name, cache) in caches {
+        c.bench_function(&format!("load:GlyphIdCache::{}:mono", name), |b| {
+            b.iter(|| {
+                FontRef::try_from_slice_and_index_with_cache(DEJA_VU_MONO, 0, cache.clone())
+                    .unwrap()
+            });
+        });
+        c.bench_function(&format!("load:GlyphIdCache::{}", name), |b| {
+            b.iter(|| {
+                FontRef::try_from_slice_and_index_with_cache(OPENS_SANS_ITALIC, 0, cache.clone())
+                    .unwrap()
+            });
+        });
+
+        let font =
+            FontRef::try_from_slice_and_index_with_cache(OPENS_SANS_ITALIC, 0, cache).unwrap();
+        c.bench_function(&format!("method:Font::glyph_id:{}", name), |b| {
+            let mut glyph = GlyphId(0);
+
+            b.iter(|| {
+                glyph = font.glyph_id('x');
+            });
+
+            assert_eq!(glyph, GlyphId(91));
+        });
+
+        // a char missing from the font
+        c.bench_function(&format!("method:Font::glyph_id:{}:missing", name), |b| {
+            let mut glyph = GlyphId(1);
+
+            b.iter(|| {
+                glyph = font.glyph_id('\u{4E2D}');
+            });
+
+            assert_eq!(glyph, GlyphId(0));
+        });
+    }
+}
+
 criterion_group!(
     name = font_method_benches;
     config = Criterion::default()
         .warm_up_time(Duration::from_millis(200))
         .sample_size(500)
         .measurement_time(Duration::from_secs(1));
-    targets = bench_font_glyph_id,
+    targets = bench_font_glyph_id, bench_font_load, bench_gpos_kern, bench_hinted_outline, bench_glyph_id_cache,
 );
 
 criterion_main!(font_method_benches);
@@ -155,7 +155,7 @@ fn bench_layout_a_sentence_otf(c: &mut Criterion) {
         }
         assert_eq!(
             format!("{:x}", hash.finalize()),
-            "5c19fee8e6440b3e6fb1c7d0b2a5b3d2354f2f7ccbc2ff4b53ba96cd4e6e37ba"
+            "7b0396c683e3b58587ce8b1fdb2cbda86f37621647b836f8ac41ec133b9b957d"
         );
     });
 }
@@ -192,11 +192,33 @@ fn bench_layout_a_sentence_ttf(c: &mut Criterion) {
         }
         assert_eq!(
             format!("{:x}", hash.finalize()),
-            "5c19fee8e6440b3e6fb1c7d0b2a5b3d2354f2f7ccbc2ff4b53ba96cd4e6e37ba"
+            "7b0396c683e3b58587ce8b1fdb2cbda86f37621647b836f8ac41ec133b9b957d"
         );
     });
 }
 
+fn bench_layout_sections(c: &mut Criterion) {
+    c.bench_function("layout_a_sentence (Layout)", |b| {
+        let fonts = [FontRef::try_from_slice(OPENS_SANS_ITALIC).unwrap()];
+        let geometry = SectionGeometry {
+            screen_position: point(100.0, 0.0),
+            bounds: (600.0, f32::INFINITY),
+        };
+        let sections = [SectionText {
+            text: SENTENCE,
+            scale: PxScale::from(25.0),
+            font_id: 0,
+        }];
+        let mut glyphs = vec![];
+
+        b.iter(|| {
+            glyphs = Layout::default().calculate_glyphs(&fonts, &geometry, &sections);
+        });
+
+        assert_eq!(glyphs.len(), SENTENCE.chars().count());
+    });
+}
+
 criterion_group!(
     name = layout_benches;
     config = Criterion::default().sample_size(400);
@@ -205,6 +227,7 @@ criterion_group!(
         bench_layout_a_sentence_arc_slice,
         bench_layout_a_sentence_otf,
         bench_layout_a_sentence_ttf,
+        bench_layout_sections,
 );
 
 criterion_main!(layout_benches);
@@ -34,7 +34,7 @@ fn bench_layout_and_draw(c: &mut Criterion) {
         });
 
         // sanity check that work has been done
-        assert_relative_eq!(coverage_sum, 6073.028);
+        assert_relative_eq!(coverage_sum, 6073.031);
     });
 
     c.bench_function("layout & draw (exo2-ttf)", |b| {
@@ -60,7 +60,7 @@ fn bench_layout_and_draw(c: &mut Criterion) {
         });
 
         // sanity check that work has been done
-        assert_relative_eq!(coverage_sum, 6069.2656);
+        assert_relative_eq!(coverage_sum, 6069.2495);
     });
 }
 
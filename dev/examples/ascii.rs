@@ -55,7 +55,7 @@ fn draw_ascii<F: Font>(font: F) {
                 // There's still a possibility that the glyph clips the boundaries of the bitmap
                 if x >= 0.0 && (x as usize) < px_width && y >= 0.0 && (y as usize) < px_height {
                     // save the coverage alpha
-                    pixel_data[(x as usize + y as usize * px_width)] += v;
+                    pixel_data[x as usize + y as usize * px_width] += v;
                 }
             });
         }
@@ -71,7 +71,7 @@ fn draw_ascii<F: Font>(font: F) {
     pixel_data
         .into_iter()
         .map(|alpha| ((1.0 - alpha) * mapping_scale + 0.5) as usize)
-        .map(|index| mapping[index.max(0).min(mapping.len() - 1)])
+        .map(|index| mapping[index.min(mapping.len() - 1)])
         .collect::<Vec<_>>()
         .chunks_exact(px_width)
         .skip_while(|row| row.iter().all(|c| *c == b' '))
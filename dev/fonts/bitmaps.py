@@ -0,0 +1,182 @@
+"""Builds bitmaps.ttf: a tiny test font with embedded bitmaps only, run `python3 bitmaps.py`.
+
+glyphs: 0 .notdef, 1 'a' (EBLC/EBDT strikes 8..14), 2 'b' (CBLC/CBDT BGRA ppem 16 & PNG ppem 32),
+        3 'c' (sbix png ppem 20), 4 'd' (sbix dupe of 'c'), 5 'e' (EBLC/EBDT zero size ppem 15)
+"""
+import os, struct, zlib
+
+UPEM = 1000
+
+# 'a' pattern 5x4 coverage levels
+A = [
+    [0.0, 1.0, 1.0, 1.0, 0.0],
+    [1.0, 0.6, 0.0, 0.6, 1.0],
+    [1.0, 0.0, 0.0, 0.25, 1.0],
+    [0.0, 1.0, 1.0, 0.75, 1.0],
+]
+AW, AH = 5, 4
+
+def quant(v, depth):
+    m = (1 << depth) - 1
+    return int(round(v * m))
+
+def bits(values, depth, width, aligned):
+    """pack rows of values with depth bits, MSB first. aligned=row byte padding"""
+    out = bytearray()
+    acc = 0; n = 0
+    def flush():
+        nonlocal acc, n
+        if n:
+            out.append((acc << (8 - n)) & 0xFF)
+        acc = 0; n = 0
+    for row in values:
+        for v in row:
+            q = quant(v, depth)
+            acc = (acc << depth) | q
+            n += depth
+            if n == 8:
+                out.append(acc); acc = 0; n = 0
+        if aligned:
+            flush()
+    flush()
+    return bytes(out)
+
+def small_metrics(w, h, bx, by, adv):
+    return struct.pack('>BBbbB', h, w, bx, by, adv)
+
+def big_metrics(w, h, bx, by, adv):
+    return struct.pack('>BBbbBbbB', h, w, bx, by, adv, 0, 0, adv)
+
+def line_metrics(asc, desc, wmax):
+    return struct.pack('>bbBbbbbbbbbb', asc, desc, wmax, 1, 0, 0, 0, 0, 0, 0, 0, 0)
+
+def png(w, h, rgba_rows):
+    raw = b''.join(b'\x00' + bytes(sum(([*p] for p in row), [])) for row in rgba_rows)
+    def chunk(t, d):
+        c = struct.pack('>I', len(d)) + t + d
+        return c + struct.pack('>I', zlib.crc32(t + d) & 0xffffffff)
+    return (b'\x89PNG\r\n\x1a\n' + chunk(b'IHDR', struct.pack('>IIBBBBB', w, h, 8, 6, 0, 0, 0))
+            + chunk(b'IDAT', zlib.compress(raw)) + chunk(b'IEND', b''))
+
+def build_bitmap_tables(version, strikes):
+    """strikes: list of (ppem, depth, glyph_id, index_format, image_format, glyph_data, metrics)
+    Each strike covers a single glyph with a single index subtable."""
+    n = len(strikes)
+    loc = bytearray(struct.pack('>II', version, n))
+    dat = bytearray(struct.pack('>I', version))
+    size_records = bytearray()
+    subtables = bytearray()
+    sub_base = 8 + 48 * n
+    for ppem, depth, gid, index_format, image_format, data, metrics in strikes:
+        array_offset = sub_base + len(subtables)
+        image_offset = len(dat)
+        dat += data
+        # IndexSubTableArray with a single element, subtable straight after
+        header = struct.pack('>HHI', index_format, image_format, image_offset)
+        if index_format == 1:
+            body = struct.pack('>II', 0, len(data))
+        elif index_format == 3:
+            body = struct.pack('>HH', 0, len(data))
+        elif index_format == 2:
+            body = struct.pack('>I', len(data)) + metrics
+        else:
+            raise ValueError(index_format)
+        sub = struct.pack('>HHI', gid, gid, 8) + header + body
+        while len(sub) % 4:
+            sub += b'\0'
+        subtables += sub
+        size_records += struct.pack('>III I', array_offset, len(sub) - 8, 1, 0)
+        size_records += line_metrics(ppem, 0, ppem) + line_metrics(ppem, 0, ppem)
+        size_records += struct.pack('>HHBBBb', gid, gid, ppem, ppem, depth, 1)
+    loc += size_records + subtables
+    return bytes(loc), bytes(dat)
+
+def a_scaled(s):
+    return [[v for v in row for _ in range(s)] for row in A for _ in range(s)]
+
+ebdt_strikes = []
+# ppem 8: mono byte-aligned (fmt 1)
+ebdt_strikes.append((8, 1, 1, 1, 1, small_metrics(AW, AH, 0, AH, 6) + bits(A, 1, AW, True), None))
+# ppem 9: mono bit-aligned (fmt 2)
+ebdt_strikes.append((9, 1, 1, 3, 2, small_metrics(AW, AH, 0, AH, 6) + bits(A, 1, AW, False), None))
+# ppem 10: gray2 byte-aligned big metrics (fmt 6)
+ebdt_strikes.append((10, 2, 1, 1, 6, big_metrics(AW, AH, 1, AH, 6) + bits(A, 2, AW, True), None))
+# ppem 11: gray2 bit-aligned shared metrics (fmt 5, index fmt 2)
+ebdt_strikes.append((11, 2, 1, 2, 5, bits(A, 2, AW, False), big_metrics(AW, AH, 0, 3, 6)))
+# ppem 12: gray4 byte-aligned (fmt 1)
+ebdt_strikes.append((12, 4, 1, 1, 1, small_metrics(AW, AH, 0, AH, 6) + bits(A, 4, AW, True), None))
+# ppem 13: gray4 bit-aligned big metrics (fmt 7)
+ebdt_strikes.append((13, 4, 1, 3, 7, big_metrics(AW, AH, 0, AH, 6) + bits(A, 4, AW, False), None))
+# ppem 14: gray8 (fmt 1), doubled size
+A2 = a_scaled(2)
+ebdt_strikes.append((14, 8, 1, 1, 1, small_metrics(AW * 2, AH * 2, 0, 2 * AH, 12) + bits(A2, 8, AW * 2, True), None))
+# ppem 15: 'e' zero size (fmt 1)
+ebdt_strikes.append((15, 8, 5, 1, 1, small_metrics(0, 0, 0, 0, 6), None))
+eblc, ebdt = build_bitmap_tables(0x00020000, ebdt_strikes)
+
+# 'b' colour: 4x4, top row red, second green, third blue, last half transparent white
+B_RGBA = [
+    [(255, 0, 0, 255)] * 4,
+    [(0, 255, 0, 255)] * 4,
+    [(0, 0, 255, 255)] * 4,
+    [(255, 255, 255, 128), (255, 255, 255, 128), (0, 0, 0, 0), (0, 0, 0, 0)],
+]
+def bgra_premul(rows):
+    out = bytearray()
+    for row in rows:
+        for r, g, b, a in row:
+            pm = lambda v: (v * a + 127) // 255
+            out += bytes([pm(b), pm(g), pm(r), a])
+    return bytes(out)
+B_PNG = png(4, 4, B_RGBA)
+cbdt_strikes = [
+    (16, 32, 2, 1, 1, small_metrics(4, 4, 1, 4, 6) + bgra_premul(B_RGBA), None),
+    (32, 32, 2, 1, 17, small_metrics(4, 4, 1, 4, 6) + struct.pack('>I', len(B_PNG)) + B_PNG, None),
+]
+cblc, cbdt = build_bitmap_tables(0x00030000, cbdt_strikes)
+
+# sbix: glyph 'c' png at ppem 20, 'd' dupe of 'c'
+NUM_GLYPHS = 6
+C_RGBA = [[(0, 0, 255, 255), (255, 255, 0, 255)], [(255, 255, 0, 255), (0, 0, 255, 255)]]
+C_PNG = png(2, 2, C_RGBA)
+glyph_datas = [b'', b'', b'', struct.pack('>hh', 1, -1) + b'png ' + C_PNG, struct.pack('>hh', 0, 0) + b'dupe' + struct.pack('>H', 3), b'']
+strike = bytearray(struct.pack('>HH', 20, 72))
+offs = []
+pos = 4 + 4 * (NUM_GLYPHS + 1)
+for d in glyph_datas:
+    offs.append(pos); pos += len(d)
+offs.append(pos)
+strike += b''.join(struct.pack('>I', o) for o in offs) + b''.join(glyph_datas)
+sbix = struct.pack('>HHII', 1, 1, 1, 12) + bytes(strike)
+
+head = struct.pack('>IIIIHHqqhhhhHHhhh', 0x00010000, 0x00010000, 0, 0x5F0F3CF5, 0b1011, UPEM,
+                   0, 0, 0, -200, 1000, 800, 0, 8, 2, 0, 0)
+hhea = struct.pack('>IhhhH' + 'h' * 11 + 'H', 0x00010000, 800, -200, 0, 1000, 0, 0, 1000, 1, 0, 0, 0, 0, 0, 0, 0, NUM_GLYPHS)
+maxp = struct.pack('>IH', 0x00005000, NUM_GLYPHS)
+hmtx = b''.join(struct.pack('>Hh', 750, 0) for _ in range(NUM_GLYPHS))
+# cmap format 4: 'a'..'e' -> 1..5
+segs = [(0x61, 0x65, 1 - 0x61), (0xFFFF, 0xFFFF, 1)]
+segx2 = len(segs) * 2
+sub = struct.pack('>HHHHHHH', 4, 0, 0, segx2, 4, 1, 0)
+sub += b''.join(struct.pack('>H', e) for _, e, _ in segs) + b'\0\0'
+sub += b''.join(struct.pack('>H', s) for s, _, _ in segs)
+sub += b''.join(struct.pack('>h', d) for _, _, d in segs)
+sub += b''.join(struct.pack('>H', 0) for _ in segs)
+sub = sub[:2] + struct.pack('>H', len(sub)) + sub[4:]
+cmap = struct.pack('>HHHHI', 0, 1, 3, 1, 12) + sub
+
+tables = {b'CBDT': cbdt, b'CBLC': cblc, b'EBDT': ebdt, b'EBLC': eblc, b'cmap': cmap,
+          b'head': head, b'hhea': hhea, b'hmtx': hmtx, b'maxp': maxp, b'sbix': sbix}
+def checksum(d):
+    d = d + b'\0' * (-len(d) % 4)
+    return sum(struct.unpack('>%dI' % (len(d) // 4), d)) & 0xffffffff
+n = len(tables)
+out = bytearray(struct.pack('>IHHHH', 0x00010000, n, 128, 3, n * 16 - 128))
+off = 12 + 16 * n
+body = bytearray()
+for tag in sorted(tables):
+    d = tables[tag]
+    out += struct.pack('>4sIII', tag, checksum(d), off + len(body), len(d))
+    body += d + b'\0' * (-len(d) % 4)
+path = os.path.join(os.path.dirname(os.path.abspath(__file__)), 'bitmaps.ttf')
+open(path, 'wb').write(bytes(out + body))
@@ -0,0 +1,65 @@
+"""Builds colr_fanout.ttf: a tiny COLRv1 test font with shared paint children, run `python3 colr_fanout.py`.
+
+glyphs: 0 .notdef, 1 PaintColrLayers fan-out graph with 2^32 leaves, each layer shares its child,
+        2 the same graph entered 3 levels from the bottom, 8 leaves.
+Leaves are PaintGlyph(.notdef) -> PaintSolid(foreground, alpha 0.5).
+"""
+import os, struct
+
+UPEM = 1000
+NUM_GLYPHS = 3
+DEPTH = 32
+FAN_OUT = 2
+
+HEADER_LEN = 34
+BASE_GLYPH_LIST = HEADER_LEN
+BASE_GLYPH_LIST_LEN = 4 + 6 * 2
+LAYER_LIST = BASE_GLYPH_LIST + BASE_GLYPH_LIST_LEN
+LAYER_LIST_LEN = 4 + 4 * FAN_OUT * DEPTH
+PAINTS = LAYER_LIST + LAYER_LIST_LEN
+
+# node k < DEPTH: PaintColrLayers of FAN_OUT layers all pointing to node k + 1
+COLR_LAYERS_LEN = 6
+def node(k):
+    return PAINTS + COLR_LAYERS_LEN * k
+LEAF = node(DEPTH)
+SOLID = LEAF + 6
+
+paints = bytearray()
+for k in range(DEPTH):
+    paints += struct.pack('>BBI', 1, FAN_OUT, FAN_OUT * k)
+paints += struct.pack('>B', 10) + (SOLID - LEAF).to_bytes(3, 'big') + struct.pack('>H', 0)
+paints += struct.pack('>BHh', 2, 0xFFFF, 0x2000)
+
+base_glyph_list = struct.pack('>I', 2)
+base_glyph_list += struct.pack('>HI', 1, node(0) - BASE_GLYPH_LIST)
+base_glyph_list += struct.pack('>HI', 2, node(DEPTH - 3) - BASE_GLYPH_LIST)
+
+layer_list = struct.pack('>I', FAN_OUT * DEPTH)
+for k in range(DEPTH):
+    layer_list += struct.pack('>I', node(k + 1) - LAYER_LIST) * FAN_OUT
+
+header = struct.pack('>HHIIHIIIII', 1, 0, 0, 0, 0, BASE_GLYPH_LIST, LAYER_LIST, 0, 0, 0)
+colr = header + base_glyph_list + layer_list + bytes(paints)
+assert len(header) == HEADER_LEN and len(base_glyph_list) == BASE_GLYPH_LIST_LEN
+
+head = struct.pack('>IIIIHHqqhhhhHHhhh', 0x00010000, 0x00010000, 0, 0x5F0F3CF5, 0b1011, UPEM,
+                   0, 0, 0, -200, 1000, 800, 0, 8, 2, 0, 0)
+hhea = struct.pack('>IhhhH' + 'h' * 11 + 'H', 0x00010000, 800, -200, 0, 1000, 0, 0, 1000, 1, 0, 0, 0, 0, 0, 0, 0, NUM_GLYPHS)
+maxp = struct.pack('>IH', 0x00005000, NUM_GLYPHS)
+hmtx = b''.join(struct.pack('>Hh', 750, 0) for _ in range(NUM_GLYPHS))
+
+tables = {b'COLR': colr, b'head': head, b'hhea': hhea, b'hmtx': hmtx, b'maxp': maxp}
+def checksum(d):
+    d = d + b'\0' * (-len(d) % 4)
+    return sum(struct.unpack('>%dI' % (len(d) // 4), d)) & 0xffffffff
+n = len(tables)
+out = bytearray(struct.pack('>IHHHH', 0x00010000, n, 64, 2, n * 16 - 64))
+off = 12 + 16 * n
+body = bytearray()
+for tag in sorted(tables):
+    d = tables[tag]
+    out += struct.pack('>4sIII', tag, checksum(d), off + len(body), len(d))
+    body += d + b'\0' * (-len(d) % 4)
+path = os.path.join(os.path.dirname(os.path.abspath(__file__)), 'colr_fanout.ttf')
+open(path, 'wb').write(bytes(out + body))
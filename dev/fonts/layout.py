@@ -0,0 +1,156 @@
+"""Builds layout.ttf: a tiny test font with GSUB, GPOS & GDEF lookups only, run `python3 layout.py`.
+
+glyphs (advance 500 unless noted):
+    0 .notdef, 1 f, 2 i, 3 f_i (ligature, advance 900), 4 a, 5 acutecomb (mark, advance 200),
+    6 b, 7 b.alt, 8 c, 9 k, 10 unused, 11 x, 12 y, 13 A, 14 V
+
+GSUB (scripts DFLT & latn, latn has language TRK without liga):
+    ss01 single format 1: b -> b.alt
+    ccmp multiple: c -> a a
+    liga ligature: f i -> f_i
+    ss02 single format 2: x -> y
+    ss03 single format 2: k -> 999, a malformed glyph id not in the font
+GPOS:
+    kern pair format 1: A V x_advance -80
+    kern pair format 2: V A x_advance -60, A y_placement 10
+    mark mark-to-base: base a anchor (250, 500), acutecomb anchor (100, 0)
+    curs cursive: k entry (0, 100) exit (500, 200)
+"""
+import os, struct
+
+NUM_GLYPHS = 15
+MISSING = 999
+F, I, FI, A, ACUTE, B, B_ALT, C, K, X, Y, CAP_A, CAP_V = 1, 2, 3, 4, 5, 6, 7, 8, 9, 11, 12, 13, 14
+ADVANCES = {FI: 900, ACUTE: 200}
+CMAP = {'f': F, 'i': I, 'a': A, '\u0301': ACUTE, 'b': B, 'c': C, 'k': K, 'x': X, 'y': Y, 'A': CAP_A, 'V': CAP_V}
+
+
+class T:
+    """A table of fixed size fields, `('O', T)` fields are Offset16s to child tables
+    serialized after the parent."""
+
+    def __init__(self, *fields):
+        self.fields = fields
+
+    def bytes(self):
+        size = sum(2 if f[0] == 'O' else struct.calcsize('>' + f[0]) for f in self.fields)
+        head, tail = b'', b''
+        for kind, value in self.fields:
+            if kind == 'O':
+                if value is None:
+                    head += struct.pack('>H', 0)
+                else:
+                    head += struct.pack('>H', size + len(tail))
+                    tail += value.bytes()
+            else:
+                head += struct.pack('>' + kind, value)
+        return head + tail
+
+
+def H(*values):
+    return [('H', v) for v in values]
+
+
+def coverage(glyphs):
+    return T(*H(1, len(glyphs), *sorted(glyphs)))
+
+
+def class_def(classes):
+    ranges = sorted(classes.items())
+    return T(*H(2, len(ranges)), *[f for g, c in ranges for f in H(g, g, c)])
+
+
+def lookup(kind, *subtables, flag=0):
+    return T(*H(kind, flag, len(subtables)), *[('O', s) for s in subtables])
+
+
+def anchor(x, y):
+    return T(('H', 1), ('h', x), ('h', y))
+
+
+def layout_table(features, lookups):
+    """features: sorted list of (tag, [lookup indices], in_trk)"""
+    all_features = list(range(len(features)))
+    trk_features = [i for i, f in enumerate(features) if f[2]]
+    langsys = lambda idx: T(*H(0, 0xFFFF, len(idx), *idx))
+    dflt = T(('O', langsys(all_features)), ('H', 0))
+    latn = T(('O', langsys(all_features)), ('H', 1), ('4s', b'TRK '), ('O', langsys(trk_features)))
+    # script record offsets are relative to the script list
+    scripts = T(('H', 2), ('4s', b'DFLT'), ('O', dflt), ('4s', b'latn'), ('O', latn))
+    feature_list = T(('H', len(features)),
+                     *[f for tag, idx, _ in features for f in [('4s', tag), ('O', T(*H(0, len(idx), *idx)))]])
+    lookup_list = T(('H', len(lookups)), *[('O', l) for l in lookups])
+    return T(('H', 1), ('H', 0), ('O', scripts), ('O', feature_list), ('O', lookup_list)).bytes()
+
+
+gsub = layout_table(
+    [(b'ccmp', [1], True), (b'liga', [2], False), (b'ss01', [0], True), (b'ss02', [3], True),
+     (b'ss03', [4], True)],
+    [
+        lookup(1, T(('H', 1), ('O', coverage([B])), ('h', B_ALT - B))),
+        lookup(2, T(('H', 1), ('O', coverage([C])), ('H', 1), ('O', T(*H(2, A, A))))),
+        lookup(4, T(('H', 1), ('O', coverage([F])), ('H', 1),
+                    ('O', T(('H', 1), ('O', T(*H(FI, 2, I))))))),
+        lookup(1, T(('H', 2), ('O', coverage([X])), *H(1, Y))),
+        lookup(1, T(('H', 2), ('O', coverage([K])), *H(1, MISSING))),
+    ],
+)
+
+pair_format1 = T(('H', 1), ('O', coverage([CAP_A])), *H(0x4, 0, 1),
+                 ('O', T(('H', 1), ('H', CAP_V), ('h', -80))))
+# class 1 of first glyphs {V}, class 1 of second glyphs {A}, class 0 everything else
+pair_format2 = T(('H', 2), ('O', coverage([CAP_V])), *H(0x4, 0x2),
+                 ('O', class_def({CAP_V: 1})), ('O', class_def({CAP_A: 1})), *H(2, 2),
+                 *[('h', v) for v in [0, 0, 0, 0,  # class1 0
+                                      0, 0, -60, 10]])  # class1 1
+mark_base = T(('H', 1), ('O', coverage([ACUTE])), ('O', coverage([A])), ('H', 1),
+              ('O', T(('H', 1), ('H', 0), ('O', anchor(100, 0)))),
+              ('O', T(('H', 1), ('O', anchor(250, 500)))))
+cursive = T(('H', 1), ('O', coverage([K])), ('H', 1), ('O', anchor(0, 100)), ('O', anchor(500, 200)))
+gpos = layout_table(
+    [(b'curs', [3], True), (b'kern', [0, 1], True), (b'mark', [2], True)],
+    [lookup(2, pair_format1), lookup(2, pair_format2), lookup(4, mark_base), lookup(3, cursive)],
+)
+
+glyph_classes = {g: 1 for g in range(1, NUM_GLYPHS)}
+glyph_classes[FI] = 2
+glyph_classes[ACUTE] = 3
+gdef = T(('H', 1), ('H', 0), ('O', class_def(glyph_classes)), *H(0, 0, 0)).bytes()
+
+head = struct.pack('>IIIIHHqqhhhhHHhhh', 0x00010000, 0x00010000, 0, 0x5F0F3CF5, 0b1011, 1000,
+                   0, 0, 0, -200, 1000, 800, 0, 8, 2, 0, 0)
+hhea = struct.pack('>IhhhH' + 'h' * 11 + 'H', 0x00010000, 800, -200, 0, 1000,
+                   0, 0, 1000, 1, 0, 0, 0, 0, 0, 0, 0, NUM_GLYPHS)
+maxp = struct.pack('>IH', 0x00005000, NUM_GLYPHS)
+hmtx = b''.join(struct.pack('>Hh', ADVANCES.get(g, 500), 0) for g in range(NUM_GLYPHS))
+
+# cmap format 4, a segment per char
+segs = sorted((ord(c), ord(c), g - ord(c)) for c, g in CMAP.items()) + [(0xFFFF, 0xFFFF, 1)]
+segx2 = len(segs) * 2
+sub = struct.pack('>HHHHHHH', 4, 0, 0, segx2, 0, 0, 0)
+sub += b''.join(struct.pack('>H', e) for _, e, _ in segs) + b'\0\0'
+sub += b''.join(struct.pack('>H', s) for s, _, _ in segs)
+sub += b''.join(struct.pack('>H', (d + 0x10000) % 0x10000) for _, _, d in segs)
+sub += b''.join(struct.pack('>H', 0) for _ in segs)
+sub = sub[:2] + struct.pack('>H', len(sub)) + sub[4:]
+cmap = struct.pack('>HHHHI', 0, 1, 3, 1, 12) + sub
+
+tables = {b'GDEF': gdef, b'GPOS': gpos, b'GSUB': gsub, b'cmap': cmap,
+          b'head': head, b'hhea': hhea, b'hmtx': hmtx, b'maxp': maxp}
+
+
+def checksum(d):
+    d = d + b'\0' * (-len(d) % 4)
+    return sum(struct.unpack('>%dI' % (len(d) // 4), d)) & 0xffffffff
+
+
+n = len(tables)
+out = bytearray(struct.pack('>IHHHH', 0x00010000, n, 128, 3, n * 16 - 128))
+off = 12 + 16 * n
+body = bytearray()
+for tag in sorted(tables):
+    d = tables[tag]
+    out += struct.pack('>4sIII', tag, checksum(d), off + len(body), len(d))
+    body += d + b'\0' * (-len(d) % 4)
+path = os.path.join(os.path.dirname(os.path.abspath(__file__)), 'layout.ttf')
+open(path, 'wb').write(bytes(out + body))
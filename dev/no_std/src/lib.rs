@@ -0,0 +1,47 @@
+//! `no_std` usage of ab_glyph, built without the "std" feature.
+#![no_std]
+
+extern crate alloc;
+
+use ab_glyph::{Font, FontRef, FontVec, GlyphIdCache, InvalidFont, ScaleFont};
+use alloc::{vec, vec::Vec};
+
+/// Coverage bitmap of a drawn glyph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coverage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<f32>,
+}
+
+/// Loads a font from borrowed `data` using the given glyph id `cache`.
+pub fn font_ref(data: &[u8], cache: GlyphIdCache) -> Result<FontRef<'_>, InvalidFont> {
+    FontRef::try_from_slice_and_index_with_cache(data, 0, cache)
+}
+
+/// Loads a font from owned `data` using the given glyph id `cache`.
+pub fn font_vec(data: Vec<u8>, cache: GlyphIdCache) -> Result<FontVec, InvalidFont> {
+    FontVec::try_from_vec_and_index_with_cache(data, 0, cache)
+}
+
+/// Outlines & draws `c` at `scale` returning the coverage, or `None` if there is no outline.
+pub fn draw<F: Font>(font: F, c: char, scale: f32) -> Option<Coverage> {
+    let font = font.as_scaled(scale);
+    let glyph = font.scaled_glyph(c);
+    let outlined = font.outline_glyph(glyph)?;
+    let bounds = outlined.px_bounds();
+    let (width, height) = (bounds.width() as usize, bounds.height() as usize);
+
+    let mut pixels = vec![0.0; width * height];
+    outlined.draw(|x, y, c| pixels[y as usize * width + x as usize] = c);
+    Some(Coverage {
+        width,
+        height,
+        pixels,
+    })
+}
+
+/// Sum of the horizontal advances of `text` at `scale`, including kerning.
+pub fn text_width<F: Font>(font: F, text: &str, scale: f32) -> f32 {
+    font.as_scaled(scale).text_width(text)
+}
@@ -0,0 +1,70 @@
+use ab_glyph::{Font, GlyphId, GlyphIdCache};
+use dev_no_std::*;
+
+const EXO2_OTF: &[u8] = include_bytes!("../../fonts/Exo2-Light.otf");
+const EXO2_TTF: &[u8] = include_bytes!("../../fonts/Exo2-Light.ttf");
+const MONO: &[u8] = include_bytes!("../../fonts/DejaVuSansMono.ttf");
+
+fn caches() -> Vec<GlyphIdCache> {
+    vec![
+        GlyphIdCache::default(),
+        GlyphIdCache::Disabled,
+        GlyphIdCache::Chars("aé☣\u{1F600}".into()),
+        GlyphIdCache::Bmp,
+    ]
+}
+
+#[test]
+fn glyph_id_caches() {
+    for data in [EXO2_OTF, EXO2_TTF, MONO] {
+        let uncached = font_ref(data, GlyphIdCache::Disabled).unwrap();
+        let mut chars: Vec<char> = uncached.codepoint_ids().map(|(_, c)| c).collect();
+        chars.extend(['☣', 'א', '\u{1F600}', '\u{10FFFF}']);
+
+        for cache in caches() {
+            let font = font_ref(data, cache.clone()).unwrap();
+            let font_vec = font_vec(data.to_vec(), cache.clone()).unwrap();
+            for &c in &chars {
+                let expected = uncached.try_glyph_id(c);
+                assert_eq!(font.try_glyph_id(c), expected, "{:?} {:?}", cache, c);
+                assert_eq!(font_vec.try_glyph_id(c), expected, "{:?} {:?}", cache, c);
+            }
+            assert_eq!(font.clone().glyph_id('s'), font.glyph_id('s'));
+        }
+    }
+
+    let font = font_ref(EXO2_OTF, GlyphIdCache::default()).unwrap();
+    assert_eq!(font.glyph_id('s'), GlyphId(56));
+    assert_eq!(font.try_glyph_id('☣'), None);
+}
+
+#[test]
+fn outline_and_draw() {
+    for data in [EXO2_OTF, EXO2_TTF, MONO] {
+        let font = font_ref(data, GlyphIdCache::default()).unwrap();
+        assert!(font.outline(font.glyph_id('a')).is_some());
+
+        let a = draw(&font, 'a', 40.0).unwrap();
+        assert!(a.width > 0 && a.height > 0);
+        assert_eq!(a.pixels.len(), a.width * a.height);
+        assert!(a.pixels.iter().all(|&c| (0.0..=1.0).contains(&c)));
+        assert!(a.pixels.iter().any(|&c| c > 0.5));
+
+        // no outline for space
+        assert_eq!(draw(&font, ' ', 40.0), None);
+
+        // same drawing with any cache & owned data
+        for cache in caches() {
+            let font = font_vec(data.to_vec(), cache).unwrap();
+            assert_eq!(draw(&font, 'a', 40.0).as_ref(), Some(&a));
+        }
+    }
+}
+
+#[test]
+fn advances() {
+    let font = font_ref(EXO2_TTF, GlyphIdCache::Bmp).unwrap();
+    let width = text_width(&font, "hello", 30.0);
+    assert!(width > 0.0);
+    assert!(text_width(&font, "hello hello", 30.0) > width * 2.0);
+}
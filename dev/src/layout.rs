@@ -13,7 +13,7 @@ pub fn layout_paragraph<F, SF>(
     F: Font,
     SF: ScaleFont<F>,
 {
-    let v_advance = font.height() + font.line_gap();
+    let v_advance = font.line_height();
     let mut caret = position + point(0.0, font.ascent());
     let mut last_glyph: Option<Glyph> = None;
     for c in text.chars() {
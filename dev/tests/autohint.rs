@@ -0,0 +1,128 @@
+use ab_glyph::*;
+
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+const EXO2_TTF: &[u8] = include_bytes!("../fonts/Exo2-Light.ttf");
+
+/// Returns the coverage of the pixel column at the horizontal center of the glyph.
+fn center_column(outlined: &OutlinedGlyph) -> Vec<f32> {
+    let bounds = outlined.px_bounds();
+    let center = (bounds.width() / 2.0) as u32;
+    let mut column = vec![0.0; bounds.height() as usize];
+    outlined.draw(|x, y, c| {
+        if x == center {
+            column[y as usize] = c;
+        }
+    });
+    column
+}
+
+fn partial_pixels(coverage: &[f32]) -> usize {
+    coverage.iter().filter(|&&c| c > 0.05 && c < 0.95).count()
+}
+
+#[test]
+fn blue_zones_otf() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let hinter = AutoHinter::new(&font);
+
+    let x_top = font.outline(font.glyph_id('x')).unwrap().bounds.min.y;
+    let o_top = font.outline(font.glyph_id('o')).unwrap().bounds.min.y;
+    assert!(x_top < o_top);
+
+    let x_height = hinter
+        .blue_zones
+        .iter()
+        .find(|zone| (zone.reference - x_top).abs() < 5.0)
+        .expect("x-height zone");
+    assert!(x_height.overshoot > x_height.reference);
+    assert!((x_height.overshoot - o_top).abs() < 10.0);
+
+    let baseline = hinter
+        .blue_zones
+        .iter()
+        .find(|zone| zone.reference == 0.0)
+        .expect("baseline zone");
+    assert!(baseline.overshoot < 0.0);
+}
+
+/// Horizontal stems should be snapped to whole pixels, sharpening them.
+#[test]
+fn autohint_horizontal_stems() {
+    for data in [EXO2_OTF, EXO2_TTF] {
+        let font = FontRef::try_from_slice(data).unwrap();
+        let hinter = AutoHinter::new(&font);
+
+        for c in ['H', 'e', 'E'] {
+            let outlined = font
+                .outline_glyph(font.glyph_id(c).with_scale(16.0))
+                .unwrap();
+            let unhinted = partial_pixels(&center_column(&outlined));
+            let hinted = partial_pixels(&center_column(&outlined.autohinted(&hinter)));
+            assert!(unhinted > 0, "{}", c);
+            assert_eq!(hinted, 0, "{} unhinted {}", c, unhinted);
+        }
+    }
+}
+
+/// Round overshoots are suppressed at small sizes so 'o' & 'x' align.
+#[test]
+fn autohint_blue_zones_align() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let hinter = AutoHinter::new(&font);
+    let outline = |c: char| {
+        let glyph = font
+            .glyph_id(c)
+            .with_scale_and_position(16.0, point(0.0, 20.0));
+        font.outline_glyph(glyph).unwrap()
+    };
+
+    let (x, o) = (outline('x'), outline('o'));
+    assert_ne!(x.px_bounds().max.y, o.px_bounds().max.y);
+
+    let (x, o) = (x.autohinted(&hinter), o.autohinted(&hinter));
+    assert_eq!(x.px_bounds().min.y, o.px_bounds().min.y);
+    assert_eq!(x.px_bounds().max.y, 20.0);
+    assert_eq!(o.px_bounds().max.y, 20.0);
+}
+
+/// Only y coordinates change.
+#[test]
+fn autohint_horizontal_metrics_untouched() {
+    let font = FontRef::try_from_slice(EXO2_TTF).unwrap();
+    let hinter = AutoHinter::new(&font);
+    let scale_factor = font.as_scaled(12.0).scale_factor();
+
+    let xs = |outline: &Outline| -> Vec<f32> {
+        outline
+            .curves
+            .iter()
+            .flat_map(|curve| match *curve {
+                OutlineCurve::Line(p0, p1) => vec![p0.x, p1.x],
+                OutlineCurve::Quad(p0, p1, p2) => vec![p0.x, p1.x, p2.x],
+                OutlineCurve::Cubic(p0, p1, p2, p3) => vec![p0.x, p1.x, p2.x, p3.x],
+            })
+            .collect()
+    };
+
+    for c in "Ag&%".chars() {
+        let outline = font.outline(font.glyph_id(c)).unwrap();
+        let hinted = outline.autohint(&hinter, scale_factor, point(0.0, 0.0));
+        assert_eq!(xs(&outline), xs(&hinted));
+        assert_eq!(outline.bounds.min.x, hinted.bounds.min.x);
+        assert_eq!(outline.bounds.max.x, hinted.bounds.max.x);
+    }
+}
+
+/// Stems are aligned to the pixel grid at subpixel positions & without blue zones.
+#[test]
+fn autohint_subpixel_position_default() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let glyph = font
+        .glyph_id('H')
+        .with_scale_and_position(15.0, point(3.2, 10.6));
+    let outlined = font.outline_glyph(glyph).unwrap();
+
+    assert!(partial_pixels(&center_column(&outlined)) > 0);
+    let hinted = outlined.autohinted(&AutoHinter::default());
+    assert_eq!(partial_pixels(&center_column(&hinted)), 0);
+}
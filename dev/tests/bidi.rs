@@ -0,0 +1,139 @@
+use ab_glyph::*;
+
+const MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+
+fn section(text: &str) -> SectionText<'_> {
+    SectionText {
+        text,
+        scale: PxScale::from(20.0),
+        font_id: 0,
+    }
+}
+
+/// Characters of each line in glyph (visual) order.
+fn visual_lines(sections: &[SectionText<'_>], glyphs: &[SectionGlyph]) -> Vec<String> {
+    let mut lines: Vec<(f32, String)> = Vec::new();
+    for g in glyphs {
+        let c = sections[g.section_index].text[g.byte_index..]
+            .chars()
+            .next()
+            .unwrap();
+        match lines.last_mut() {
+            Some((y, line)) if *y == g.glyph.position.y => line.push(c),
+            _ => lines.push((g.glyph.position.y, c.to_string())),
+        }
+    }
+    lines.into_iter().map(|(_, l)| l).collect()
+}
+
+fn layout_visual(layout: Layout, geometry: SectionGeometry, text: &str) -> Vec<String> {
+    let font = FontRef::try_from_slice(MONO).unwrap();
+    let sections = [section(text)];
+    let glyphs = layout.calculate_glyphs(&[font], &geometry, &sections);
+    visual_lines(&sections, &glyphs)
+}
+
+fn visual(text: &str) -> String {
+    layout_visual(Layout::default(), SectionGeometry::default(), text).concat()
+}
+
+#[test]
+fn bidi_reorder() {
+    // right-to-left paragraph
+    assert_eq!(visual("שלום עולם"), "םלוע םולש");
+    assert_eq!(visual("مرحبا بالعالم"), "ملاعلاب ابحرم");
+    // left-to-right paragraph with a right-to-left run
+    assert_eq!(visual("abc אבג def"), "abc גבא def");
+    // right-to-left paragraph with left-to-right & number runs
+    assert_eq!(visual("אבג 123 abc"), "abc 123 גבא");
+    assert_eq!(visual("אבג abc def."), ".abc def גבא");
+    // pure left-to-right is unchanged
+    assert_eq!(visual("hello (world)"), "hello (world)");
+}
+
+#[test]
+fn bidi_paragraph_direction_per_line() {
+    let lines = layout_visual(
+        Layout::default(),
+        SectionGeometry::default(),
+        "abc אבג!\nאבג abc!",
+    );
+    assert_eq!(lines, ["abc גבא!", "!abc גבא"]);
+}
+
+/// Brackets in right-to-left runs use mirrored glyphs.
+#[test]
+fn bidi_mirror_brackets() {
+    let font = FontRef::try_from_slice(MONO).unwrap();
+    let sections = [section("א(ב) [x]")];
+    let glyphs =
+        Layout::default().calculate_glyphs(&[&font], &SectionGeometry::default(), &sections);
+
+    // the brackets are in the right-to-left paragraph's direction
+    assert_eq!(visual_lines(&sections, &glyphs), ["]x[ )ב(א"]);
+    let ids: Vec<_> = glyphs.iter().map(|g| g.glyph.id).collect();
+    let expected: Vec<_> = "[x] (ב)א".chars().map(|c| font.glyph_id(c)).collect();
+    assert_eq!(ids, expected);
+}
+
+/// Visually ordered glyphs are positioned left to right by advance.
+#[test]
+fn bidi_positions() {
+    let font = FontRef::try_from_slice(MONO).unwrap();
+    let advance = font.as_scaled(20.0).h_advance(font.glyph_id('a'));
+    let sections = [section("abc אבג 12 def")];
+    let geometry = SectionGeometry {
+        screen_position: point(10.0, 0.0),
+        ..SectionGeometry::default()
+    };
+    let glyphs = Layout::default().calculate_glyphs(&[&font], &geometry, &sections);
+    for (idx, g) in glyphs.iter().enumerate() {
+        assert!(
+            (g.glyph.position.x - (10.0 + idx as f32 * advance)).abs() < 1e-3,
+            "{:?}",
+            g
+        );
+    }
+}
+
+/// Right-to-left paragraphs wrap in logical order with trailing whitespace hanging left.
+#[test]
+fn bidi_wrap() {
+    let font = FontRef::try_from_slice(MONO).unwrap();
+    let advance = font.as_scaled(20.0).h_advance(font.glyph_id('a'));
+    let text = "אחת שתיים שלוש ארבע";
+    let sections = [section(text)];
+    let geometry = SectionGeometry {
+        screen_position: point(500.0, 0.0),
+        bounds: (advance * 10.5, f32::INFINITY),
+    };
+    let layout = Layout {
+        h_align: HorizontalAlign::Right,
+        ..Layout::default()
+    };
+    let glyphs = layout.calculate_glyphs(&[&font], &geometry, &sections);
+    assert_eq!(
+        visual_lines(&sections, &glyphs),
+        [" םייתש תחא", "עברא שולש"]
+    );
+
+    // visible glyphs end at the position
+    for last in [&glyphs[9], &glyphs[18]] {
+        assert!((last.glyph.position.x + advance - 500.0).abs() < 1e-3);
+    }
+    // the trailing space hangs left
+    assert_eq!(&text[glyphs[0].byte_index..][..1], " ");
+    assert!((glyphs[0].glyph.position.x - (500.0 - 10.0 * advance)).abs() < 1e-3);
+    assert!((glyphs[10].glyph.position.x - (500.0 - 9.0 * advance)).abs() < 1e-3);
+}
+
+#[test]
+fn bidi_across_sections() {
+    let font = FontRef::try_from_slice(MONO).unwrap();
+    let sections = [section("abc אב"), section("גד "), section("efg")];
+    let glyphs =
+        Layout::default().calculate_glyphs(&[&font], &SectionGeometry::default(), &sections);
+    assert_eq!(visual_lines(&sections, &glyphs), ["abc דגבא efg"]);
+    let indices: Vec<_> = glyphs.iter().map(|g| g.section_index).collect();
+    assert_eq!(indices, [0, 0, 0, 0, 1, 1, 0, 0, 1, 2, 2, 2]);
+}
@@ -0,0 +1,204 @@
+use ab_glyph::*;
+
+const BITMAPS: &[u8] = include_bytes!("../fonts/bitmaps.ttf");
+const BITMAP_OTB: &[u8] = include_bytes!("../fonts/bitmap.otb");
+
+/// Coverage of the 5x4 'a' bitmap in bitmaps.ttf.
+const A: [[f32; 5]; 4] = [
+    [0.0, 1.0, 1.0, 1.0, 0.0],
+    [1.0, 0.6, 0.0, 0.6, 1.0],
+    [1.0, 0.0, 0.0, 0.25, 1.0],
+    [0.0, 1.0, 1.0, 0.75, 1.0],
+];
+
+/// Expected alpha of 'a' quantized to `depth` bits per pixel.
+fn expected_a_alpha(depth: u32) -> Vec<u8> {
+    let max = ((1 << depth) - 1) as f32;
+    A.iter()
+        .flatten()
+        .map(|v| ((v * max).round() * 255.0 / max) as u8)
+        .collect()
+}
+
+#[test]
+fn ebdt_bitmap_formats() {
+    let font = FontRef::try_from_slice(BITMAPS).unwrap();
+    let a = font.glyph_id('a');
+
+    for (ppem, format, depth) in [
+        (8, GlyphImageFormat::BitmapMono, 1),
+        (9, GlyphImageFormat::BitmapMonoPacked, 1),
+        (10, GlyphImageFormat::BitmapGray2, 2),
+        (11, GlyphImageFormat::BitmapGray2Packed, 2),
+        (12, GlyphImageFormat::BitmapGray4, 4),
+        (13, GlyphImageFormat::BitmapGray4Packed, 4),
+    ] {
+        let image = font.glyph_raster_image(a, ppem).unwrap();
+        assert_eq!(image.format, format, "ppem {}", ppem);
+        assert_eq!(image.scale, f32::from(ppem));
+        assert_eq!((image.width, image.height), (5, 4));
+
+        let decoded = image.decode().unwrap();
+        assert_eq!((decoded.width, decoded.height), (5, 4));
+        assert_eq!(decoded.alpha(), expected_a_alpha(depth), "{:?}", format);
+        assert!(decoded.rgba.chunks(4).all(|px| px[..3] == [0, 0, 0]));
+    }
+}
+
+#[test]
+fn ebdt_origin() {
+    let font = FontRef::try_from_slice(BITMAPS).unwrap();
+    let a = font.glyph_id('a');
+
+    // small metrics, bearing y 4 -> bottom on the baseline
+    let image = font.glyph_raster_image(a, 8).unwrap();
+    assert_eq!(image.origin, point(0.0, 0.0));
+    assert_eq!(image.decode().unwrap().origin, point(0.0, -4.0));
+
+    // big metrics, bearing x 1
+    let image = font.glyph_raster_image(a, 10).unwrap();
+    assert_eq!(image.origin, point(1.0, 0.0));
+
+    // shared metrics from the index subtable, bearing y 3 -> 1px below the baseline
+    let image = font.glyph_raster_image(a, 11).unwrap();
+    assert_eq!(image.origin, point(0.0, -1.0));
+    assert_eq!(image.decode().unwrap().origin, point(0.0, -3.0));
+}
+
+#[test]
+fn bitmap_strike_selection() {
+    let font = FontRef::try_from_slice(BITMAPS).unwrap();
+    let a = font.glyph_id('a');
+
+    let strike = |size| font.glyph_raster_image(a, size).unwrap().scale;
+    // smallest strike at least as large as requested
+    assert_eq!(strike(1), 8.0);
+    assert_eq!(strike(11), 11.0);
+    // otherwise the largest
+    assert_eq!(strike(100), 14.0);
+    assert_eq!(strike(u16::MAX), 14.0);
+
+    // glyphs without images
+    assert!(font.glyph_raster_image(GlyphId(0), 8).is_none());
+}
+
+#[test]
+fn ebdt_otb_gray8() {
+    let font = FontRef::try_from_slice(BITMAP_OTB).unwrap();
+    const W: u8 = 0;
+    const B: u8 = 255;
+
+    let image = font.glyph_raster_image(font.glyph_id('"'), 1).unwrap();
+    assert_eq!(image.format, GlyphImageFormat::BitmapGray8);
+    assert_eq!(image.origin, point(1.0, 4.0));
+    assert_eq!((image.width, image.height, image.scale), (3, 2, 8.0));
+
+    let decoded = image.decode().unwrap();
+    assert_eq!(decoded.origin, point(1.0, -6.0));
+    #[rustfmt::skip]
+    assert_eq!(decoded.alpha(), [
+        B, W, B,
+        B, W, B,
+    ]);
+}
+
+#[test]
+fn cbdt_bgra() {
+    let font = FontRef::try_from_slice(BITMAPS).unwrap();
+    let image = font.glyph_raster_image(font.glyph_id('b'), 16).unwrap();
+    assert_eq!(image.format, GlyphImageFormat::BitmapPremulBgra32);
+    assert_eq!(image.origin, point(1.0, 0.0));
+
+    let decoded = image.decode().unwrap();
+    assert_eq!((decoded.width, decoded.height), (4, 4));
+    let px = |x: usize, y: usize| &decoded.rgba[(y * 4 + x) * 4..][..4];
+    assert_eq!(px(0, 0), [255, 0, 0, 255]);
+    assert_eq!(px(3, 1), [0, 255, 0, 255]);
+    assert_eq!(px(2, 2), [0, 0, 255, 255]);
+    assert_eq!(px(0, 3), [255, 255, 255, 128]);
+    assert_eq!(px(3, 3), [0, 0, 0, 0]);
+}
+
+#[test]
+fn cbdt_png_matches_bgra() {
+    let font = FontRef::try_from_slice(BITMAPS).unwrap();
+    let b = font.glyph_id('b');
+
+    let png = font.glyph_raster_image(b, 32).unwrap();
+    assert_eq!(png.format, GlyphImageFormat::Png);
+    assert_eq!((png.width, png.height), (4, 4));
+
+    let bgra = font.glyph_raster_image(b, 16).unwrap();
+    assert_eq!(png.decode().unwrap().rgba, bgra.decode().unwrap().rgba);
+}
+
+#[test]
+fn sbix_png_and_dupe() {
+    let font = FontRef::try_from_slice(BITMAPS).unwrap();
+
+    let c = font.glyph_raster_image(font.glyph_id('c'), 20).unwrap();
+    assert_eq!(c.format, GlyphImageFormat::Png);
+    assert_eq!(c.origin, point(1.0, -1.0));
+    assert_eq!((c.width, c.height, c.scale), (2, 2, 20.0));
+
+    let decoded = c.decode().unwrap();
+    assert_eq!(decoded.origin, point(1.0, -1.0));
+    #[rustfmt::skip]
+    assert_eq!(decoded.rgba, [
+        0, 0, 255, 255,   255, 255, 0, 255,
+        255, 255, 0, 255, 0, 0, 255, 255,
+    ]);
+
+    // 'd' is a "dupe" of 'c'
+    let d = font.glyph_raster_image(font.glyph_id('d'), 20).unwrap();
+    assert_eq!(d.data, c.data);
+}
+
+#[test]
+fn decode_glyph_image_scaled() {
+    let font = FontRef::try_from_slice(BITMAPS).unwrap();
+    let b = font.glyph_id('b');
+
+    // 16ppem 4x4 image drawn at 8ppem
+    let image = font.decode_glyph_image(b, 8.0).unwrap();
+    assert_eq!((image.width, image.height), (2, 2));
+    assert_eq!(image.scale, 8.0);
+    assert_eq!(image.origin, point(0.5, -2.0));
+    // top row is mostly an average of red & green rows, with some blue from the next
+    assert_eq!(&image.rgba[..4], [109, 109, 36, 255]);
+
+    // same result using a ScaleFont
+    assert_eq!(font.as_scaled(8.0).decode_glyph_image(b), Some(image));
+
+    // non-uniform upscaling of 'a' 14ppem to 28x14ppem
+    let image = font
+        .decode_glyph_image(font.glyph_id('a'), PxScale { x: 28.0, y: 14.0 })
+        .unwrap();
+    assert_eq!((image.width, image.height), (20, 8));
+    // fully opaque & transparent areas remain so
+    let alpha = image.alpha();
+    assert_eq!(alpha[0], 0);
+    assert_eq!(alpha[20 * 3 + 19], 255);
+}
+
+/// Zero size bitmaps are valid, e.g. for whitespace glyphs.
+#[test]
+fn zero_size_bitmap() {
+    let font = FontRef::try_from_slice(BITMAPS).unwrap();
+    let e = font.glyph_id('e');
+
+    let image = font.glyph_raster_image(e, 15).unwrap();
+    assert_eq!((image.width, image.height), (0, 0));
+    let decoded = image.decode().unwrap();
+    assert_eq!((decoded.width, decoded.height), (0, 0));
+    assert!(decoded.rgba.is_empty());
+
+    // resizing keeps the image empty
+    let resized = decoded.resized(2.0, 2.0);
+    assert_eq!((resized.width, resized.height), (0, 0));
+    assert!(resized.rgba.is_empty());
+    assert_eq!(resized.scale, 30.0);
+
+    let image = font.decode_glyph_image(e, 30.0).unwrap();
+    assert_eq!((image.width, image.height), (0, 0));
+}
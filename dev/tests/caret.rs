@@ -0,0 +1,210 @@
+use ab_glyph::*;
+
+const MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+
+fn layout_text(layout: Layout, geometry: SectionGeometry, text: &str) -> (LaidOutText, f32) {
+    let font = FontRef::try_from_slice(MONO).unwrap();
+    let scale = PxScale::from(20.0);
+    let advance = font.as_scaled(scale).h_advance(font.glyph_id('a'));
+    let sections = [SectionText {
+        text,
+        scale,
+        font_id: 0,
+    }];
+    (
+        layout.calculate_text(&[font], &geometry, &sections),
+        advance,
+    )
+}
+
+fn laid_out(text: &str) -> (LaidOutText, f32) {
+    layout_text(Layout::default(), SectionGeometry::default(), text)
+}
+
+fn caret_x(text: &LaidOutText, index: usize) -> f32 {
+    text.caret(index).unwrap().min.x
+}
+
+#[test]
+fn caret_ltr() {
+    let (text, advance) = laid_out("abc");
+    for idx in 0..=3 {
+        let caret = text.caret(idx).unwrap();
+        assert!(
+            (caret.min.x - idx as f32 * advance).abs() < 1e-3,
+            "caret {} at {:?}",
+            idx,
+            caret
+        );
+        assert_eq!(caret.min.x, caret.max.x);
+        assert!(caret.height() > 0.0);
+    }
+    assert_eq!(text.caret(4), None);
+
+    // not a char boundary
+    let (text, _) = laid_out("aé");
+    assert!(text.caret(1).is_some());
+    assert_eq!(text.caret(2), None);
+    assert!(text.caret(3).is_some());
+}
+
+#[test]
+fn caret_multiline() {
+    let (text, advance) = laid_out("ab\ncd\n");
+    let first = text.caret(0).unwrap();
+    let line_break = text.caret(2).unwrap();
+    let second = text.caret(3).unwrap();
+    let last = text.caret(6).unwrap();
+
+    assert_eq!(line_break.min.y, first.min.y);
+    assert!((line_break.min.x - 2.0 * advance).abs() < 1e-3);
+    assert_eq!(second.min.x, 0.0);
+    assert!(second.min.y > first.min.y);
+    // trailing hard break caret is at the start of an empty line
+    assert_eq!(last.min.x, 0.0);
+    assert!(last.min.y > second.min.y);
+
+    // soft wrap boundary is at the start of the next line
+    let geometry = SectionGeometry {
+        bounds: (advance * 5.5, f32::INFINITY),
+        ..<_>::default()
+    };
+    let (text, _) = layout_text(Layout::default(), geometry, "abcd efgh");
+    let wrap = text.caret(5).unwrap();
+    assert_eq!(wrap.min.x, 0.0);
+    assert!(wrap.min.y > text.caret(0).unwrap().min.y);
+}
+
+#[test]
+fn hit_test_nearest_boundary() {
+    let (text, advance) = laid_out("abc\ndef");
+    let line_height = text.caret(4).unwrap().min.y - text.caret(0).unwrap().min.y;
+
+    assert_eq!(text.hit_test(point(0.4 * advance, 1.0)), 0);
+    assert_eq!(text.hit_test(point(0.6 * advance, 1.0)), 1);
+    // beyond the line end, before the line break
+    assert_eq!(text.hit_test(point(100.0 * advance, 1.0)), 3);
+    assert_eq!(text.hit_test(point(-5.0, 1.0)), 0);
+    // second line
+    assert_eq!(text.hit_test(point(1.2 * advance, line_height + 1.0)), 5);
+    // clamped to the first & last lines
+    assert_eq!(text.hit_test(point(1.2 * advance, -100.0)), 1);
+    assert_eq!(
+        text.hit_test(point(100.0 * advance, 100.0 * line_height)),
+        7
+    );
+
+    // round trip of every boundary
+    for idx in 0..=text.text().len() {
+        let caret = text.caret(idx).unwrap();
+        let hit = text.hit_test(point(caret.min.x, caret.min.y + 1.0));
+        if idx != 3 {
+            assert_eq!(hit, idx);
+        }
+    }
+
+    let (empty, _) = laid_out("");
+    assert_eq!(empty.hit_test(point(10.0, 10.0)), 0);
+}
+
+#[test]
+fn hit_test_grapheme_clusters() {
+    // "e" + combining acute accent is a single grapheme cluster
+    let (text, advance) = laid_out("ae\u{301}b");
+    let hits: Vec<_> = (0..40)
+        .map(|n| text.hit_test(point(n as f32 * advance / 10.0, 1.0)))
+        .collect();
+    assert!(hits.contains(&1));
+    assert!(hits.contains(&4));
+    assert!(!hits.contains(&2), "hit inside a grapheme cluster");
+
+    // the caret is still available at the char boundary
+    assert!(text.caret(2).is_some());
+}
+
+#[test]
+fn hit_test_wrapped_line_end() {
+    let font = FontRef::try_from_slice(MONO).unwrap();
+    let advance = font.as_scaled(20.0).h_advance(font.glyph_id('a'));
+    let geometry = SectionGeometry {
+        bounds: (advance * 5.5, f32::INFINITY),
+        ..<_>::default()
+    };
+    let (text, _) = layout_text(Layout::default(), geometry, "abcd efgh");
+    let first_line_y = text.caret(0).unwrap().min.y + 1.0;
+    // the end of the first line isn't the start of the second
+    assert_eq!(text.hit_test(point(100.0 * advance, first_line_y)), 4);
+}
+
+#[test]
+fn caret_rtl() {
+    let (text, advance) = laid_out("אבג");
+    let len = text.text().len();
+    // right-to-left carets move leftwards
+    let xs: Vec<_> = text
+        .text()
+        .char_indices()
+        .map(|(idx, _)| idx)
+        .chain(Some(len))
+        .map(|idx| caret_x(&text, idx))
+        .collect();
+    assert!(xs.windows(2).all(|w| w[1] < w[0]), "{:?}", xs);
+    assert!((xs[0] - 3.0 * advance).abs() < 1e-3);
+    assert!(xs[3].abs() < 1e-3);
+
+    // clicking the left edge of the first visual glyph is the text end
+    assert_eq!(text.hit_test(point(0.0, 1.0)), len);
+    assert_eq!(text.hit_test(point(3.0 * advance, 1.0)), 0);
+}
+
+#[test]
+fn selection_rects() {
+    let (text, advance) = laid_out("abc\ndef");
+    assert!(text.selection_rects(1..1).is_empty());
+
+    let single = text.selection_rects(1..3);
+    assert_eq!(single.len(), 1);
+    assert!((single[0].min.x - advance).abs() < 1e-3);
+    assert!((single[0].width() - 2.0 * advance).abs() < 1e-3);
+
+    let multi = text.selection_rects(1..6);
+    assert_eq!(multi.len(), 2);
+    assert!((multi[0].min.x - advance).abs() < 1e-3);
+    assert_eq!(multi[1].min.x, 0.0);
+    assert!((multi[1].width() - 2.0 * advance).abs() < 1e-3);
+    // consecutive lines are continuous
+    assert_eq!(multi[0].max.y, multi[1].min.y);
+}
+
+#[test]
+fn selection_rects_bidi() {
+    // visually "abc גבא def"
+    let (text, advance) = laid_out("abc אבג def");
+    // "c א" is split visually
+    let rects = text.selection_rects(2..6);
+    assert_eq!(rects.len(), 2, "{:?}", rects);
+    assert!((rects[0].min.x - 2.0 * advance).abs() < 1e-3);
+    assert!((rects[0].width() - 2.0 * advance).abs() < 1e-3);
+    assert!((rects[1].min.x - 6.0 * advance).abs() < 1e-3);
+    assert!((rects[1].width() - advance).abs() < 1e-3);
+}
+
+#[test]
+fn caret_justify() {
+    let font = FontRef::try_from_slice(MONO).unwrap();
+    let advance = font.as_scaled(20.0).h_advance(font.glyph_id('a'));
+    let width = advance * 10.0;
+    let layout = Layout {
+        h_align: HorizontalAlign::Justify,
+        ..<_>::default()
+    };
+    let geometry = SectionGeometry {
+        bounds: (width, f32::INFINITY),
+        ..<_>::default()
+    };
+    let (text, _) = layout_text(layout, geometry, "ab cd efghijkl");
+    // the expanded space is selectable & "cd" ends at the line width
+    let space = text.caret(3).unwrap().min.x - text.caret(2).unwrap().min.x;
+    assert!(space > advance * 1.5, "space {}", space);
+    assert!((caret_x(&text, 5) - width).abs() < 1e-3);
+}
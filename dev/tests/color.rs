@@ -0,0 +1,168 @@
+use ab_glyph::*;
+use image::{Rgba, RgbaImage};
+use std::{env, io::Cursor, path::PathBuf};
+
+const COLR_1: &[u8] = include_bytes!("../fonts/colr_1.ttf");
+const COLR_FANOUT: &[u8] = include_bytes!("../fonts/colr_fanout.ttf");
+
+/// Return target directory accounting for env var `CARGO_TARGET_DIR`.
+fn temp_path(name: impl AsRef<std::path::Path>) -> PathBuf {
+    let mut path = env::var("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("../target"));
+
+    path.push(name);
+    path
+}
+
+fn color_draw(id: u16) -> RgbaImage {
+    let font = FontRef::try_from_slice(COLR_1).unwrap();
+    let glyph = GlyphId(id).with_scale_and_position(100.0, point(0.0, 80.0));
+    let colored = font.outline_color_glyph(glyph, 0).unwrap();
+
+    let bounds = colored.px_bounds();
+    let mut image = RgbaImage::new(bounds.width() as u32, bounds.height() as u32);
+    colored.draw(Color::new(0, 0, 0, 255), |x, y, [r, g, b, a]| {
+        let unmultiply = |v: f32| match a {
+            a if a > 0.0 => (v / a * 255.0).round() as u8,
+            _ => 0,
+        };
+        image.put_pixel(
+            x,
+            y,
+            Rgba([
+                unmultiply(r),
+                unmultiply(g),
+                unmultiply(b),
+                (a * 255.0).round() as u8,
+            ]),
+        );
+    });
+    image
+}
+
+fn compare_image(new_image: &RgbaImage, reference_bytes: &[u8]) {
+    let reference = image::load(Cursor::new(reference_bytes), image::ImageFormat::Png)
+        .expect("!image::load")
+        .to_rgba8();
+
+    assert_eq!(reference.dimensions(), new_image.dimensions());
+
+    for (x, y, pixel) in reference.enumerate_pixels() {
+        assert_eq!(
+            pixel,
+            new_image.get_pixel(x, y),
+            "unexpected colour difference at ({}, {})",
+            x,
+            y
+        );
+    }
+}
+
+#[test]
+fn colr_v0_layers() {
+    let font = FontRef::try_from_slice(COLR_1).unwrap();
+
+    match font.color_glyph(GlyphId(168), 0) {
+        Some(ColorGlyph::Layers(layers)) => {
+            assert_eq!(layers.len(), 8);
+            assert_eq!(layers[0].id, GlyphId(176));
+            assert!(layers.iter().all(|l| l.color.is_some()));
+        }
+        g => panic!("unexpected color glyph {:?}", g),
+    }
+
+    // plain outline glyphs have no color data
+    assert!(font.color_glyph(GlyphId(2), 0).is_none());
+    // cyclic paint graphs are rejected
+    assert!(font.color_glyph(GlyphId(179), 0).is_none());
+}
+
+/// Paint graphs sharing children expand exponentially, these are rejected.
+#[test]
+fn colr_v1_fan_out_rejected() {
+    let font = FontRef::try_from_slice(COLR_FANOUT).unwrap();
+
+    // 2^32 leaves
+    assert!(font.color_glyph(GlyphId(1), 0).is_none());
+    let glyph = GlyphId(1).with_scale(100.0);
+    assert!(font.outline_color_glyph(glyph, 0).is_none());
+
+    // a small graph sharing the same tables is fine
+    match font.color_glyph(GlyphId(2), 0) {
+        Some(ColorGlyph::Paint {
+            paint: Paint::Layers(layers),
+            ..
+        }) => assert_eq!(layers.len(), 2),
+        g => panic!("unexpected color glyph {:?}", g),
+    }
+}
+
+#[test]
+fn colr_v1_linear_gradient() {
+    let font = FontRef::try_from_slice(COLR_1).unwrap();
+
+    match font.color_glyph(GlyphId(9), 0) {
+        Some(ColorGlyph::Paint {
+            paint: Paint::Glyph { id, paint },
+            clip_box,
+        }) => {
+            assert_eq!(id, GlyphId(9));
+            assert_eq!(
+                clip_box,
+                Some(Rect {
+                    min: point(100.0, 950.0),
+                    max: point(900.0, 250.0),
+                })
+            );
+            match *paint {
+                Paint::LinearGradient {
+                    color_line, p0, p1, ..
+                } => {
+                    assert_eq!(color_line.extend, Extend::Repeat);
+                    assert_eq!(color_line.stops.len(), 2);
+                    assert_eq!(color_line.stops[0].color, Some(Color::new(255, 0, 0, 255)));
+                    assert_eq!(p0, point(100.0, 250.0));
+                    assert_eq!(p1, point(900.0, 250.0));
+                }
+                p => panic!("unexpected paint {:?}", p),
+            }
+        }
+        g => panic!("unexpected color glyph {:?}", g),
+    }
+}
+
+#[test]
+fn reference_color_draw_v0_layers() {
+    let new_image = color_draw(168);
+    new_image.save(temp_path("new_colr_168.png")).unwrap();
+    compare_image(&new_image, include_bytes!("reference_colr_168.png"));
+}
+
+#[test]
+fn reference_color_draw_linear_gradient() {
+    let new_image = color_draw(9);
+    new_image.save(temp_path("new_colr_9.png")).unwrap();
+    compare_image(&new_image, include_bytes!("reference_colr_9.png"));
+}
+
+#[test]
+fn reference_color_draw_radial_gradient() {
+    let new_image = color_draw(93);
+    new_image.save(temp_path("new_colr_93.png")).unwrap();
+    compare_image(&new_image, include_bytes!("reference_colr_93.png"));
+}
+
+#[test]
+fn reference_color_draw_sweep_gradient() {
+    let new_image = color_draw(13);
+    new_image.save(temp_path("new_colr_13.png")).unwrap();
+    compare_image(&new_image, include_bytes!("reference_colr_13.png"));
+}
+
+#[test]
+fn reference_color_draw_composite() {
+    let new_image = color_draw(131);
+    new_image.save(temp_path("new_colr_131.png")).unwrap();
+    compare_image(&new_image, include_bytes!("reference_colr_131.png"));
+}
@@ -0,0 +1,129 @@
+use ab_glyph::*;
+
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+const EXO2_TTF: &[u8] = include_bytes!("../fonts/Exo2-Light.ttf");
+
+/// Signed areas of each contour's control polygon.
+fn contour_areas(outline: &Outline) -> Vec<f32> {
+    let mut areas = Vec::new();
+    let mut start = None;
+    for curve in &outline.curves {
+        let points = match *curve {
+            OutlineCurve::Line(p0, p1) => vec![p0, p1],
+            OutlineCurve::Quad(p0, p1, p2) => vec![p0, p1, p2],
+            OutlineCurve::Cubic(p0, p1, p2, p3) => vec![p0, p1, p2, p3],
+        };
+        if start.is_none() {
+            start = Some(points[0]);
+            areas.push(0.0);
+        }
+        *areas.last_mut().unwrap() += points
+            .windows(2)
+            .map(|w| w[0].x * w[1].y - w[1].x * w[0].y)
+            .sum::<f32>();
+        if Some(points[points.len() - 1]) == start {
+            start = None;
+        }
+    }
+    areas
+}
+
+fn coverage_area(outlined: &OutlinedGlyph) -> f32 {
+    let mut area = 0.0;
+    outlined.draw(|_, _, c| area += c.min(1.0));
+    area
+}
+
+/// Contours keep their direction & the glyph grows by the strength for both TrueType
+/// (clockwise) & CFF (counter-clockwise) outlines.
+#[test]
+fn embolden_preserves_direction() {
+    for data in [EXO2_TTF, EXO2_OTF] {
+        let font = FontRef::try_from_slice(data).unwrap();
+        for c in "IoB@e".chars() {
+            let outline = font.outline(font.glyph_id(c)).unwrap();
+            let bold = outline.embolden(30.0, 20.0);
+
+            let (areas, bold_areas) = (contour_areas(&outline), contour_areas(&bold));
+            assert_eq!(areas.len(), bold_areas.len(), "{}", c);
+            for (a, b) in areas.iter().zip(&bold_areas) {
+                assert_eq!(a.signum(), b.signum(), "{}", c);
+            }
+
+            assert!(bold.bounds.width() >= outline.bounds.width() + 30.0 - 0.01);
+            assert!(bold.bounds.height() <= outline.bounds.height() - 20.0 + 0.01);
+
+            let scale_factor = font.as_scaled(40.0).scale_factor();
+            let area = |outline: Outline| {
+                let glyph = font.glyph_id(c).with_scale(40.0);
+                coverage_area(&OutlinedGlyph::new(glyph, outline, scale_factor))
+            };
+            let (bold_area, area, thin_area) = (
+                area(bold),
+                area(outline.clone()),
+                area(outline.embolden(-10.0, -10.0)),
+            );
+            assert!(bold_area > area, "{}: {} > {}", c, bold_area, area);
+            assert!(thin_area < area, "{}: {} < {}", c, thin_area, area);
+        }
+    }
+}
+
+/// Straight stems thicken by the strength exactly.
+#[test]
+fn embolden_stem_width() {
+    let font = FontRef::try_from_slice(EXO2_TTF).unwrap();
+    let outline = font.outline(font.glyph_id('I')).unwrap();
+    let bold = outline.embolden(24.0, 12.0);
+
+    assert_eq!(bold.bounds.min.x, outline.bounds.min.x);
+    assert_eq!(bold.bounds.max.x, outline.bounds.max.x + 24.0);
+    assert_eq!(bold.bounds.min.y, outline.bounds.min.y + 12.0);
+    assert_eq!(bold.bounds.max.y, outline.bounds.max.y);
+
+    let unchanged = outline.embolden(0.0, 0.0);
+    assert_eq!(
+        format!("{:?}", unchanged.curves),
+        format!("{:?}", outline.curves)
+    );
+}
+
+/// Generic text width measure, as used by layout code.
+fn text_width<F: Font, SF: ScaleFont<F>>(font: SF, text: &str) -> f32 {
+    text.chars().map(|c| font.h_advance(font.glyph_id(c))).sum()
+}
+
+#[test]
+fn emboldened_scale_font() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let strength = font.units_per_em().unwrap() / 24.0;
+    let scaled = font.as_scaled(30.0);
+    let bold = scaled.embolden(strength, strength);
+
+    let extra = strength * scaled.h_scale_factor();
+    let text = "Hello";
+    let width = text_width(scaled, text);
+    let bold_ref: &EmboldenedPxScaleFont<_> = &bold;
+    let bold_width = text_width(bold_ref, text);
+    assert!((bold_width - width - 5.0 * extra).abs() < 1e-3);
+
+    let glyph = bold.scaled_glyph('H');
+    let (bounds, bold_bounds) = (scaled.glyph_bounds(&glyph), bold.glyph_bounds(&glyph));
+    assert!((bold_bounds.width() - bounds.width() - extra).abs() < 1e-4);
+    assert_eq!(bold_bounds.height(), bounds.height());
+
+    let outlined = scaled.outline_glyph(glyph.clone()).unwrap();
+    let bold_outlined = bold.outline_glyph(glyph).unwrap();
+    assert!(bold_outlined.px_bounds().width() > outlined.px_bounds().width());
+    assert!(coverage_area(&bold_outlined) > coverage_area(&outlined));
+
+    let bold = bold.with_scale(60.0);
+    assert_eq!(bold.height(), 60.0);
+    assert!(
+        (bold.h_advance(bold.glyph_id('H'))
+            - 2.0 * scaled.h_advance(bold.glyph_id('H'))
+            - 2.0 * extra)
+            .abs()
+            < 1e-3
+    );
+}
@@ -0,0 +1,118 @@
+use ab_glyph::*;
+
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+const MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+
+fn exo_then_mono() -> FontFallback {
+    let exo = FontArc::try_from_slice(EXO2_OTF).unwrap();
+    let mono = FontArc::try_from_slice(MONO).unwrap();
+    FontFallback::new(exo).with_fallback(mono)
+}
+
+#[test]
+fn resolve_first_covering_font() {
+    let fonts = exo_then_mono();
+    let exo = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let mono = FontRef::try_from_slice(MONO).unwrap();
+
+    assert_eq!(fonts.resolve('a'), (0, exo.glyph_id('a')));
+    assert_eq!(fonts.resolve('→'), (1, mono.glyph_id('→')));
+    // cached resolutions are the same
+    assert_eq!(fonts.resolve('→'), (1, mono.glyph_id('→')));
+    // not in any font
+    assert_eq!(fonts.resolve('\u{20DD}'), (0, GlyphId(0)));
+}
+
+#[test]
+fn push_resolves_missing_chars() {
+    let mut fonts = FontFallback::new(FontArc::try_from_slice(EXO2_OTF).unwrap());
+    assert_eq!(fonts.resolve('☣'), (0, GlyphId(0)));
+
+    let idx = fonts.push(FontArc::try_from_slice(MONO).unwrap());
+    assert_eq!(idx, 1);
+    assert_eq!(fonts.fonts().len(), 2);
+    let (font, id) = fonts.resolve('☣');
+    assert_eq!(font, 1);
+    assert_ne!(id, GlyphId(0));
+}
+
+#[test]
+fn resolve_cluster_keeps_marks_with_base() {
+    let fonts = exo_then_mono();
+    // U+0301 is in both fonts
+    assert_eq!(fonts.resolve_cluster("e\u{301}"), 0);
+    // U+0336 is only in the fallback, so the base uses it too
+    assert_eq!(fonts.resolve('a').0, 0);
+    assert_eq!(fonts.resolve_cluster("a\u{336}"), 1);
+    // no font covers U+20DD, use the base char font
+    assert_eq!(fonts.resolve_cluster("a\u{20DD}"), 0);
+    assert_eq!(fonts.resolve_cluster("→\u{20DD}"), 1);
+    assert_eq!(fonts.resolve_cluster(""), 0);
+}
+
+#[test]
+fn layout_with_fallback_metrics() {
+    let fonts = exo_then_mono();
+    let scale = PxScale::from(30.0);
+    let sections = [SectionText {
+        text: "a→b",
+        scale,
+        font_id: 0,
+    }];
+    let glyphs = Layout::default().calculate_glyphs_with_fallback(
+        &fonts,
+        &SectionGeometry::default(),
+        &sections,
+    );
+    assert_eq!(glyphs.len(), 3);
+    assert_eq!(
+        glyphs.iter().map(|g| g.font_id).collect::<Vec<_>>(),
+        [0, 1, 0]
+    );
+
+    let exo = fonts.fonts()[0].as_scaled(scale);
+    let mono = fonts.fonts()[1].as_scaled(scale);
+    // the fallback glyph is not .notdef & uses the fallback advance
+    assert_eq!(glyphs[1].glyph.id, mono.glyph_id('→'));
+    let arrow_x = glyphs[1].glyph.position.x;
+    let b_x = glyphs[2].glyph.position.x;
+    assert!((arrow_x - exo.h_advance(exo.glyph_id('a'))).abs() < 1e-3);
+    assert!((b_x - arrow_x - mono.h_advance(mono.glyph_id('→'))).abs() < 1e-3);
+
+    // line metrics include the fallback font
+    let text = Layout::default().calculate_text_with_fallback(
+        &fonts,
+        &SectionGeometry::default(),
+        &sections,
+    );
+    let caret = text.caret(0).unwrap();
+    let expected = exo.ascent().max(mono.ascent()) - exo.descent().min(mono.descent());
+    assert!((caret.height() - expected).abs() < 1e-3);
+}
+
+#[test]
+fn layout_with_fallback_prefers_section_font() {
+    let fonts = exo_then_mono();
+    let sections = [
+        SectionText {
+            text: "ab",
+            font_id: 1,
+            ..SectionText::default()
+        },
+        SectionText {
+            text: "a\u{336}",
+            font_id: 0,
+            ..SectionText::default()
+        },
+    ];
+    let glyphs = Layout::default().calculate_glyphs_with_fallback(
+        &fonts,
+        &SectionGeometry::default(),
+        &sections,
+    );
+    // the preferred fallback font covers "ab", the combining mark cluster falls back together
+    assert_eq!(
+        glyphs.iter().map(|g| g.font_id).collect::<Vec<_>>(),
+        [1, 1, 1, 1]
+    );
+}
@@ -0,0 +1,140 @@
+use ab_glyph_rasterizer::*;
+
+/// Draws a closed polygon through `points`.
+fn draw_polygon(rasterizer: &mut Rasterizer, points: &[Point]) {
+    for (idx, &p) in points.iter().enumerate() {
+        rasterizer.draw_line(p, points[(idx + 1) % points.len()]);
+    }
+}
+
+/// Draws a clockwise (in pixel coordinates) rectangle.
+fn draw_rect(rasterizer: &mut Rasterizer, min: Point, max: Point) {
+    draw_polygon(
+        rasterizer,
+        &[min, point(max.x, min.y), max, point(min.x, max.y)],
+    );
+}
+
+fn coverage(rasterizer: &Rasterizer) -> Vec<f32> {
+    let mut pixels = Vec::new();
+    rasterizer.for_each_pixel(|_, alpha| pixels.push(alpha.min(1.0)));
+    pixels
+}
+
+fn pixel(rasterizer: &Rasterizer, x: usize, y: usize) -> f32 {
+    let (width, _) = rasterizer.dimensions();
+    coverage(rasterizer)[y * width + x]
+}
+
+/// Two overlapping rectangles of the same direction: non-zero fills the overlap,
+/// even-odd leaves it empty.
+#[test]
+fn overlapping_same_direction() {
+    let mut rasterizer = Rasterizer::new(6, 4);
+    draw_rect(&mut rasterizer, point(0.0, 0.0), point(4.0, 4.0));
+    draw_rect(&mut rasterizer, point(2.0, 0.0), point(6.0, 4.0));
+
+    assert_eq!(rasterizer.fill_rule(), FillRule::NonZero);
+    assert!(coverage(&rasterizer).iter().all(|&c| c == 1.0));
+
+    rasterizer.set_fill_rule(FillRule::EvenOdd);
+    for y in 0..4 {
+        let row: Vec<_> = (0..6).map(|x| pixel(&rasterizer, x, y)).collect();
+        assert_eq!(row, [1.0, 1.0, 0.0, 0.0, 1.0, 1.0]);
+    }
+}
+
+/// Overlapping rectangles of opposite directions cancel out with both rules.
+#[test]
+fn overlapping_opposite_direction() {
+    for fill_rule in [FillRule::NonZero, FillRule::EvenOdd] {
+        let mut rasterizer = Rasterizer::new(6, 4);
+        rasterizer.set_fill_rule(fill_rule);
+        draw_rect(&mut rasterizer, point(0.0, 0.0), point(4.0, 4.0));
+        draw_rect(&mut rasterizer, point(6.0, 0.0), point(2.0, 4.0));
+
+        for y in 0..4 {
+            let row: Vec<_> = (0..6).map(|x| pixel(&rasterizer, x, y)).collect();
+            assert_eq!(row, [1.0, 1.0, 0.0, 0.0, 1.0, 1.0], "{:?}", fill_rule);
+        }
+    }
+}
+
+/// A self-intersecting pentagram has a center with winding number 2.
+#[test]
+fn pentagram() {
+    let star: Vec<_> = (0..5)
+        .map(|n| {
+            let angle = core::f32::consts::PI * (-0.5 + n as f32 * 0.8);
+            point(20.0 + 18.0 * angle.cos(), 20.0 + 18.0 * angle.sin())
+        })
+        .collect();
+
+    let mut rasterizer = Rasterizer::new(40, 40);
+    draw_polygon(&mut rasterizer, &star);
+
+    // center & a point
+    assert!(pixel(&rasterizer, 20, 20) > 0.999);
+    assert!(pixel(&rasterizer, 20, 6) > 0.999);
+
+    rasterizer.set_fill_rule(FillRule::EvenOdd);
+    assert!(pixel(&rasterizer, 20, 20) < 0.001);
+    assert!(pixel(&rasterizer, 20, 6) > 0.999);
+}
+
+/// Without overlaps, including partial coverage of curved edges, both rules are the same.
+#[test]
+fn no_overlap_same_coverage() {
+    let mut rasterizer = Rasterizer::new(20, 20);
+    let [l, t, r, b] = [
+        point(1.5, 10.0),
+        point(10.0, 1.5),
+        point(18.5, 10.0),
+        point(10.0, 18.5),
+    ];
+    rasterizer.draw_quad(l, point(1.5, 1.5), t);
+    rasterizer.draw_quad(t, point(18.5, 1.5), r);
+    rasterizer.draw_quad(r, point(18.5, 18.5), b);
+    rasterizer.draw_quad(b, point(1.5, 18.5), l);
+    // inner hole of the opposite direction
+    draw_rect(&mut rasterizer, point(13.0, 7.0), point(7.0, 13.0));
+
+    let non_zero = coverage(&rasterizer);
+    assert!(non_zero.iter().any(|&c| c > 0.1 && c < 0.9));
+    assert_eq!(non_zero[10 * 20 + 10], 0.0);
+
+    rasterizer.set_fill_rule(FillRule::EvenOdd);
+    let even_odd = coverage(&rasterizer);
+    for (a, b) in non_zero.iter().zip(&even_odd) {
+        assert!((a - b).abs() < 1e-5, "{} != {}", a, b);
+    }
+}
+
+/// Partially covered pixels of overlapping edges use the fractional winding.
+#[test]
+fn even_odd_partial_overlap() {
+    let mut rasterizer = Rasterizer::new(3, 1);
+    rasterizer.set_fill_rule(FillRule::EvenOdd);
+    draw_rect(&mut rasterizer, point(0.0, 0.0), point(1.5, 1.0));
+    draw_rect(&mut rasterizer, point(0.0, 0.0), point(3.0, 1.0));
+
+    // winding 2, 1.5 & 1
+    let pixels = coverage(&rasterizer);
+    assert!(pixels[0].abs() < 1e-5, "{:?}", pixels);
+    assert!((pixels[1] - 0.5).abs() < 1e-5, "{:?}", pixels);
+    assert!((pixels[2] - 1.0).abs() < 1e-5, "{:?}", pixels);
+}
+
+#[test]
+fn fill_rule_kept_on_reset() {
+    let mut rasterizer = Rasterizer::new(3, 3);
+    rasterizer.set_fill_rule(FillRule::EvenOdd);
+    rasterizer.reset(5, 5);
+    rasterizer.clear();
+    assert_eq!(rasterizer.fill_rule(), FillRule::EvenOdd);
+
+    let mut lcd = LcdRasterizer::new(3, 3);
+    assert_eq!(lcd.fill_rule(), FillRule::NonZero);
+    lcd.set_fill_rule(FillRule::EvenOdd);
+    assert_eq!(lcd.fill_rule(), FillRule::EvenOdd);
+}
@@ -0,0 +1,337 @@
+use ab_glyph::*;
+use std::collections::HashSet;
+
+const DEJA_VU_MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+
+fn fonts() -> [FontRef<'static>; 2] {
+    [
+        FontRef::try_from_slice(DEJA_VU_MONO).unwrap(),
+        FontRef::try_from_slice(EXO2_OTF).unwrap(),
+    ]
+}
+
+/// Asserts the cached texture region of a glyph positioned at a subpixel bin center
+/// matches drawing the glyph directly.
+fn assert_cached_matches_draw(cache: &GlyphCache, fonts: &[FontRef<'_>], id: usize, g: &Glyph) {
+    let cached = cache.rect_for(id, g).unwrap();
+    let outlined = fonts[id].outline_glyph(g.clone()).unwrap();
+    assert_eq!(cached.px_bounds, outlined.px_bounds());
+
+    let rect = cached.texture_rect;
+    assert_eq!(rect.width as f32, outlined.px_bounds().width());
+    assert_eq!(rect.height as f32, outlined.px_bounds().height());
+
+    let (width, _) = cache.dimensions();
+    outlined.draw(|x, y, coverage| {
+        let idx = (rect.y + y) * width + rect.x + x;
+        let expected = (coverage * 255.0 + 0.5) as u8;
+        assert_eq!(cache.texture()[idx as usize], expected, "({}, {})", x, y);
+    });
+}
+
+#[test]
+fn cache_queued_glyphs() {
+    let fonts = fonts();
+    let mut cache = GlyphCache::new(256, 256);
+
+    let glyphs: Vec<_> = "Hello, World"
+        .chars()
+        .enumerate()
+        .map(|(idx, c)| {
+            let id = idx % 2;
+            let x = 10.125 + 20.0 * idx as f32;
+            (
+                id,
+                fonts[id]
+                    .glyph_id(c)
+                    .with_scale_and_position(30.0, point(x, 40.625)),
+            )
+        })
+        .collect();
+    for (id, g) in &glyphs {
+        cache.queue_glyph(*id, g.clone());
+    }
+    cache.cache_queued(&fonts).unwrap();
+
+    // space has no outline, font 0 'l' & 'o' are cached once
+    let dirty = cache.take_dirty_rects();
+    assert_eq!(dirty.len(), 9);
+    assert!(cache.take_dirty_rects().is_empty());
+
+    for (id, g) in &glyphs {
+        if fonts[*id].outline_glyph(g.clone()).is_none() {
+            assert_eq!(cache.rect_for(*id, g), None);
+            continue;
+        }
+        assert_cached_matches_draw(&cache, &fonts, *id, g);
+
+        let cached = cache.rect_for(*id, g).unwrap();
+        let rect = cached.texture_rect;
+        assert!(dirty.iter().any(|d| d.x < rect.x
+            && d.y < rect.y
+            && d.x + d.width > rect.x + rect.width
+            && d.y + d.height > rect.y + rect.height));
+        assert_eq!(
+            cached.tex_coords.min,
+            point(rect.x as f32 / 256.0, rect.y as f32 / 256.0)
+        );
+        assert_eq!(
+            cached.tex_coords.max,
+            point(
+                (rect.x + rect.width) as f32 / 256.0,
+                (rect.y + rect.height) as f32 / 256.0
+            )
+        );
+    }
+
+    // already cached glyphs are not redrawn
+    for (id, g) in &glyphs {
+        cache.queue_glyph(*id, g.clone());
+    }
+    cache.cache_queued(&fonts).unwrap();
+    assert!(cache.take_dirty_rects().is_empty());
+}
+
+#[test]
+fn subpixel_positions_share_bins() {
+    let fonts = fonts();
+    let mut cache = GlyphCache::new(128, 128);
+    let id = fonts[0].glyph_id('w');
+
+    let a = id.with_scale_and_position(20.0, point(10.01, 5.3));
+    let b = id.with_scale_and_position(20.0, point(30.2, -8.7));
+    let c = id.with_scale_and_position(20.0, point(10.3, 5.3));
+    for g in [&a, &b, &c] {
+        cache.queue_glyph(0, g.clone());
+    }
+    cache.cache_queued(&fonts).unwrap();
+    assert_eq!(cache.take_dirty_rects().len(), 2);
+
+    let a = cache.rect_for(0, &a).unwrap();
+    let b = cache.rect_for(0, &b).unwrap();
+    let c = cache.rect_for(0, &c).unwrap();
+    assert_eq!(a.texture_rect, b.texture_rect);
+    assert_ne!(a.texture_rect, c.texture_rect);
+
+    // placed by the integer position
+    assert_eq!(b.px_bounds.min - a.px_bounds.min, point(20.0, -14.0));
+    assert_eq!(b.px_bounds.max - a.px_bounds.max, point(20.0, -14.0));
+
+    // uncached scale
+    let other_scale = id.with_scale_and_position(21.0, point(10.01, 5.3));
+    assert_eq!(cache.rect_for(0, &other_scale), None);
+    assert_eq!(cache.rect_for(1, &id.with_scale(20.0)), None);
+}
+
+#[test]
+fn evict_least_recently_used() {
+    let fonts = fonts();
+    let mut cache = GlyphCache::new(64, 64);
+    let glyph = |c| {
+        fonts[0]
+            .glyph_id(c)
+            .with_scale_and_position(40.0, point(0.125, 0.125))
+    };
+    let a = glyph('A');
+
+    // cache a new glyph each batch, always using 'A'
+    let mut cached = vec![];
+    for c in "BCDEFGHIJKLMNOP".chars() {
+        cache.queue_glyph(0, a.clone());
+        cache.queue_glyph(0, glyph(c));
+        cache.cache_queued(&fonts).unwrap();
+        cached.push(glyph(c));
+
+        assert_cached_matches_draw(&cache, &fonts, 0, &a);
+        assert_cached_matches_draw(&cache, &fonts, 0, &glyph(c));
+
+        // the least recently used glyphs are evicted first
+        let evicted = cached
+            .iter()
+            .take_while(|g| cache.rect_for(0, g).is_none())
+            .count();
+        for g in &cached[evicted..] {
+            assert_cached_matches_draw(&cache, &fonts, 0, g);
+        }
+    }
+    // the texture only fits a few glyphs
+    assert!(cache.rect_for(0, &glyph('B')).is_none());
+}
+
+#[test]
+fn cache_queued_errors() {
+    let fonts = fonts();
+    let mut cache = GlyphCache::new(64, 64);
+    let glyph = |c, scale| {
+        fonts[0]
+            .glyph_id(c)
+            .with_scale_and_position(scale, point(0.125, 0.125))
+    };
+
+    cache.queue_glyph(0, glyph('M', 200.0));
+    assert_eq!(
+        cache.cache_queued(&fonts),
+        Err(GlyphCacheError::GlyphTooLarge)
+    );
+
+    for c in "ABCDEFGHIJ".chars() {
+        cache.queue_glyph(0, glyph(c, 40.0));
+    }
+    assert_eq!(
+        cache.cache_queued(&fonts),
+        Err(GlyphCacheError::NoRoomForWholeQueue)
+    );
+
+    // cache remains usable
+    cache.queue_glyph(0, glyph('D', 40.0));
+    cache.cache_queued(&fonts).unwrap();
+    assert_cached_matches_draw(&cache, &fonts, 0, &glyph('D', 40.0));
+}
+
+/// Many glyphs cycling through a small texture always fit once older glyphs are evicted.
+#[test]
+fn eviction_reuses_space() {
+    let fonts = fonts();
+    let mut cache = GlyphCache::new(128, 128);
+
+    for (round, text) in ["abcdefghij", "klmnopqrst", "uvwxyzABCD", "EFGHIJKLMN"]
+        .iter()
+        .cycle()
+        .take(12)
+        .enumerate()
+    {
+        let scale = 24.0 + (round % 5) as f32 * 4.0;
+        let glyphs: Vec<_> = text
+            .chars()
+            .map(|c| {
+                fonts[round % 2]
+                    .glyph_id(c)
+                    .with_scale_and_position(scale, point(5.125, 5.125))
+            })
+            .collect();
+        for g in &glyphs {
+            cache.queue_glyph(round % 2, g.clone());
+        }
+        cache.cache_queued(&fonts).unwrap();
+        for g in &glyphs {
+            assert_cached_matches_draw(&cache, &fonts, round % 2, g);
+        }
+    }
+}
+
+#[test]
+fn cache_key_subpixel_bins() {
+    let fonts = fonts();
+    let id = fonts[1].glyph_id('g');
+    let key = |x: f32, y: f32, bins| {
+        GlyphCacheKey::new(1, &id.with_scale_and_position(18.0, point(x, y)), bins)
+    };
+
+    let keys: HashSet<_> = (0..200)
+        .map(|n| {
+            let v = n as f32 * 0.37 - 30.0;
+            key(v, -v * 1.3, 3)
+        })
+        .collect();
+    assert_eq!(keys.len(), 9);
+    for k in &keys {
+        assert!(k.subpixel_x < 3 && k.subpixel_y < 3);
+        assert_eq!((k.font_id, k.id, k.subpixel_bins), (1, id, 3));
+        assert_eq!(f32::from_bits(k.scale_x), 18.0);
+    }
+
+    // bins are determined by the fractional offset from the floor
+    assert_eq!(key(-0.9, 2.05, 4), key(3.1, -7.95, 4));
+    assert_eq!(key(-0.9, 2.05, 4).subpixel_x, 0);
+    assert_eq!(key(7.99, 0.5, 4).subpixel_x, 3);
+    assert_eq!(key(7.99, 0.5, 4).subpixel_y, 2);
+    assert_eq!(key(7.99, 0.5, 1), key(1.0, 0.0, 1));
+    assert_eq!(key(7.99, 0.5, 0), key(1.0, 0.0, 1));
+    assert_ne!(key(0.1, 0.1, 4), key(0.1, 0.1, 8));
+
+    let glyph = key(7.99, 0.5, 4).glyph();
+    assert_eq!(glyph.id, id);
+    assert_eq!(glyph.scale, PxScale::from(18.0));
+    assert_eq!(glyph.position, point(0.875, 0.625));
+}
+
+#[test]
+fn outlined_glyph_subpixel_bin() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+
+    for (x, y) in [(10.6, 20.2), (-3.05, 7.99), (100.5, -0.4)] {
+        let glyph = font
+            .glyph_id('e')
+            .with_scale_and_position(24.0, point(x, y));
+        let outlined = font.outline_glyph(glyph.clone()).unwrap();
+        let binned = outlined.at_subpixel_bin(8);
+
+        let key = outlined.cache_key(0, 8);
+        assert_eq!(key, GlyphCacheKey::new(0, &glyph, 8));
+        assert_eq!(binned.glyph(), &key.glyph());
+
+        let offset = outlined.integer_position();
+        assert_eq!(offset, point(x.floor(), y.floor()));
+
+        // the binned rasterization placed at the integer position, at most 1/16px from
+        // the exact position, closely matches the exact rasterization
+        let binned_bounds = binned.px_bounds();
+        let mut binned_coverage =
+            vec![0.0; (binned_bounds.width() * binned_bounds.height()) as usize];
+        binned.draw(|x, y, c| binned_coverage[(y * binned_bounds.width() as u32 + x) as usize] = c);
+
+        let bounds = outlined.px_bounds();
+        outlined.draw(|px, py, c| {
+            let bx = bounds.min.x + px as f32 - offset.x - binned_bounds.min.x;
+            let by = bounds.min.y + py as f32 - offset.y - binned_bounds.min.y;
+            let binned_c = if bx >= 0.0
+                && by >= 0.0
+                && bx < binned_bounds.width()
+                && by < binned_bounds.height()
+            {
+                binned_coverage[(by * binned_bounds.width() + bx) as usize]
+            } else {
+                0.0
+            };
+            assert!(
+                (c - binned_c).abs() < 0.2,
+                "({}, {}) {} != {}",
+                px,
+                py,
+                c,
+                binned_c
+            );
+        });
+    }
+}
+
+#[test]
+fn glyph_cache_subpixel_bins() {
+    let fonts = fonts();
+    let mut cache = GlyphCache::new(128, 128).with_subpixel_bins(1);
+    let id = fonts[0].glyph_id('v');
+
+    let glyphs: Vec<_> = (0..10)
+        .map(|n| id.with_scale_and_position(20.0, point(n as f32 * 10.3, n as f32 * 0.7)))
+        .collect();
+    for g in &glyphs {
+        cache.queue_glyph(0, g.clone());
+    }
+    cache.cache_queued(&fonts).unwrap();
+    assert_eq!(cache.take_dirty_rects().len(), 1);
+
+    let first = cache.rect_for(0, &glyphs[0]).unwrap();
+    let binned = fonts[0]
+        .outline_glyph(glyphs[0].clone())
+        .unwrap()
+        .at_subpixel_bin(1);
+    for g in &glyphs {
+        let cached = cache.rect_for(0, g).unwrap();
+        assert_eq!(cached.texture_rect, first.texture_rect);
+
+        let offset = point(g.position.x.floor(), g.position.y.floor());
+        assert_eq!(cached.px_bounds.min, binned.px_bounds().min + offset);
+        assert_eq!(cached.px_bounds.max, binned.px_bounds().max + offset);
+    }
+}
@@ -0,0 +1,128 @@
+use ab_glyph::*;
+
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+const MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+
+#[test]
+fn try_glyph_id_missing() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+
+    assert_eq!(font.try_glyph_id('s'), Some(GlyphId(56)));
+    assert!(font.has_glyph('s'));
+
+    assert_eq!(font.try_glyph_id('☣'), None);
+    assert!(!font.has_glyph('☣'));
+    assert_eq!(font.glyph_id('☣'), GlyphId(0));
+}
+
+#[test]
+fn try_glyph_id_matches_cmap() {
+    for data in [EXO2_OTF, MONO] {
+        let font = FontRef::try_from_slice(data).unwrap();
+        for (id, c) in font.codepoint_ids() {
+            let expected = Some(id).filter(|id| id.0 != 0);
+            assert_eq!(font.try_glyph_id(c), expected, "{:?}", c);
+            assert_eq!(font.glyph_id(c), id, "{:?}", c);
+        }
+    }
+}
+
+/// Prefilled common chars missing from the font are also reported as missing.
+#[test]
+fn try_glyph_id_cached_missing() {
+    let font = FontRef::try_from_slice(MONO).unwrap();
+    let mapped: Vec<char> = font.codepoint_ids().map(|(_, c)| c).collect();
+    for c in "ABCxyz019#+*ç%&€§° \n\t\r".chars() {
+        assert_eq!(font.has_glyph(c), mapped.contains(&c), "{:?}", c);
+        if !font.has_glyph(c) {
+            assert_eq!(font.glyph_id(c), GlyphId(0));
+        }
+    }
+}
+
+#[test]
+fn try_glyph_id_wrappers() {
+    let font_ref = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let font_vec = FontVec::try_from_vec(EXO2_OTF.to_vec()).unwrap();
+    let font_arc = FontArc::try_from_slice(EXO2_OTF).unwrap();
+
+    fn check<F: Font>(font: F) {
+        assert_eq!(font.try_glyph_id('s'), Some(GlyphId(56)));
+        assert_eq!(font.try_glyph_id('☣'), None);
+        assert!(font.has_glyph('s'));
+        assert!(!font.has_glyph('☣'));
+
+        let scaled = font.as_scaled(24.0);
+        assert_eq!(scaled.try_glyph_id('s'), Some(GlyphId(56)));
+        assert_eq!(scaled.try_glyph_id('☣'), None);
+        assert!(scaled.has_glyph('s'));
+        assert!(!scaled.has_glyph('☣'));
+    }
+    check(&font_ref);
+    check(&font_vec);
+    check(&font_arc);
+    check(font_ref);
+    check(font_vec);
+    check(font_arc);
+}
+
+fn caches() -> Vec<GlyphIdCache> {
+    vec![
+        GlyphIdCache::default(),
+        GlyphIdCache::Disabled,
+        GlyphIdCache::Chars("aé☣\u{1F600}".into()),
+        GlyphIdCache::Bmp,
+        GlyphIdCache::Lazy,
+    ]
+}
+
+/// All cache strategies give the same results as uncached lookups.
+#[test]
+fn glyph_id_cache_strategies() {
+    for data in [EXO2_OTF, MONO] {
+        let mut chars: Vec<char> = FontRef::try_from_slice(data)
+            .unwrap()
+            .codepoint_ids()
+            .map(|(_, c)| c)
+            .collect();
+        // missing & non-BMP chars
+        chars.extend(['☣', 'א', '\u{1F600}', '\u{10FFFF}', '\u{FFFF}']);
+
+        let uncached =
+            FontRef::try_from_slice_and_index_with_cache(data, 0, GlyphIdCache::Disabled).unwrap();
+        for cache in caches() {
+            let font =
+                FontRef::try_from_slice_and_index_with_cache(data, 0, cache.clone()).unwrap();
+            let font_vec =
+                FontVec::try_from_vec_and_index_with_cache(data.to_vec(), 0, cache.clone())
+                    .unwrap();
+            // lookup twice to use lazily filled entries
+            for _ in 0..2 {
+                for &c in &chars {
+                    let expected = uncached.try_glyph_id(c);
+                    assert_eq!(font.try_glyph_id(c), expected, "{:?} {:?}", cache, c);
+                    assert_eq!(font_vec.try_glyph_id(c), expected, "{:?} {:?}", cache, c);
+                }
+            }
+            let cloned = font.clone();
+            assert_eq!(cloned.glyph_id('s'), font.glyph_id('s'));
+        }
+    }
+}
+
+#[test]
+fn glyph_id_lazy_cache_threads() {
+    let uncached =
+        FontRef::try_from_slice_and_index_with_cache(MONO, 0, GlyphIdCache::Disabled).unwrap();
+    let expected: Vec<_> = ('a'..='z').map(|c| uncached.glyph_id(c)).collect();
+    let font = FontRef::try_from_slice_and_index_with_cache(MONO, 0, GlyphIdCache::Lazy).unwrap();
+
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                let ids: Vec<_> = ('a'..='z').map(|c| font.glyph_id(c)).collect();
+                assert_eq!(ids, expected);
+            });
+        }
+    });
+}
@@ -0,0 +1,135 @@
+use ab_glyph::*;
+
+const DEJA_VU_MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+
+/// Returns the outline curve points of a hinted glyph in 26.6 pixels.
+fn hinted_points(c: char, ppem: f32) -> Vec<(i32, i32)> {
+    let font = FontRef::try_from_slice(DEJA_VU_MONO).unwrap();
+    let outline = font.hinted_outline(font.glyph_id(c), ppem).unwrap();
+    let px_26 = ppem / font.units_per_em().unwrap() * 64.0;
+    let to_26 = |p: Point| ((p.x * px_26).round() as i32, (p.y * px_26).round() as i32);
+
+    outline
+        .curves
+        .iter()
+        .flat_map(|curve| match *curve {
+            OutlineCurve::Line(p0, p1) => vec![p0, p1],
+            OutlineCurve::Quad(p0, p1, p2) => vec![p0, p1, p2],
+            OutlineCurve::Cubic(p0, p1, p2, p3) => vec![p0, p1, p2, p3],
+        })
+        .map(to_26)
+        .collect()
+}
+
+/// Asserts hinted outline points match `expected` glyph points. Implied on-curve
+/// points between consecutive control points are also allowed.
+fn assert_hinted_points(c: char, ppem: f32, expected: &[(i32, i32)]) {
+    let points = hinted_points(c, ppem);
+
+    for p in expected {
+        assert!(points.contains(p), "{:?} missing from {:?}", p, points);
+    }
+    for p in &points {
+        let implied = expected.iter().any(|a| {
+            expected
+                .iter()
+                .any(|b| (a.0 + b.0 - 2 * p.0).abs() <= 1 && (a.1 + b.1 - 2 * p.1).abs() <= 1)
+        });
+        assert!(implied, "Unexpected {:?}", p);
+    }
+}
+
+// Expected points are FreeType's v35 interpreter output.
+#[test]
+fn hinted_ttf_a_12ppem() {
+    #[rustfmt::skip]
+    assert_hinted_points('A', 12.0, &[
+        (256, 480), (181, 192), (331, 192), (215, 576), (297, 576), (448, 0),
+        (382, 0), (348, 128), (164, 128), (130, 0), (64, 0),
+    ]);
+}
+
+#[test]
+fn hinted_ttf_g_9ppem() {
+    #[rustfmt::skip]
+    assert_hinted_points('g', 9.0, &[
+        (256, 160), (256, 207), (224, 256), (194, 256), (162, 256), (128, 207),
+        (128, 160), (128, 113), (162, 64), (194, 64), (224, 64), (256, 113),
+        (320, 33), (320, -46), (262, -128), (207, -128), (188, -128), (148, -128),
+        (128, -128), (128, -64), (149, -64), (182, -64), (196, -64), (227, -64),
+        (256, -23), (256, 22), (256, 25), (256, 71), (245, 35), (205, 0),
+        (176, 0), (125, 0), (64, 87), (64, 160), (64, 233), (125, 320),
+        (176, 320), (205, 320), (244, 286), (256, 251), (256, 320), (320, 320),
+    ]);
+}
+
+/// Composite glyph of 'e' & acute accent.
+#[test]
+fn hinted_ttf_e_acute_13ppem() {
+    #[rustfmt::skip]
+    assert_hinted_points('é', 13.0, &[
+        (448, 232), (448, 192), (126, 192), (126, 191), (126, 130), (208, 64),
+        (282, 64), (319, 64), (401, 96), (448, 128), (448, 64), (405, 32),
+        (326, 0), (289, 0), (183, 0), (64, 119), (64, 224), (64, 326),
+        (176, 448), (269, 448), (352, 448), (448, 332), (384, 256), (382, 319),
+        (321, 384), (264, 384), (207, 384), (135, 316), (128, 256), (305, 640),
+        (384, 640), (254, 512), (192, 512),
+    ]);
+}
+
+/// Uses diagonal projection vectors.
+#[test]
+fn hinted_ttf_m_9ppem() {
+    #[rustfmt::skip]
+    assert_hinted_points('M', 9.0, &[
+        (64, 448), (144, 448), (192, 195), (240, 448), (320, 448), (320, 0),
+        (256, 0), (256, 319), (221, 128), (163, 128), (128, 319), (128, 0),
+        (64, 0),
+    ]);
+}
+
+/// Hinting should align stems to pixel boundaries, reducing partially covered pixels.
+#[test]
+fn hinted_draw_sharper_stems() {
+    let font = FontRef::try_from_slice(DEJA_VU_MONO).unwrap();
+    // 12 pixels per em
+    let scale = 12.0 * font.height_unscaled() / font.units_per_em().unwrap();
+    let glyph = font.glyph_id('H').with_scale(scale);
+
+    let partial_pixels = |outlined: OutlinedGlyph| {
+        let mut count = 0;
+        outlined.draw(|_, _, c| {
+            if c > 0.05 && c < 0.95 {
+                count += 1;
+            }
+        });
+        count
+    };
+
+    let hinted = font.outline_glyph_hinted(glyph.clone()).unwrap();
+    let unhinted = font.outline_glyph(glyph).unwrap();
+    let bounds = hinted.px_bounds();
+    assert_eq!(bounds.min.x.fract(), 0.0);
+    assert_eq!(bounds.max.x.fract(), 0.0);
+
+    let (hinted, unhinted) = (partial_pixels(hinted), partial_pixels(unhinted));
+    assert_eq!(hinted, 0, "unhinted {}", unhinted);
+    assert!(unhinted > 0);
+}
+
+#[test]
+fn hinted_outline_cff_none() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    assert!(font.hinted_outline(font.glyph_id('a'), 12.0).is_none());
+    assert!(font.outline(font.glyph_id('a')).is_some());
+}
+
+#[test]
+fn hinted_outline_invalid_ppem() {
+    let font = FontRef::try_from_slice(DEJA_VU_MONO).unwrap();
+    let id = font.glyph_id('a');
+    assert!(font.hinted_outline(id, 0.0).is_none());
+    assert!(font.hinted_outline(id, f32::NAN).is_none());
+    assert!(font.hinted_outline(font.glyph_id(' '), 12.0).is_none());
+}
@@ -0,0 +1,85 @@
+use ab_glyph::*;
+
+const LAYOUT: &[u8] = include_bytes!("../fonts/layout.ttf");
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+const EXO2_TTF: &[u8] = include_bytes!("../fonts/Exo2-Light.ttf");
+
+// layout.ttf glyph ids
+const A: GlyphId = GlyphId(4);
+const CAP_A: GlyphId = GlyphId(13);
+const CAP_V: GlyphId = GlyphId(14);
+
+#[test]
+fn gpos_pair_format1_kern() {
+    let font = FontRef::try_from_slice(LAYOUT).unwrap();
+    assert_eq!(font.kern_unscaled(CAP_A, CAP_V), -80.0);
+    assert_eq!(font.kern_unscaled(CAP_A, CAP_A), 0.0);
+    assert_eq!(font.kern_unscaled(CAP_V, CAP_V), 0.0);
+    assert_eq!(font.kern_unscaled(A, CAP_V), 0.0);
+}
+
+#[test]
+fn gpos_pair_format2_kern() {
+    let font = FontVec::try_from_vec(LAYOUT.to_vec()).unwrap();
+    assert_eq!(font.kern_unscaled(CAP_V, CAP_A), -60.0);
+    assert_eq!(font.kern_unscaled(CAP_V, A), 0.0);
+    // out of range glyph ids
+    assert_eq!(font.kern_unscaled(CAP_V, GlyphId(999)), 0.0);
+    assert_eq!(font.kern_unscaled(GlyphId(999), CAP_A), 0.0);
+
+    let font = font.as_scaled(PxScale::from(100.0));
+    assert_eq!(font.kern(CAP_V, CAP_A), -6.0);
+}
+
+/// `kern_unscaled` should match kerning applied by `GPOS` shaping.
+#[test]
+fn gpos_kern_matches_shaping() {
+    for data in [EXO2_OTF, EXO2_TTF] {
+        let font = FontRef::try_from_slice(data).unwrap();
+        let mut kerned = 0;
+        for pair in ["AV", "To", "Ty", "LT", "av", "r.", "WA", "P,", "ab"] {
+            let shaped = font.shape(pair, &[Feature::off(b"liga")], b"latn", None);
+            let (first, second) = (shaped[0].id, shaped[1].id);
+            let kern = font.kern_unscaled(first, second);
+            assert_eq!(
+                shaped[0].x_advance,
+                font.h_advance_unscaled(first) + kern,
+                "{}",
+                pair
+            );
+            if kern != 0.0 {
+                kerned += 1;
+            }
+        }
+        assert!(kerned >= 5, "{}", kerned);
+    }
+}
+
+/// Kerning with cached coverage & class definitions should match uncached `GPOS` shaping
+/// for all pairs of printable ascii chars.
+#[test]
+fn gpos_kern_cached_matches_shaping_all_pairs() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let mut kerned = 0;
+    for a in ' '..='~' {
+        for b in ' '..='~' {
+            let pair: String = [a, b].iter().collect();
+            let shaped = font.shape(&pair, &[Feature::off(b"liga")], b"latn", None);
+            if shaped.len() != 2 {
+                continue;
+            }
+            let (first, second) = (shaped[0].id, shaped[1].id);
+            let kern = font.kern_unscaled(first, second);
+            assert_eq!(
+                shaped[0].x_advance,
+                font.h_advance_unscaled(first) + kern,
+                "{:?}",
+                pair
+            );
+            if kern != 0.0 {
+                kerned += 1;
+            }
+        }
+    }
+    assert!(kerned > 500, "{}", kerned);
+}
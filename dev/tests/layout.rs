@@ -0,0 +1,309 @@
+use ab_glyph::*;
+
+const MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+const OPENS_SANS_ITALIC: &[u8] = include_bytes!("../fonts/OpenSans-Italic.ttf");
+
+fn section(text: &str) -> SectionText<'_> {
+    SectionText {
+        text,
+        scale: PxScale::from(20.0),
+        font_id: 0,
+    }
+}
+
+/// Lines of text reconstructed from the glyph byte indices, grouped by baseline.
+fn lines(sections: &[SectionText<'_>], glyphs: &[SectionGlyph]) -> Vec<String> {
+    let mut lines: Vec<(f32, String)> = Vec::new();
+    for g in glyphs {
+        let c = sections[g.section_index].text[g.byte_index..]
+            .chars()
+            .next()
+            .unwrap();
+        match lines.last_mut() {
+            Some((y, line)) if *y == g.glyph.position.y => line.push(c),
+            _ => lines.push((g.glyph.position.y, c.to_string())),
+        }
+    }
+    lines.into_iter().map(|(_, l)| l).collect()
+}
+
+/// Baselines of each line.
+fn baselines(glyphs: &[SectionGlyph]) -> Vec<f32> {
+    let mut ys: Vec<f32> = glyphs.iter().map(|g| g.glyph.position.y).collect();
+    ys.dedup();
+    ys
+}
+
+#[test]
+fn layout_wraps_words() {
+    let font = FontRef::try_from_slice(MONO).unwrap();
+    let advance = font.as_scaled(20.0).h_advance(font.glyph_id('a'));
+    let geometry = SectionGeometry {
+        bounds: (advance * 11.5, f32::INFINITY),
+        ..SectionGeometry::default()
+    };
+    let sections = [section("hello world, this is a well-known  paragraph!")];
+
+    let glyphs = Layout::default().calculate_glyphs(&[&font], &geometry, &sections);
+    assert_eq!(
+        lines(&sections, &glyphs),
+        [
+            "hello ",
+            "world, this ",
+            "is a well-",
+            "known  ",
+            "paragraph!"
+        ]
+    );
+    // every line starts at the left & fits, ignoring trailing whitespace
+    for g in &glyphs {
+        let c = sections[0].text[g.byte_index..].chars().next().unwrap();
+        assert!(g.glyph.position.x >= 0.0);
+        if !c.is_whitespace() {
+            assert!(g.glyph.position.x + advance <= geometry.bounds.0 + 1e-3);
+        }
+    }
+}
+
+#[test]
+fn layout_long_word_overflows() {
+    let font = FontRef::try_from_slice(MONO).unwrap();
+    let geometry = SectionGeometry {
+        bounds: (50.0, f32::INFINITY),
+        ..SectionGeometry::default()
+    };
+    let sections = [section("a incomprehensibilities b")];
+    let glyphs = Layout::default().calculate_glyphs(&[&font], &geometry, &sections);
+    assert_eq!(
+        lines(&sections, &glyphs),
+        ["a ", "incomprehensibilities ", "b"]
+    );
+}
+
+#[test]
+fn layout_hard_breaks() {
+    let font = FontRef::try_from_slice(MONO).unwrap();
+    let scaled = font.as_scaled(20.0);
+    let sections = [section("one\ntwo\r\n\nfour\u{2028}five")];
+
+    let glyphs =
+        Layout::default().calculate_glyphs(&[&font], &SectionGeometry::default(), &sections);
+    assert_eq!(lines(&sections, &glyphs), ["one", "two", "four", "five"]);
+
+    let line_height = scaled.height() + scaled.line_gap();
+    let ys = baselines(&glyphs);
+    assert_eq!(ys[0], scaled.ascent());
+    // the empty line is kept
+    let steps: Vec<f32> = ys.windows(2).map(|w| w[1] - w[0]).collect();
+    for (step, lines) in steps.iter().zip(&[1.0, 2.0, 1.0]) {
+        assert!((step - line_height * lines).abs() < 1e-3, "{}", step);
+    }
+}
+
+#[test]
+fn layout_section_indices() {
+    let exo2 = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let italic = FontRef::try_from_slice(OPENS_SANS_ITALIC).unwrap();
+    let sections = [
+        SectionText {
+            text: "Héllo ",
+            scale: PxScale::from(30.0),
+            font_id: 0,
+        },
+        SectionText {
+            text: "wörld\n",
+            scale: PxScale::from(20.0),
+            font_id: 1,
+        },
+        SectionText {
+            text: "",
+            ..SectionText::default()
+        },
+        SectionText {
+            text: "¡ok!",
+            scale: PxScale::from(25.0),
+            font_id: 0,
+        },
+    ];
+    let glyphs = Layout::default().calculate_glyphs(
+        &[exo2.clone(), italic.clone()],
+        &SectionGeometry::default(),
+        &sections,
+    );
+
+    let text: String = glyphs
+        .iter()
+        .map(|g| {
+            sections[g.section_index].text[g.byte_index..]
+                .chars()
+                .next()
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(text, "Héllo wörld¡ok!");
+
+    let fonts = [exo2, italic];
+    for g in &glyphs {
+        let section = &sections[g.section_index];
+        let c = section.text[g.byte_index..].chars().next().unwrap();
+        assert_eq!(g.font_id, section.font_id);
+        assert_eq!(g.glyph.scale, section.scale);
+        assert_eq!(g.glyph.id, fonts[g.font_id].glyph_id(c));
+    }
+
+    // the first line baseline fits the largest ascent of the line
+    let ascent = fonts[0].as_scaled(30.0).ascent();
+    assert!(ascent > fonts[1].as_scaled(20.0).ascent());
+    assert_eq!(glyphs[0].glyph.position.y, ascent);
+}
+
+/// Sections with the same font & scale lay out like a single section, with kerning.
+#[test]
+fn layout_kerns_across_sections() {
+    let font = FontRef::try_from_slice(OPENS_SANS_ITALIC).unwrap();
+    let scaled = font.as_scaled(20.0);
+    assert_ne!(scaled.kern(font.glyph_id('A'), font.glyph_id('V')), 0.0);
+
+    let single = [section("AVAV")];
+    let split = [section("AV"), section("AV")];
+    let geometry = SectionGeometry::default();
+    let positions = |sections: &[SectionText<'_>]| -> Vec<Point> {
+        Layout::default()
+            .calculate_glyphs(&[&font], &geometry, sections)
+            .into_iter()
+            .map(|g| g.glyph.position)
+            .collect()
+    };
+    assert_eq!(positions(&single), positions(&split));
+}
+
+#[test]
+fn layout_horizontal_align() {
+    let font = FontRef::try_from_slice(MONO).unwrap();
+    let advance = font.as_scaled(20.0).h_advance(font.glyph_id('a'));
+    let sections = [section("ab cdef\ngh  ")];
+    let geometry = SectionGeometry {
+        screen_position: point(500.0, 100.0),
+        ..SectionGeometry::default()
+    };
+
+    let layout = |h_align| {
+        let glyphs = Layout {
+            h_align,
+            ..Layout::default()
+        }
+        .calculate_glyphs(&[&font], &geometry, &sections);
+        // first glyph x & last visible glyph right edge of each line
+        let line = |range: std::ops::Range<usize>| {
+            let line = &glyphs[range];
+            let visible = line.iter().rev().find(|g| g.glyph.id != font.glyph_id(' '));
+            (
+                line[0].glyph.position.x,
+                visible.unwrap().glyph.position.x + advance,
+            )
+        };
+        (line(0..7), line(7..11))
+    };
+
+    let (first, second) = layout(HorizontalAlign::Left);
+    assert_eq!((first.0, second.0), (500.0, 500.0));
+
+    let (first, second) = layout(HorizontalAlign::Right);
+    assert!((first.1 - 500.0).abs() < 1e-3);
+    // trailing whitespace is ignored
+    assert!((second.1 - 500.0).abs() < 1e-3);
+
+    let (first, second) = layout(HorizontalAlign::Center);
+    assert!((first.0 + first.1 - 1000.0).abs() < 1e-3);
+    assert!((second.0 + second.1 - 1000.0).abs() < 1e-3);
+}
+
+#[test]
+fn layout_justify() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let scaled = font.as_scaled(20.0);
+    let width = 150.0;
+    let geometry = SectionGeometry {
+        screen_position: point(10.0, 0.0),
+        bounds: (width, f32::INFINITY),
+    };
+    let text = "The quick brown fox jumps over the lazy dog.\nHard break.";
+    let sections = [section(text)];
+    let layout = Layout {
+        h_align: HorizontalAlign::Justify,
+        ..Layout::default()
+    };
+    let glyphs = layout.calculate_glyphs(&[&font], &geometry, &sections);
+    let left = Layout::default().calculate_glyphs(&[&font], &geometry, &sections);
+    assert_eq!(glyphs.len(), left.len());
+
+    let ys = baselines(&glyphs);
+    assert!(ys.len() >= 4, "{:?}", ys);
+    for (idx, y) in ys.iter().enumerate() {
+        let line: Vec<_> = glyphs.iter().filter(|g| g.glyph.position.y == *y).collect();
+        let visible = line
+            .iter()
+            .rev()
+            .find(|g| !text[g.byte_index..].starts_with(' '))
+            .unwrap();
+        let right = visible.glyph.position.x + scaled.h_advance(visible.glyph.id);
+        assert_eq!(line[0].glyph.position.x, 10.0);
+
+        let hard_break_line = idx == ys.len() - 2;
+        if idx == ys.len() - 1 || hard_break_line {
+            // not stretched, same as left aligned
+            let left_line: Vec<_> = left.iter().filter(|g| g.glyph.position.y == *y).collect();
+            assert_eq!(line, left_line);
+            assert!(right < 10.0 + width);
+        } else {
+            assert!((right - (10.0 + width)).abs() < 1e-3, "{}", right);
+        }
+    }
+}
+
+#[test]
+fn layout_vertical_align() {
+    let font = FontRef::try_from_slice(MONO).unwrap();
+    let scaled = font.as_scaled(20.0);
+    let sections = [section("a\nb\nc")];
+    let text_height = 3.0 * scaled.height() + 2.0 * scaled.line_gap();
+
+    let first_baseline = |v_align| {
+        let geometry = SectionGeometry {
+            screen_position: point(0.0, 200.0),
+            ..SectionGeometry::default()
+        };
+        let glyphs = Layout {
+            v_align,
+            ..Layout::default()
+        }
+        .calculate_glyphs(&[&font], &geometry, &sections);
+        glyphs[0].glyph.position.y
+    };
+
+    let top = first_baseline(VerticalAlign::Top);
+    assert_eq!(top, 200.0 + scaled.ascent());
+    let center = first_baseline(VerticalAlign::Center);
+    assert!((center - (top - text_height / 2.0)).abs() < 1e-3);
+    let bottom = first_baseline(VerticalAlign::Bottom);
+    assert!((bottom - (top - text_height)).abs() < 1e-3);
+}
+
+#[test]
+fn layout_bounds_height() {
+    let font = FontRef::try_from_slice(MONO).unwrap();
+    let scaled = font.as_scaled(20.0);
+    let sections = [section("a\nb\nc\nd")];
+    let line_height = scaled.height() + scaled.line_gap();
+    let geometry = SectionGeometry {
+        bounds: (f32::INFINITY, line_height * 1.5),
+        ..SectionGeometry::default()
+    };
+    let glyphs = Layout::default().calculate_glyphs(&[&font], &geometry, &sections);
+    assert_eq!(lines(&sections, &glyphs), ["a", "b"]);
+
+    assert!(Layout::default()
+        .calculate_glyphs(&[&font], &SectionGeometry::default(), &[section("")])
+        .is_empty());
+}
@@ -0,0 +1,190 @@
+use ab_glyph::*;
+use image::{Rgb, RgbImage};
+use std::{env, io::Cursor, path::PathBuf};
+
+const DEJA_VU_MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+
+/// Return target directory accounting for env var `CARGO_TARGET_DIR`.
+fn temp_path(name: impl AsRef<std::path::Path>) -> PathBuf {
+    let mut path = env::var("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("../target"));
+
+    path.push(name);
+    path
+}
+
+fn outlined(font: &[u8], c: char, scale: f32, position: Point) -> OutlinedGlyph {
+    let font = FontRef::try_from_slice(font).unwrap();
+    let glyph = font.glyph_id(c).with_scale_and_position(scale, position);
+    font.outline_glyph(glyph).unwrap()
+}
+
+/// Draws lcd coverage into a `lcd_px_bounds` sized buffer.
+fn draw_lcd(glyph: &OutlinedGlyph, order: LcdOrder, filter: LcdFilter) -> Vec<Vec<[f32; 3]>> {
+    let bounds = glyph.lcd_px_bounds(order);
+    let mut pixels = vec![vec![[0.0; 3]; bounds.width() as usize]; bounds.height() as usize];
+    glyph.draw_lcd(order, filter, |x, y, rgb| {
+        for c in rgb {
+            assert!((0.0..=1.0001).contains(&c), "({}, {}) {:?}", x, y, rgb);
+        }
+        pixels[y as usize][x as usize] = rgb;
+    });
+    pixels
+}
+
+#[test]
+fn reference_lcd_otf_w() {
+    let glyph = outlined(EXO2_OTF, 'w', 16.0, point(0.3, 0.0));
+    let pixels = draw_lcd(&glyph, LcdOrder::Rgb, LcdFilter::DEFAULT);
+
+    let mut new_image = RgbImage::new(pixels[0].len() as u32, pixels.len() as u32);
+    for (y, row) in pixels.iter().enumerate() {
+        for (x, rgb) in row.iter().enumerate() {
+            let c = rgb.map(|c| (c * 255.0).round() as u8);
+            new_image.put_pixel(x as u32, y as u32, Rgb(c));
+        }
+    }
+    new_image.save(temp_path("new_lcd_otf_w.png")).unwrap();
+
+    let reference = image::load(
+        Cursor::new(include_bytes!("reference_lcd_otf_w.png") as &[u8]),
+        image::ImageFormat::Png,
+    )
+    .expect("!image::load")
+    .to_rgb8();
+
+    assert_eq!(reference.dimensions(), new_image.dimensions());
+    for (x, y, pixel) in reference.enumerate_pixels() {
+        assert_eq!(
+            pixel,
+            new_image.get_pixel(x, y),
+            "unexpected coverage difference at ({}, {})",
+            x,
+            y
+        );
+    }
+}
+
+/// Without filtering the mean subpixel coverage is the grayscale coverage.
+/// Uses a glyph of straight lines, as curves are flattened differently at 3x resolution.
+#[test]
+fn unfiltered_mean_matches_draw() {
+    for order in [LcdOrder::Rgb, LcdOrder::Bgr, LcdOrder::Vrgb, LcdOrder::Vbgr] {
+        let glyph = outlined(DEJA_VU_MONO, 'E', 20.0, point(3.7, 10.2));
+        let pixels = draw_lcd(&glyph, order, LcdFilter::NONE);
+        let (dx, dy) = match order {
+            LcdOrder::Rgb | LcdOrder::Bgr => (1, 0),
+            _ => (0, 1),
+        };
+
+        let mut gray = vec![];
+        glyph.draw(|x, y, c| gray.push((x, y, c.min(1.0))));
+        for (x, y, c) in gray {
+            let [r, g, b] = pixels[y as usize + dy][x as usize + dx];
+            let mean = (r + g + b) / 3.0;
+            assert!(
+                (mean - c).abs() < 0.001,
+                "{:?} ({}, {}) {} != {}",
+                order,
+                x,
+                y,
+                mean,
+                c
+            );
+        }
+    }
+}
+
+/// Filters with weights summing to 1 preserve the total coverage.
+#[test]
+fn filter_preserves_coverage() {
+    let glyph = outlined(EXO2_OTF, 'g', 18.0, point(0.5, 0.5));
+    let total = |filter| -> f32 {
+        draw_lcd(&glyph, LcdOrder::Rgb, filter)
+            .iter()
+            .flatten()
+            .map(|[r, g, b]| r + g + b)
+            .sum()
+    };
+    let none = total(LcdFilter::NONE);
+    assert!(none > 50.0, "{}", none);
+    for filter in [LcdFilter::DEFAULT, LcdFilter::LIGHT] {
+        let filtered = total(filter);
+        assert!(
+            (filtered - none).abs() < 0.01 * none,
+            "{} != {}",
+            filtered,
+            none
+        );
+    }
+
+    // filtering spreads coverage into the extra pixel columns
+    let pixels = draw_lcd(&glyph, LcdOrder::Rgb, LcdFilter::DEFAULT);
+    assert!(pixels.iter().any(|row| row[0][2] > 0.0));
+    assert!(pixels.iter().all(|row| row[0][0] < 1e-5));
+}
+
+#[test]
+fn subpixel_orders() {
+    let glyph = outlined(DEJA_VU_MONO, 'k', 15.0, point(0.2, 0.0));
+    let rgb = draw_lcd(&glyph, LcdOrder::Rgb, LcdFilter::DEFAULT);
+    let bgr = draw_lcd(&glyph, LcdOrder::Bgr, LcdFilter::DEFAULT);
+    let vrgb = draw_lcd(&glyph, LcdOrder::Vrgb, LcdFilter::DEFAULT);
+    let vbgr = draw_lcd(&glyph, LcdOrder::Vbgr, LcdFilter::DEFAULT);
+
+    // vertical orders extend vertically
+    let vertical = glyph.lcd_px_bounds(LcdOrder::Vrgb);
+    assert_eq!(vertical.width(), glyph.px_bounds().width());
+    assert_eq!(vertical.height(), glyph.px_bounds().height() + 2.0);
+    assert_eq!(vertical, glyph.lcd_px_bounds(LcdOrder::Vbgr));
+    assert_eq!(vrgb.len(), vertical.height() as usize);
+    assert_eq!(vrgb[0].len(), vertical.width() as usize);
+
+    // the leftmost/topmost subpixel is red for rgb & blue for bgr
+    for (rgb, bgr) in [(&rgb, &bgr), (&vrgb, &vbgr)] {
+        assert_ne!(rgb, bgr);
+        for (rgb_row, bgr_row) in rgb.iter().zip(bgr) {
+            for ([r, g, b], [br, bg, bb]) in rgb_row.iter().zip(bgr_row) {
+                assert_eq!([r, g, b], [bb, bg, br]);
+            }
+        }
+    }
+
+    // on the left edge of the stem of a 'k' the leftmost subpixel is least covered
+    let stem = rgb
+        .iter()
+        .flat_map(|row| row.iter().find(|[_, _, b]| *b > 0.5))
+        .next()
+        .unwrap();
+    assert!(stem[0] < stem[1] && stem[1] < stem[2], "{:?}", stem);
+    let stem = vrgb.iter().flatten().find(|[_, _, b]| *b > 0.5).unwrap();
+    assert!(stem[0] < stem[2], "{:?}", stem);
+}
+
+#[test]
+fn lcd_rasterizer_filter() {
+    use ab_glyph_rasterizer::{point, LcdFilter, LcdRasterizer};
+
+    // a vertical edge covering subpixels from x = 4/3
+    let mut rasterizer = LcdRasterizer::new(3, 1);
+    rasterizer.draw_line(point(4.0 / 3.0, 0.0), point(4.0 / 3.0, 1.0));
+    rasterizer.draw_line(point(3.0, 1.0), point(3.0, 0.0));
+
+    let mut pixels = vec![[0.0; 3]; 3];
+    rasterizer.for_each_pixel_rgb(|idx, rgb| pixels[idx] = rgb);
+    // subpixels 4 to 8 are covered, filtering spreads 2 subpixels either side
+    let [w0, w1, w2, w3, w4] = LcdFilter::DEFAULT.0;
+    let expected = [
+        [0.0, 0.0, w4],
+        [w3 + w4, w2 + w3 + w4, w1 + w2 + w3 + w4],
+        // coverage beyond the right edge is lost
+        [1.0, w0 + w1 + w2 + w3, w0 + w1 + w2],
+    ];
+    for (px, expected) in pixels.iter().zip(expected) {
+        for (c, e) in px.iter().zip(expected) {
+            assert!((c - e).abs() < 1e-5, "{:?} != {:?}", pixels, expected);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use ab_glyph::*;
+
+const OPENS_SANS_ITALIC: &[u8] = include_bytes!("../fonts/OpenSans-Italic.ttf");
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+
+fn union(a: Rect, b: Rect) -> Rect {
+    Rect {
+        min: point(a.min.x.min(b.min.x), a.min.y.min(b.min.y)),
+        max: point(a.max.x.max(b.max.x), a.max.y.max(b.max.y)),
+    }
+}
+
+/// Measurements match the glyphs positioned by `Layout`.
+#[test]
+fn measure_matches_layout() {
+    let font = FontRef::try_from_slice(OPENS_SANS_ITALIC).unwrap();
+    let scaled = font.as_scaled(30.0);
+    let text = "AVATAR Type, Ltd.";
+    let sections = [SectionText {
+        text,
+        scale: scaled.scale(),
+        font_id: 0,
+    }];
+    let geometry = SectionGeometry {
+        screen_position: point(20.3, 40.7),
+        ..SectionGeometry::default()
+    };
+    let glyphs = Layout::default().calculate_glyphs(&[&font], &geometry, &sections);
+
+    let last = &glyphs.last().unwrap().glyph;
+    let layout_width = last.position.x + scaled.h_advance(last.id) - 20.3;
+    assert!((scaled.text_width(text) - layout_width).abs() < 1e-3);
+
+    let baseline = glyphs[0].glyph.position;
+    assert_eq!(baseline, point(20.3, 40.7 + scaled.ascent()));
+    let layout_bounds = glyphs
+        .iter()
+        .filter_map(|g| font.outline_glyph(g.glyph.clone()))
+        .map(|g| g.px_bounds())
+        .reduce(union);
+    assert_eq!(scaled.text_px_bounds(text, baseline), layout_bounds);
+}
+
+#[test]
+fn measure_multiline_matches_layout() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let scaled = font.as_scaled(24.0);
+    let text = "Short\r\nA longer line\nmid\n";
+    let sections = [SectionText {
+        text,
+        scale: scaled.scale(),
+        font_id: 0,
+    }];
+    let glyphs =
+        Layout::default().calculate_glyphs(&[&font], &SectionGeometry::default(), &sections);
+
+    let (width, height) = scaled.multiline_size(text);
+    assert_eq!(width, scaled.text_width("A longer line"));
+    // trailing line break starts a 4th line
+    assert!((height - (3.0 * scaled.line_height() + scaled.height())).abs() < 1e-3);
+
+    let mut baselines: Vec<f32> = glyphs.iter().map(|g| g.glyph.position.y).collect();
+    baselines.dedup();
+    assert_eq!(baselines.len(), 3);
+    for (idx, y) in baselines.iter().enumerate() {
+        let expected = scaled.ascent() + idx as f32 * scaled.line_height();
+        assert!((y - expected).abs() < 1e-3);
+    }
+
+    let first_baseline = point(0.0, scaled.ascent());
+    let layout_bounds = glyphs
+        .iter()
+        .filter_map(|g| font.outline_glyph(g.glyph.clone()))
+        .map(|g| g.px_bounds())
+        .reduce(union)
+        .unwrap();
+    let bounds = scaled.multiline_px_bounds(text, first_baseline).unwrap();
+    assert!((bounds.min.x - layout_bounds.min.x).abs() <= 1.0);
+    assert!((bounds.max.x - layout_bounds.max.x).abs() <= 1.0);
+    assert!((bounds.min.y - layout_bounds.min.y).abs() <= 1.0);
+    assert!((bounds.max.y - layout_bounds.max.y).abs() <= 1.0);
+}
+
+/// Line & paragraph separators are line breaks, not measured glyphs, as in `Layout`.
+#[test]
+fn measure_line_separator_matches_layout() {
+    let font = FontRef::try_from_slice(OPENS_SANS_ITALIC).unwrap();
+    let scaled = font.as_scaled(30.0);
+    let text = "Type\u{2028}AVATAR\u{2029}";
+    let sections = [SectionText {
+        text,
+        scale: scaled.scale(),
+        font_id: 0,
+    }];
+    let glyphs =
+        Layout::default().calculate_glyphs(&[&font], &SectionGeometry::default(), &sections);
+
+    let first_line: Vec<_> = glyphs
+        .iter()
+        .filter(|g| g.glyph.position.y == glyphs[0].glyph.position.y)
+        .collect();
+    assert_eq!(first_line.len(), 4);
+    let last = &first_line.last().unwrap().glyph;
+    let layout_width = last.position.x + scaled.h_advance(last.id);
+    assert!((scaled.text_width("Type\u{2028}") - layout_width).abs() < 1e-3);
+
+    let first_baseline = point(0.0, scaled.ascent());
+    let layout_bounds = first_line
+        .iter()
+        .filter_map(|g| font.outline_glyph(g.glyph.clone()))
+        .map(|g| g.px_bounds())
+        .reduce(union);
+    assert_eq!(
+        scaled.text_px_bounds("Type\u{2028}", first_baseline),
+        layout_bounds
+    );
+
+    let (width, height) = scaled.multiline_size(text);
+    assert_eq!(width, scaled.text_width("AVATAR"));
+    // trailing paragraph separator starts a 3rd line
+    assert!((height - (2.0 * scaled.line_height() + scaled.height())).abs() < 1e-3);
+}
+
+#[test]
+fn measure_edge_cases() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let scaled = font.as_scaled(20.0);
+
+    assert_eq!(scaled.text_width(""), 0.0);
+    assert_eq!(scaled.text_width("a\tb"), scaled.text_width("ab"));
+    assert!(scaled.text_width("  ") > 0.0);
+    assert_eq!(scaled.text_px_bounds("", point(0.0, 0.0)), None);
+    assert_eq!(scaled.multiline_px_bounds(" \n ", point(0.0, 0.0)), None);
+
+    assert_eq!(scaled.multiline_size(""), (0.0, scaled.height()));
+    assert_eq!(
+        scaled.multiline_size("ab"),
+        (scaled.text_width("ab"), scaled.height())
+    );
+    assert_eq!(
+        scaled.multiline_size("a\r\nb"),
+        scaled.multiline_size("a\nb")
+    );
+    assert_eq!(
+        scaled.multiline_size("a\u{2028}b").1,
+        scaled.line_height() + scaled.height()
+    );
+    assert_eq!(scaled.line_height(), scaled.height() + scaled.line_gap());
+}
+
+/// Measurement uses the `ScaleFont`'s advances & outlines.
+#[test]
+fn measure_emboldened() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let scaled = font.as_scaled(20.0);
+    let strength = font.units_per_em().unwrap() / 24.0;
+    let bold = scaled.embolden(strength, strength);
+
+    let extra = strength * scaled.h_scale_factor();
+    assert!((bold.text_width("bold") - scaled.text_width("bold") - 4.0 * extra).abs() < 1e-3);
+
+    let bounds = scaled.text_px_bounds("bold", point(0.0, 20.0)).unwrap();
+    let bold_bounds = bold.text_px_bounds("bold", point(0.0, 20.0)).unwrap();
+    assert!(bold_bounds.max.x > bounds.max.x);
+}
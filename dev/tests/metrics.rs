@@ -0,0 +1,111 @@
+use ab_glyph::*;
+
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+const EXO2_TTF: &[u8] = include_bytes!("../fonts/Exo2-Light.ttf");
+const MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+
+fn check_invalid_id<F: Font>(font: F) {
+    let invalid = GlyphId(font.glyph_count() as u16);
+    assert_eq!(font.try_h_advance_unscaled(invalid), None);
+    assert_eq!(font.try_h_side_bearing_unscaled(invalid), None);
+    assert_eq!(font.try_v_advance_unscaled(invalid), None);
+    assert_eq!(font.try_v_side_bearing_unscaled(invalid), None);
+
+    let scaled = font.as_scaled(24.0);
+    assert_eq!(scaled.try_h_advance(invalid), None);
+    assert_eq!(scaled.try_h_side_bearing(invalid), None);
+    assert_eq!(scaled.try_v_advance(invalid), None);
+    assert_eq!(scaled.try_v_side_bearing(invalid), None);
+
+    let bold = scaled.embolden(20.0, 20.0);
+    assert_eq!(bold.try_h_advance(invalid), None);
+    assert_eq!(bold.try_v_advance(invalid), None);
+}
+
+#[test]
+fn invalid_glyph_id_metrics() {
+    let font_ref = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let font_vec = FontVec::try_from_vec(EXO2_TTF.to_vec()).unwrap();
+    let font_arc = FontArc::try_from_slice(MONO).unwrap();
+
+    check_invalid_id(&font_ref);
+    check_invalid_id(&font_vec);
+    check_invalid_id(&font_arc);
+    check_invalid_id(font_ref);
+    check_invalid_id(font_vec);
+    check_invalid_id(font_arc);
+}
+
+#[test]
+fn try_metrics_match_panicking_metrics() {
+    let font = FontRef::try_from_slice(EXO2_TTF).unwrap();
+    let scaled = font.as_scaled(30.0);
+    let bold = scaled.embolden(15.0, 10.0);
+    for c in "Ag .é".chars() {
+        let id = font.glyph_id(c);
+        assert_eq!(
+            font.try_h_advance_unscaled(id),
+            Some(font.h_advance_unscaled(id))
+        );
+        assert_eq!(
+            font.try_h_side_bearing_unscaled(id),
+            Some(font.h_side_bearing_unscaled(id))
+        );
+        assert_eq!(
+            font.try_v_advance_unscaled(id),
+            Some(font.v_advance_unscaled(id))
+        );
+        assert_eq!(
+            font.try_v_side_bearing_unscaled(id),
+            Some(font.v_side_bearing_unscaled(id))
+        );
+
+        assert_eq!(scaled.try_h_advance(id), Some(scaled.h_advance(id)));
+        assert_eq!(
+            scaled.try_h_side_bearing(id),
+            Some(scaled.h_side_bearing(id))
+        );
+        assert_eq!(scaled.try_v_advance(id), Some(scaled.v_advance(id)));
+        assert_eq!(
+            scaled.try_v_side_bearing(id),
+            Some(scaled.v_side_bearing(id))
+        );
+        assert_eq!(bold.try_h_advance(id), Some(bold.h_advance(id)));
+        assert_eq!(bold.try_v_advance(id), Some(bold.v_advance(id)));
+    }
+}
+
+/// None of the test fonts have `vhea`/`vmtx` tables, so vertical metrics are derived.
+#[test]
+fn derived_vertical_metrics() {
+    for data in [EXO2_OTF, EXO2_TTF, MONO] {
+        let font = FontRef::try_from_slice(data).unwrap();
+
+        // the same advance for all glyphs, ascender - descender
+        let advance = font.v_advance_unscaled(font.glyph_id('A'));
+        assert!(advance > 0.0);
+        for c in "a.g ".chars() {
+            assert_eq!(font.v_advance_unscaled(font.glyph_id(c)), advance);
+        }
+
+        // top side bearings place glyph tops relative to the ascender
+        let top = |c| {
+            let id = font.glyph_id(c);
+            let y_max = font.outline(id).unwrap().bounds.min.y;
+            font.v_side_bearing_unscaled(id) + y_max
+        };
+        let ascender = top('A');
+        assert!((top('a') - ascender).abs() < 1e-3);
+        assert!((top('.') - ascender).abs() < 1e-3);
+        assert!(
+            font.v_side_bearing_unscaled(font.glyph_id('A'))
+                < font.v_side_bearing_unscaled(font.glyph_id('.'))
+        );
+
+        // glyphs without outlines have zero top side bearing
+        assert_eq!(
+            font.try_v_side_bearing_unscaled(font.glyph_id(' ')),
+            Some(0.0)
+        );
+    }
+}
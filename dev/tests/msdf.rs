@@ -0,0 +1,183 @@
+use ab_glyph::*;
+use image::RgbImage;
+use std::{env, io::Cursor, path::PathBuf};
+
+const DEJA_VU_MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+
+/// Return target directory accounting for env var `CARGO_TARGET_DIR`.
+fn temp_path(name: impl AsRef<std::path::Path>) -> PathBuf {
+    let mut path = env::var("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("../target"));
+
+    path.push(name);
+    path
+}
+
+fn msdf_image(font: &[u8], c: char, scale: f32, spread: f32, padding: u32) -> RgbImage {
+    let font = FontRef::try_from_slice(font).unwrap();
+    let glyph = font.glyph_id(c).with_scale(scale);
+    let msdf = font.outline_glyph(glyph).unwrap().msdf(spread, padding);
+    assert_eq!(msdf.channels, 3);
+    RgbImage::from_raw(msdf.width, msdf.height, msdf.data).unwrap()
+}
+
+fn compare_image(new_image: &RgbImage, reference_bytes: &[u8]) {
+    let reference = image::load(Cursor::new(reference_bytes), image::ImageFormat::Png)
+        .expect("!image::load")
+        .to_rgb8();
+
+    assert_eq!(reference.dimensions(), new_image.dimensions());
+
+    for (x, y, pixel) in reference.enumerate_pixels() {
+        assert_eq!(
+            pixel,
+            new_image.get_pixel(x, y),
+            "unexpected distance difference at ({}, {})",
+            x,
+            y
+        );
+    }
+}
+
+fn median(a: f32, b: f32, c: f32) -> f32 {
+    a.min(b).max(a.max(b).min(c))
+}
+
+/// Samples a field with bilinear interpolation at pixel coordinates relative to its
+/// `px_bounds`, returning the decoded distance in pixels, positive inside.
+fn sample(field: &DistanceField, x: f32, y: f32, channels: &[usize]) -> Vec<f32> {
+    let x = (x - 0.5).clamp(0.0, field.width as f32 - 1.0);
+    let y = (y - 0.5).clamp(0.0, field.height as f32 - 1.0);
+    let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+    let (x1, y1) = (
+        (x0 + 1).min(field.width - 1),
+        (y0 + 1).min(field.height - 1),
+    );
+    let (fx, fy) = (x.fract(), y.fract());
+
+    let value = |x: u32, y: u32, c: usize| {
+        let idx = (y * field.width + x) as usize * usize::from(field.channels) + c;
+        f32::from(field.data[idx]) / 255.0
+    };
+    channels
+        .iter()
+        .map(|&c| {
+            let top = value(x0, y0, c) * (1.0 - fx) + value(x1, y0, c) * fx;
+            let bottom = value(x0, y1, c) * (1.0 - fx) + value(x1, y1, c) * fx;
+            let v = top * (1.0 - fy) + bottom * fy;
+            (v - 0.5) * 2.0 * field.spread
+        })
+        .collect()
+}
+
+/// Counts pixels of a glyph rendered `upscale` times larger where the inside/outside
+/// reconstruction from a small field disagrees with the rasterized coverage.
+fn reconstruction_errors(
+    font: &FontRef<'_>,
+    c: char,
+    field: impl Fn(&OutlinedGlyph) -> DistanceField,
+    distance: impl Fn(&DistanceField, f32, f32) -> f32,
+) -> usize {
+    const SCALE: f32 = 24.0;
+    const UPSCALE: f32 = 8.0;
+
+    let small = font
+        .outline_glyph(font.glyph_id(c).with_scale(SCALE))
+        .unwrap();
+    let field = field(&small);
+    let large = font
+        .outline_glyph(font.glyph_id(c).with_scale(SCALE * UPSCALE))
+        .unwrap();
+    let large_min = large.px_bounds().min;
+
+    let mut errors = 0;
+    large.draw(|x, y, coverage| {
+        if (0.1..0.9).contains(&coverage) {
+            return;
+        }
+        let px = (large_min.x + x as f32 + 0.5) / UPSCALE - field.px_bounds.min.x;
+        let py = (large_min.y + y as f32 + 0.5) / UPSCALE - field.px_bounds.min.y;
+        if (distance(&field, px, py) > 0.0) != (coverage >= 0.9) {
+            errors += 1;
+        }
+    });
+    errors
+}
+
+#[test]
+fn reference_msdf_otf_m() {
+    let new_image = msdf_image(EXO2_OTF, 'M', 32.0, 3.0, 3);
+    new_image.save(temp_path("new_msdf_otf_m.png")).unwrap();
+    compare_image(&new_image, include_bytes!("reference_msdf_otf_m.png"));
+}
+
+#[test]
+fn reference_msdf_ttf_a() {
+    let new_image = msdf_image(DEJA_VU_MONO, 'A', 32.0, 3.0, 3);
+    new_image.save(temp_path("new_msdf_ttf_a.png")).unwrap();
+    compare_image(&new_image, include_bytes!("reference_msdf_ttf_a.png"));
+}
+
+#[test]
+fn msdf_bounds_and_channels() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let glyph = font
+        .glyph_id('k')
+        .with_scale_and_position(30.0, point(10.3, 40.6));
+    let outlined = font.outline_glyph(glyph.clone()).unwrap();
+    let sdf = outlined.sdf(3.0, 4);
+
+    let msdf = outlined.msdf(3.0, 4);
+    assert_eq!(msdf.channels, 3);
+    assert_eq!(msdf.px_bounds, sdf.px_bounds);
+    assert_eq!((msdf.width, msdf.height), (sdf.width, sdf.height));
+    assert_eq!(msdf.data.len(), (msdf.width * msdf.height * 3) as usize);
+
+    // same result via Outline
+    let outline = font.outline(glyph.id).unwrap();
+    let scale_factor = font.as_scaled(glyph.scale).scale_factor();
+    assert_eq!(outline.msdf(scale_factor, glyph.position, 3.0, 4), msdf);
+
+    let mtsdf = outlined.mtsdf(3.0, 4);
+    assert_eq!(mtsdf.channels, 4);
+    assert_eq!(mtsdf.data.len(), (mtsdf.width * mtsdf.height * 4) as usize);
+    for (idx, px) in mtsdf.data.chunks(4).enumerate() {
+        assert_eq!(px[..3], msdf.data[idx * 3..idx * 3 + 3]);
+        assert_eq!(px[3], sdf.data[idx], "alpha != sdf at {}", idx);
+    }
+}
+
+/// The median of an upscaled msdf should reproduce corners far better than a sdf.
+#[test]
+fn msdf_reconstructs_corners() {
+    let font = FontRef::try_from_slice(DEJA_VU_MONO).unwrap();
+
+    let mut sdf_errors = 0;
+    let mut msdf_errors = 0;
+    for c in "AMWXZkx4E".chars() {
+        sdf_errors += reconstruction_errors(
+            &font,
+            c,
+            |g| g.sdf(3.0, 3),
+            |f, x, y| sample(f, x, y, &[0])[0],
+        );
+        msdf_errors += reconstruction_errors(
+            &font,
+            c,
+            |g| g.msdf(3.0, 3),
+            |f, x, y| {
+                let d = sample(f, x, y, &[0, 1, 2]);
+                median(d[0], d[1], d[2])
+            },
+        );
+    }
+    assert!(sdf_errors > 200, "sdf_errors = {}", sdf_errors);
+    assert!(
+        msdf_errors * 10 < sdf_errors,
+        "msdf_errors = {}, sdf_errors = {}",
+        msdf_errors,
+        sdf_errors
+    );
+}
@@ -1,4 +1,4 @@
-use ab_glyph::{Font, FontRef, ScaleFont};
+use ab_glyph::{Font, FontRef, ScaleFont, VariableFont};
 use ab_glyph_rasterizer::Rasterizer;
 use image::{DynamicImage, LumaA};
 use std::{env, io::Cursor, path::PathBuf};
@@ -143,6 +143,36 @@ fn reference_outline_draw_cantarell_f() {
     );
 }
 
+/// Cantarell variable font instance at the minimum weight.
+#[test]
+fn reference_outline_draw_cantarell_f_wght_100() {
+    let mut font = FontRef::try_from_slice(CANTARELL_VF).unwrap();
+    assert!(font.set_variation(b"wght", 100.0));
+    let new_image = outline_draw(font, 'f', 300.0);
+    new_image
+        .save(temp_path("new_outlined_cantarell_f_wght_100.png"))
+        .unwrap();
+    compare_image!(
+        new_image,
+        include_bytes!("reference_outlined_cantarell_f_wght_100.png")
+    );
+}
+
+/// Cantarell variable font instance at the maximum weight.
+#[test]
+fn reference_outline_draw_cantarell_f_wght_800() {
+    let mut font = FontRef::try_from_slice(CANTARELL_VF).unwrap();
+    assert!(font.set_variation(b"wght", 800.0));
+    let new_image = outline_draw(font, 'f', 300.0);
+    new_image
+        .save(temp_path("new_outlined_cantarell_f_wght_800.png"))
+        .unwrap();
+    compare_image!(
+        new_image,
+        include_bytes!("reference_outlined_cantarell_f_wght_800.png")
+    );
+}
+
 fn outline_draw<F: Font>(font: F, c: char, scale: f32) -> image::GrayAlphaImage {
     let font = font.into_scaled(scale);
 
@@ -0,0 +1,108 @@
+use ab_glyph::*;
+use image::GrayImage;
+use std::{env, io::Cursor, path::PathBuf};
+
+const DEJA_VU_MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+
+/// Return target directory accounting for env var `CARGO_TARGET_DIR`.
+fn temp_path(name: impl AsRef<std::path::Path>) -> PathBuf {
+    let mut path = env::var("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("../target"));
+
+    path.push(name);
+    path
+}
+
+fn sdf_image(font: &[u8], c: char, scale: f32, spread: f32, padding: u32) -> GrayImage {
+    let font = FontRef::try_from_slice(font).unwrap();
+    let glyph = font.glyph_id(c).with_scale(scale);
+    let sdf = font.outline_glyph(glyph).unwrap().sdf(spread, padding);
+    assert_eq!(sdf.channels, 1);
+    GrayImage::from_raw(sdf.width, sdf.height, sdf.data).unwrap()
+}
+
+fn compare_image(new_image: &GrayImage, reference_bytes: &[u8]) {
+    let reference = image::load(Cursor::new(reference_bytes), image::ImageFormat::Png)
+        .expect("!image::load")
+        .to_luma8();
+
+    assert_eq!(reference.dimensions(), new_image.dimensions());
+
+    for (x, y, pixel) in reference.enumerate_pixels() {
+        assert_eq!(
+            pixel,
+            new_image.get_pixel(x, y),
+            "unexpected distance difference at ({}, {})",
+            x,
+            y
+        );
+    }
+}
+
+#[test]
+fn reference_sdf_otf_a() {
+    let new_image = sdf_image(EXO2_OTF, 'a', 64.0, 6.0, 6);
+    new_image.save(temp_path("new_sdf_otf_a.png")).unwrap();
+    compare_image(&new_image, include_bytes!("reference_sdf_otf_a.png"));
+}
+
+#[test]
+fn reference_sdf_ttf_at() {
+    let new_image = sdf_image(DEJA_VU_MONO, '@', 48.0, 4.0, 5);
+    new_image.save(temp_path("new_sdf_ttf_at.png")).unwrap();
+    compare_image(&new_image, include_bytes!("reference_sdf_ttf_at.png"));
+}
+
+#[test]
+fn sdf_bounds_and_padding() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let glyph = font
+        .glyph_id('w')
+        .with_scale_and_position(30.0, point(10.3, 40.6));
+    let outlined = font.outline_glyph(glyph.clone()).unwrap();
+    let bounds = outlined.px_bounds();
+
+    let sdf = outlined.sdf(3.0, 4);
+    assert_eq!(sdf.px_bounds.min, bounds.min - point(4.0, 4.0));
+    assert_eq!(sdf.px_bounds.max, bounds.max + point(4.0, 4.0));
+    assert_eq!(sdf.width as f32, bounds.width() + 8.0);
+    assert_eq!(sdf.height as f32, bounds.height() + 8.0);
+    assert_eq!(sdf.data.len(), (sdf.width * sdf.height) as usize);
+    assert_eq!(sdf.spread, 3.0);
+
+    // same result via Outline
+    let outline = font.outline(glyph.id).unwrap();
+    let scale_factor = font.as_scaled(glyph.scale).scale_factor();
+    assert_eq!(outline.sdf(scale_factor, glyph.position, 3.0, 4), sdf);
+
+    // corners are more than 3px from the outline
+    assert_eq!(sdf.data[0], 0);
+    assert_eq!(*sdf.data.last().unwrap(), 0);
+}
+
+/// Pixels fully covered are inside, uncovered are outside & partially covered are near
+/// the edge.
+#[test]
+fn sdf_matches_coverage() {
+    let font = FontRef::try_from_slice(DEJA_VU_MONO).unwrap();
+    let glyph = font.glyph_id('%').with_scale(40.0);
+    let outlined = font.outline_glyph(glyph).unwrap();
+    let padding = 2;
+    let sdf = outlined.sdf(2.0, padding);
+
+    let mut checked = 0;
+    outlined.draw(|x, y, coverage| {
+        let idx = (y + padding) * sdf.width + x + padding;
+        let value = sdf.data[idx as usize];
+        match coverage {
+            c if c > 0.99 => assert!(value > 128, "({}, {}) {}", x, y, value),
+            c if c < 0.01 => assert!(value < 128, "({}, {}) {}", x, y, value),
+            // a partially covered pixel center is within ~0.71px of the edge
+            _ => assert!((35..=220).contains(&value), "({}, {}) {}", x, y, value),
+        }
+        checked += 1;
+    });
+    assert!(checked > 0);
+}
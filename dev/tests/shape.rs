@@ -0,0 +1,176 @@
+use ab_glyph::*;
+
+const LAYOUT: &[u8] = include_bytes!("../fonts/layout.ttf");
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+const OPENS_SANS_ITALIC: &[u8] = include_bytes!("../fonts/OpenSans-Italic.ttf");
+
+// layout.ttf glyph ids
+const F: GlyphId = GlyphId(1);
+const I: GlyphId = GlyphId(2);
+const F_I: GlyphId = GlyphId(3);
+const A: GlyphId = GlyphId(4);
+const ACUTE: GlyphId = GlyphId(5);
+const B: GlyphId = GlyphId(6);
+const B_ALT: GlyphId = GlyphId(7);
+const K: GlyphId = GlyphId(9);
+const X: GlyphId = GlyphId(11);
+const Y: GlyphId = GlyphId(12);
+const CAP_A: GlyphId = GlyphId(13);
+const CAP_V: GlyphId = GlyphId(14);
+
+fn ids(shaped: &[ShapedGlyph]) -> Vec<GlyphId> {
+    shaped.iter().map(|g| g.id).collect()
+}
+
+fn clusters(shaped: &[ShapedGlyph]) -> Vec<usize> {
+    shaped.iter().map(|g| g.cluster).collect()
+}
+
+#[test]
+fn gsub_ligature() {
+    let font = FontRef::try_from_slice(LAYOUT).unwrap();
+
+    let shaped = font.shape("afib", &[], b"latn", None);
+    assert_eq!(ids(&shaped), [A, F_I, B]);
+    assert_eq!(clusters(&shaped), [0, 1, 3]);
+    assert_eq!(shaped[1].x_advance, 900.0);
+
+    let shaped = font.shape("afib", &[Feature::off(b"liga")], b"latn", None);
+    assert_eq!(ids(&shaped), [A, F, I, B]);
+    assert_eq!(clusters(&shaped), [0, 1, 2, 3]);
+}
+
+#[test]
+fn gsub_multiple() {
+    let font = FontRef::try_from_slice(LAYOUT).unwrap();
+
+    let shaped = font.shape("bcb", &[], b"latn", None);
+    assert_eq!(ids(&shaped), [B, A, A, B]);
+    assert_eq!(clusters(&shaped), [0, 1, 1, 2]);
+}
+
+#[test]
+fn gsub_single() {
+    let font = FontRef::try_from_slice(LAYOUT).unwrap();
+
+    // not default features
+    assert_eq!(ids(&font.shape("bx", &[], b"latn", None)), [B, X]);
+
+    let shaped = font.shape("bx", &[Feature::on(b"ss01")], b"latn", None);
+    assert_eq!(ids(&shaped), [B_ALT, X]);
+
+    let shaped = font.shape("bx", &[Feature::on(b"ss02")], b"latn", None);
+    assert_eq!(ids(&shaped), [B, Y]);
+
+    // later settings take precedence
+    let features = [Feature::on(b"ss01"), Feature::off(b"ss01")];
+    assert_eq!(ids(&font.shape("bx", &features, b"latn", None)), [B, X]);
+}
+
+/// Substitutions to glyph ids missing from the font shouldn't panic.
+#[test]
+fn gsub_single_missing_glyph() {
+    let font = FontRef::try_from_slice(LAYOUT).unwrap();
+
+    let shaped = font.shape("kk", &[Feature::on(b"ss03")], b"latn", None);
+    assert_eq!(ids(&shaped), [GlyphId(999), GlyphId(999)]);
+    assert_eq!(shaped[0].x_advance, 0.0);
+}
+
+#[test]
+fn script_and_language() {
+    let font = FontRef::try_from_slice(LAYOUT).unwrap();
+
+    // TRK language system has no liga
+    assert_eq!(ids(&font.shape("fi", &[], b"latn", Some(b"TRK "))), [F, I]);
+    // unknown languages use the default language system
+    assert_eq!(ids(&font.shape("fi", &[], b"latn", Some(b"DEU "))), [F_I]);
+    // unknown scripts fall back to DFLT
+    assert_eq!(ids(&font.shape("fi", &[], b"cyrl", Some(b"TRK "))), [F_I]);
+}
+
+#[test]
+fn gpos_pair_kerning() {
+    let font = FontRef::try_from_slice(LAYOUT).unwrap();
+
+    // format 1 A V
+    let shaped = font.shape("AV", &[], b"latn", None);
+    assert_eq!(ids(&shaped), [CAP_A, CAP_V]);
+    assert_eq!(shaped[0].x_advance, 420.0);
+    assert_eq!(shaped[1].x_advance, 500.0);
+
+    // format 2 V A, also adjusting the second glyph
+    let shaped = font.shape("VA", &[], b"latn", None);
+    assert_eq!(shaped[0].x_advance, 440.0);
+    assert_eq!(shaped[1].x_advance, 500.0);
+    assert_eq!(shaped[1].y_offset, 10.0);
+
+    // both lookups apply
+    let shaped = font.shape("VAV", &[], b"latn", None);
+    assert_eq!(shaped[0].x_advance, 440.0);
+    assert_eq!(shaped[1].x_advance, 420.0);
+    assert_eq!(shaped[1].y_offset, 10.0);
+
+    let shaped = font.shape("AV", &[Feature::off(b"kern")], b"latn", None);
+    assert_eq!(shaped[0].x_advance, 500.0);
+}
+
+#[test]
+fn gpos_mark_to_base() {
+    let font = FontRef::try_from_slice(LAYOUT).unwrap();
+
+    let shaped = font.shape("a\u{301}b", &[], b"latn", None);
+    assert_eq!(ids(&shaped), [A, ACUTE, B]);
+    assert_eq!(clusters(&shaped), [0, 1, 3]);
+    assert_eq!(shaped[0].x_advance, 500.0);
+    // anchor (250, 500) relative to the pen after 'a'
+    assert_eq!(shaped[1].x_offset, 250.0 - 100.0 - 500.0);
+    assert_eq!(shaped[1].y_offset, 500.0);
+    assert_eq!(shaped[1].x_advance, 0.0);
+
+    // unattached marks keep their advance
+    let shaped = font.shape("a\u{301}", &[Feature::off(b"mark")], b"latn", None);
+    assert_eq!((shaped[1].x_offset, shaped[1].x_advance), (0.0, 200.0));
+}
+
+#[test]
+fn gpos_cursive() {
+    let font = FontRef::try_from_slice(LAYOUT).unwrap();
+
+    let shaped = font.shape("kkk", &[], b"latn", None);
+    assert_eq!(ids(&shaped), [K, K, K]);
+    // exit (500, 200) joins the next entry (0, 100)
+    for (idx, g) in shaped.iter().enumerate() {
+        assert_eq!(g.x_advance, 500.0);
+        assert_eq!(g.x_offset, 0.0);
+        assert_eq!(g.y_offset, 100.0 * idx as f32);
+    }
+}
+
+#[test]
+fn exo2_ligature_and_kerning() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+
+    let shaped = font.shape("fit", &[], b"latn", None);
+    assert_eq!(shaped.len(), 2);
+    assert_eq!(clusters(&shaped), [0, 2]);
+
+    // kerning using GPOS
+    let av = font.shape("AV", &[], b"latn", None);
+    let a_advance = font.h_advance_unscaled(font.glyph_id('A'));
+    assert!(av[0].x_advance < a_advance, "{:?}", av);
+
+    let av = font.shape("AV", &[Feature::off(b"kern")], b"latn", None);
+    assert_eq!(av[0].x_advance, a_advance);
+}
+
+#[test]
+fn legacy_kern_fallback() {
+    let font = FontRef::try_from_slice(OPENS_SANS_ITALIC).unwrap();
+    let (a, v) = (font.glyph_id('A'), font.glyph_id('V'));
+    let kern = font.kern_unscaled(a, v);
+    assert!(kern < 0.0);
+
+    let shaped = font.shape("AV", &[], b"latn", None);
+    assert_eq!(shaped[0].x_advance, font.h_advance_unscaled(a) + kern);
+}
@@ -0,0 +1,283 @@
+use ab_glyph_rasterizer::*;
+
+/// Distance from `p` to the line segment `a`-`b`.
+fn segment_distance(p: Point, a: Point, b: Point) -> f32 {
+    let (ab, ap) = (b - a, p - a);
+    let len_sq = ab.x * ab.x + ab.y * ab.y;
+    let t = match len_sq {
+        l if l > 0.0 => ((ap.x * ab.x + ap.y * ab.y) / l).clamp(0.0, 1.0),
+        _ => 0.0,
+    };
+    let d = ap - point(ab.x * t, ab.y * t);
+    (d.x * d.x + d.y * d.y).sqrt()
+}
+
+fn coverage(stroker: &Stroker, width: usize, height: usize) -> Vec<f32> {
+    let mut rasterizer = Rasterizer::new(width, height);
+    stroker.draw(&mut rasterizer);
+    let mut pixels = Vec::new();
+    rasterizer.for_each_pixel(|_, a| pixels.push(a.min(1.0)));
+    pixels
+}
+
+/// Round joins & caps stroke exactly the points within half the width of the path.
+fn assert_round_stroke(polyline: &[Point], closed: bool, width: f32) {
+    let stroke = Stroke {
+        join: LineJoin::Round,
+        cap: LineCap::Round,
+        ..Stroke::new(width)
+    };
+    let mut stroker = Stroker::new(stroke);
+    for w in polyline.windows(2) {
+        stroker.draw_line(w[0], w[1]);
+    }
+    if closed {
+        stroker.close();
+    }
+
+    let mut segments: Vec<_> = polyline.windows(2).map(|w| (w[0], w[1])).collect();
+    if closed {
+        segments.push((polyline[polyline.len() - 1], polyline[0]));
+    }
+
+    let pixels = coverage(&stroker, 40, 40);
+    for (idx, &c) in pixels.iter().enumerate() {
+        let center = point((idx % 40) as f32 + 0.5, (idx / 40) as f32 + 0.5);
+        let distance = segments
+            .iter()
+            .map(|&(a, b)| segment_distance(center, a, b))
+            .fold(f32::MAX, f32::min);
+        if distance < width / 2.0 - 0.75 {
+            assert!(
+                c > 0.999,
+                "{:?} distance {} coverage {}",
+                center,
+                distance,
+                c
+            );
+        } else if distance > width / 2.0 + 0.75 {
+            assert!(
+                c < 0.001,
+                "{:?} distance {} coverage {}",
+                center,
+                distance,
+                c
+            );
+        }
+    }
+}
+
+#[test]
+fn round_stroke_open() {
+    let path = [
+        point(5.0, 5.0),
+        point(30.0, 8.0),
+        point(10.0, 20.0),
+        point(35.0, 35.0),
+        point(33.0, 10.0),
+    ];
+    assert_round_stroke(&path, false, 3.0);
+    assert_round_stroke(&path, false, 7.0);
+}
+
+#[test]
+fn round_stroke_closed() {
+    let path = [
+        point(8.0, 8.0),
+        point(32.0, 6.0),
+        point(20.0, 18.0),
+        point(30.0, 32.0),
+        point(6.0, 30.0),
+    ];
+    assert_round_stroke(&path, true, 2.0);
+    assert_round_stroke(&path, true, 6.5);
+}
+
+/// Reversals & very short segments shouldn't leave gaps.
+#[test]
+fn round_stroke_sharp_turns() {
+    let path = [
+        point(5.0, 20.0),
+        point(35.0, 20.0),
+        point(10.0, 20.5),
+        point(10.2, 20.7),
+        point(20.0, 5.0),
+    ];
+    assert_round_stroke(&path, false, 4.0);
+}
+
+/// A closed rectangle with miter joins fills the square ring exactly.
+#[test]
+fn miter_rectangle() {
+    let mut stroker = Stroker::new(Stroke::new(2.0));
+    let corners = [
+        point(4.0, 4.0),
+        point(16.0, 4.0),
+        point(16.0, 12.0),
+        point(4.0, 12.0),
+    ];
+    for idx in 0..4 {
+        stroker.draw_line(corners[idx], corners[(idx + 1) % 4]);
+    }
+
+    let pixels = coverage(&stroker, 20, 16);
+    for (idx, &c) in pixels.iter().enumerate() {
+        let (x, y) = (idx % 20, idx / 20);
+        let outer = (3..17).contains(&x) && (3..13).contains(&y);
+        let inner = (5..15).contains(&x) && (5..11).contains(&y);
+        let expected = if outer && !inner { 1.0 } else { 0.0 };
+        assert!((c - expected).abs() < 1e-4, "({}, {}) {}", x, y, c);
+    }
+}
+
+/// Miters are limited & bevel joins cut corners.
+#[test]
+fn join_styles() {
+    // acute corner at (30, 20)
+    let draw = |join| {
+        let mut stroker = Stroker::new(Stroke {
+            join,
+            miter_limit: 10.0,
+            ..Stroke::new(4.0)
+        });
+        stroker.draw_line(point(2.0, 12.0), point(30.0, 20.0));
+        stroker.draw_line(point(30.0, 20.0), point(2.0, 28.0));
+        coverage(&stroker, 40, 40).iter().sum::<f32>()
+    };
+    let (miter, round, bevel) = (
+        draw(LineJoin::Miter),
+        draw(LineJoin::Round),
+        draw(LineJoin::Bevel),
+    );
+    assert!(miter > round, "{} > {}", miter, round);
+    assert!(round > bevel, "{} > {}", round, bevel);
+
+    // miter is beyond limit of 2 so falls back to bevel
+    let mut limited = Stroker::new(Stroke {
+        miter_limit: 2.0,
+        ..Stroke::new(4.0)
+    });
+    limited.draw_line(point(2.0, 12.0), point(30.0, 20.0));
+    limited.draw_line(point(30.0, 20.0), point(2.0, 28.0));
+    let limited = coverage(&limited, 40, 40).iter().sum::<f32>();
+    assert!((limited - bevel).abs() < 1e-3, "{} != {}", limited, bevel);
+}
+
+#[test]
+fn cap_styles() {
+    let draw = |cap| {
+        let mut stroker = Stroker::new(Stroke {
+            cap,
+            ..Stroke::new(4.0)
+        });
+        stroker.draw_line(point(10.0, 10.0), point(20.0, 10.0));
+        coverage(&stroker, 30, 20)
+    };
+    let area = |pixels: &[f32]| pixels.iter().sum::<f32>();
+
+    let butt = draw(LineCap::Butt);
+    assert!((area(&butt) - 40.0).abs() < 1e-3, "{}", area(&butt));
+    let square = draw(LineCap::Square);
+    assert!((area(&square) - 56.0).abs() < 1e-3, "{}", area(&square));
+    let round = draw(LineCap::Round);
+    let round_area = 40.0 + core::f32::consts::PI * 4.0;
+    assert!((area(&round) - round_area).abs() < 0.5, "{}", area(&round));
+
+    // a zero length line draws a dot
+    let mut dot = Stroker::new(Stroke {
+        cap: LineCap::Round,
+        ..Stroke::new(4.0)
+    });
+    dot.draw_line(point(10.0, 10.0), point(10.0, 10.0));
+    let dot_area = area(&coverage(&dot, 20, 20));
+    assert!(
+        (dot_area - core::f32::consts::PI * 4.0).abs() < 0.5,
+        "{}",
+        dot_area
+    );
+}
+
+/// Curves are stroked at a constant width.
+#[test]
+fn curve_stroke() {
+    let center = point(20.0, 20.0);
+    // rounded shape of 4 quads
+    let on = [(12.0, 0.0), (0.0, 12.0), (-12.0, 0.0), (0.0, -12.0)];
+    let off = [(12.0, 12.0), (-12.0, 12.0), (-12.0, -12.0), (12.0, -12.0)];
+    let p = |(x, y): (f32, f32)| center + point(x, y);
+    let quad = |idx: usize| (p(on[idx]), p(off[idx]), p(on[(idx + 1) % 4]));
+
+    let mut stroker = Stroker::new(Stroke::new(3.0));
+    let mut perimeter = 0.0;
+    for idx in 0..4 {
+        let (p0, p1, p2) = quad(idx);
+        stroker.draw_quad(p0, p1, p2);
+
+        let at = |t: f32| {
+            let a = p0 + point((p1.x - p0.x) * t, (p1.y - p0.y) * t);
+            let b = p1 + point((p2.x - p1.x) * t, (p2.y - p1.y) * t);
+            a + point((b.x - a.x) * t, (b.y - a.y) * t)
+        };
+        for n in 0..1000 {
+            let d = at((n + 1) as f32 / 1000.0) - at(n as f32 / 1000.0);
+            perimeter += (d.x * d.x + d.y * d.y).sqrt();
+        }
+    }
+
+    let pixels = coverage(&stroker, 40, 40);
+    let area: f32 = pixels.iter().sum();
+    // offset curves of a closed convex path enclose a ring of perimeter * width
+    let ring = perimeter * 3.0;
+    assert!((area - ring).abs() / ring < 0.005, "{} vs {}", area, ring);
+    // center is not filled
+    assert_eq!(pixels[20 * 40 + 20], 0.0);
+}
+
+fn glyph_coverage(outlined: &ab_glyph::OutlinedGlyph) -> Vec<f32> {
+    let bounds = outlined.px_bounds();
+    let width = bounds.width() as usize;
+    let mut pixels = vec![0.0; width * bounds.height() as usize];
+    outlined.draw(|x, y, c| pixels[y as usize * width + x as usize] = c.min(1.0));
+    pixels
+}
+
+/// Stroking a glyph draws its contours hollow.
+#[test]
+fn stroked_glyph() {
+    use ab_glyph::{Font, FontRef};
+
+    let font = FontRef::try_from_slice(include_bytes!("../fonts/Exo2-Light.otf")).unwrap();
+    let glyph = font.glyph_id('I').with_scale(60.0);
+    let outlined = font.outline_glyph(glyph).unwrap();
+
+    let stroke = Stroke::new(1.0);
+    let stroked = outlined.stroked(&stroke);
+    let (bounds, stroked_bounds) = (outlined.px_bounds(), stroked.px_bounds());
+    assert!(stroked_bounds.min.x <= bounds.min.x && stroked_bounds.min.y <= bounds.min.y);
+    assert!(stroked_bounds.max.x >= bounds.max.x && stroked_bounds.max.y >= bounds.max.y);
+    assert!(stroked_bounds.width() <= bounds.width() + 2.0 * stroke.extent().ceil());
+
+    // the filled stem is wider than 2 strokes so its center is hollow
+    let filled = glyph_coverage(&outlined);
+    let hollow = glyph_coverage(&stroked);
+    let center = |pixels: &[f32], b: ab_glyph::Rect| {
+        let width = b.width() as usize;
+        pixels[(b.height() as usize / 2) * width + width / 2]
+    };
+    assert_eq!(center(&filled, bounds), 1.0);
+    assert_eq!(center(&hollow, stroked_bounds), 0.0);
+
+    // a 1px stroke of the rectangular 'I' covers around its perimeter in pixels
+    let perimeter = 2.0 * (bounds.width() + bounds.height());
+    let area: f32 = hollow.iter().sum();
+    assert!(
+        (area - perimeter).abs() / perimeter < 0.1,
+        "{} {}",
+        area,
+        perimeter
+    );
+
+    let mut drawn = 0.0;
+    outlined.draw_stroked(&stroke, |_, _, c| drawn += c.min(1.0));
+    assert!((drawn - area).abs() < 1e-3);
+}
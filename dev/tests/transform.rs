@@ -0,0 +1,163 @@
+use ab_glyph::*;
+use std::f32::consts::{FRAC_PI_2, PI};
+
+const EXO2_OTF: &[u8] = include_bytes!("../fonts/Exo2-Light.otf");
+const EXO2_TTF: &[u8] = include_bytes!("../fonts/Exo2-Light.ttf");
+
+/// Draws into a full `px_bounds` sized buffer of coverage.
+fn draw(outlined: &OutlinedGlyph) -> (Rect, Vec<f32>) {
+    let bounds = outlined.px_bounds();
+    let width = bounds.width() as usize;
+    let mut pixels = vec![0.0; width * bounds.height() as usize];
+    outlined.draw(|x, y, c| pixels[x as usize + y as usize * width] = c);
+    (bounds, pixels)
+}
+
+fn assert_transform_eq(a: Transform, b: Transform) {
+    let (a, b): ([f32; 6], [f32; 6]) = (a.into(), b.into());
+    for (a, b) in a.iter().zip(&b) {
+        assert!((a - b).abs() < 1e-5, "{:?} != {:?}", a, b);
+    }
+}
+
+#[test]
+fn transform_compose_invert() {
+    let t = Transform::rotate(0.7)
+        .then(Transform::skew(0.2, -0.1))
+        .then(Transform::scale(2.0, -3.0))
+        .then(Transform::translate(5.0, 7.0));
+
+    assert_transform_eq(t.then(t.invert().unwrap()), Transform::IDENTITY);
+    assert_transform_eq(t.invert().unwrap() * t, Transform::IDENTITY);
+
+    let p = point(3.0, -4.0);
+    let q = t.invert().unwrap().apply(t.apply(p));
+    assert!((q.x - p.x).abs() < 1e-4 && (q.y - p.y).abs() < 1e-4);
+
+    assert_eq!(Transform::scale(0.0, 1.0).invert(), None);
+    assert_eq!(Transform::default(), Transform::IDENTITY);
+    assert_eq!(
+        Transform::from([1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).apply(point(1.0, 1.0)),
+        point(9.0, 12.0)
+    );
+}
+
+#[test]
+fn with_transform_identity() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let glyph = font
+        .glyph_id('a')
+        .with_scale_and_position(30.0, point(10.3, 40.6));
+    let outlined = font.outline_glyph(glyph).unwrap();
+
+    let same = outlined.with_transform(&Transform::IDENTITY);
+    assert_eq!(same.px_bounds(), outlined.px_bounds());
+    assert_eq!(draw(&same), draw(&outlined));
+}
+
+/// Horizontally mirrored glyphs draw exactly mirrored pixels about the position.
+#[test]
+fn with_transform_mirror() {
+    for data in [EXO2_OTF, EXO2_TTF] {
+        let font = FontRef::try_from_slice(data).unwrap();
+        let glyph = font
+            .glyph_id('R')
+            .with_scale_and_position(40.0, point(50.0, 60.0));
+        let outlined = font.outline_glyph(glyph).unwrap();
+
+        let mirrored = outlined.with_transform(&Transform::scale(-1.0, 1.0));
+        let (bounds, pixels) = draw(&outlined);
+        let (m_bounds, m_pixels) = draw(&mirrored);
+
+        assert_eq!(m_bounds.min.x, 100.0 - bounds.max.x);
+        assert_eq!(m_bounds.max.x, 100.0 - bounds.min.x);
+        assert_eq!(
+            (m_bounds.min.y, m_bounds.max.y),
+            (bounds.min.y, bounds.max.y)
+        );
+
+        let width = bounds.width() as usize;
+        for (row, m_row) in pixels.chunks(width).zip(m_pixels.chunks(width)) {
+            for (c, m_c) in row.iter().zip(m_row.iter().rev()) {
+                assert!((c - m_c).abs() < 1e-4, "{} != {}", c, m_c);
+            }
+        }
+    }
+}
+
+/// Skewing leans the glyph right, keeping the baseline & ink area.
+#[test]
+fn with_transform_oblique() {
+    let font = FontRef::try_from_slice(EXO2_TTF).unwrap();
+    let glyph = font
+        .glyph_id('H')
+        .with_scale_and_position(48.0, point(20.0, 60.0));
+    let outlined = font.outline_glyph(glyph).unwrap();
+    let italic = outlined.with_transform(&Transform::skew(12_f32.to_radians(), 0.0));
+
+    let (bounds, pixels) = draw(&outlined);
+    let (i_bounds, i_pixels) = draw(&italic);
+    assert_eq!(i_bounds.max.y, bounds.max.y);
+    assert_eq!(i_bounds.min.x, bounds.min.x);
+    assert!(i_bounds.max.x > bounds.max.x);
+
+    let (area, i_area) = (pixels.iter().sum::<f32>(), i_pixels.iter().sum::<f32>());
+    assert!(
+        (area - i_area).abs() < area * 0.01,
+        "{} vs {}",
+        area,
+        i_area
+    );
+
+    // the bottom row is unshifted, the top row is shifted right
+    let first_ink = |pixels: &[f32], bounds: Rect, row: usize| {
+        let width = bounds.width() as usize;
+        let x = pixels[row * width..][..width].iter().position(|c| *c > 0.5);
+        bounds.min.x + x.unwrap() as f32
+    };
+    let (h, i_h) = (bounds.height() as usize, i_bounds.height() as usize);
+    assert_eq!(
+        first_ink(&i_pixels, i_bounds, i_h - 2),
+        first_ink(&pixels, bounds, h - 2)
+    );
+    let (top, i_top) = (
+        first_ink(&pixels, bounds, 1),
+        first_ink(&i_pixels, i_bounds, 1),
+    );
+    assert!(i_top >= top + 5.0, "{} vs {}", i_top, top);
+}
+
+/// Rotations are in pixels about the glyph position, regardless of the scale.
+#[test]
+fn with_transform_rotate() {
+    let font = FontRef::try_from_slice(EXO2_OTF).unwrap();
+    let position = point(100.0, 100.0);
+    let glyph = font
+        .glyph_id('T')
+        .with_scale_and_position(PxScale { x: 60.0, y: 30.0 }, position);
+    let outlined = font.outline_glyph(glyph).unwrap();
+    let bounds = outlined.px_bounds();
+
+    // half turn, mirrored through the position
+    let half = outlined.with_transform(&Transform::rotate(PI)).px_bounds();
+    assert!((half.min.x - (200.0 - bounds.max.x)).abs() <= 1.0);
+    assert!((half.max.y - (200.0 - bounds.min.y)).abs() <= 1.0);
+
+    // quarter turn counter-clockwise, swapping the pixel width & height
+    let quarter = outlined.with_transform(&Transform::rotate(FRAC_PI_2));
+    let q_bounds = quarter.px_bounds();
+    assert!((q_bounds.width() - bounds.height()).abs() <= 1.0);
+    assert!((q_bounds.height() - bounds.width()).abs() <= 1.0);
+    // glyph above the baseline now lies left of the position
+    assert!(q_bounds.max.x <= position.x + 1.0);
+
+    let (_, pixels) = draw(&outlined);
+    let (_, q_pixels) = draw(&quarter);
+    let (area, q_area) = (pixels.iter().sum::<f32>(), q_pixels.iter().sum::<f32>());
+    assert!(
+        (area - q_area).abs() < area * 0.01,
+        "{} vs {}",
+        area,
+        q_area
+    );
+}
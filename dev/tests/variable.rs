@@ -0,0 +1,27 @@
+use ab_glyph::*;
+use approx::assert_relative_eq;
+
+const CANTARELL_VF: &[u8] = include_bytes!("../fonts/Cantarell-VF.otf");
+
+/// Advances & outlines should reflect the current variation instance.
+#[test]
+fn variation_affects_advance_and_outline() {
+    let mut font = FontRef::try_from_slice(CANTARELL_VF).unwrap();
+    let f = font.glyph_id('f');
+
+    assert_relative_eq!(font.h_advance_unscaled(f), 340.0);
+    assert_relative_eq!(font.outline(f).unwrap().bounds.max.x, 365.0);
+
+    assert!(font.set_variation(b"wght", 800.0));
+    assert_relative_eq!(font.h_advance_unscaled(f), 406.0);
+    assert_relative_eq!(font.outline(f).unwrap().bounds.max.x, 436.0);
+
+    // font-wide metrics are unaffected without MVAR deltas
+    assert_relative_eq!(font.ascent_unscaled(), 983.0);
+    assert_relative_eq!(font.descent_unscaled(), -217.0);
+
+    // out of range values are clamped to the axis max
+    let mut clamped = FontVec::try_from_vec(CANTARELL_VF.to_vec()).unwrap();
+    assert!(clamped.set_variation(b"wght", 2000.0));
+    assert_relative_eq!(clamped.h_advance_unscaled(f), 406.0);
+}
@@ -0,0 +1,389 @@
+//! Light auto-hinting, snapping horizontal outline edges to the vertical pixel grid.
+#[cfg(all(feature = "libm", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+use crate::{float_cmp::total_cmp, point, Font, Outline, OutlineCurve, Point, PxScaleFactor, Rect};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Segments within this many pixels vertically are merged into a single edge.
+const EDGE_THRESHOLD: f32 = 0.25;
+/// Edges within this many pixels of a blue zone are aligned to it.
+const BLUE_THRESHOLD: f32 = 0.5;
+
+/// Reference characters used to measure blue zones: flat, round & whether the zone
+/// aligns glyph tops or bottoms.
+const BLUE_CHARS: [(&str, &str, bool); 6] = [
+    // capital height
+    ("THEZ", "OCQS", true),
+    // capital baseline
+    ("HEZL", "OCUS", false),
+    // x-height
+    ("xzr", "oesc", true),
+    // small baseline
+    ("xzr", "oesc", false),
+    // ascender
+    ("bdhkl", "", true),
+    // descender
+    ("pq", "", false),
+];
+
+/// A vertical alignment zone in unscaled font units, such as the baseline, x-height or
+/// cap height.
+///
+/// Flat edges near the `reference` & round edges near the `overshoot` are aligned together,
+/// suppressing the overshoot at small sizes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlueZone {
+    /// Position of flat edges, e.g. the top of _x_ for the x-height.
+    pub reference: f32,
+    /// Position of round edges overshooting the reference, e.g. the top of _o_ for the x-height.
+    pub overshoot: f32,
+}
+
+/// A FreeType "light" style auto-hinter that snaps horizontal stems & blue zone edges to
+/// the vertical pixel grid, leaving horizontal metrics untouched.
+///
+/// This sharpens glyphs of fonts without hinting instructions, including all CFF fonts.
+/// See [`OutlinedGlyph::autohinted`](crate::OutlinedGlyph::autohinted) &
+/// [`Outline::autohint`].
+///
+/// The `Default` hinter has no blue zones & only aligns stems.
+///
+/// # Example
+/// ```
+/// # use ab_glyph::*;
+/// # let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf")).unwrap();
+/// let hinter = AutoHinter::new(&font);
+///
+/// let glyph = font.glyph_id('x').with_scale(16.0);
+/// let outlined = font.outline_glyph(glyph).unwrap();
+///
+/// // choose per draw whether to hint
+/// outlined.autohinted(&hinter).draw(|x, y, c| { /* draw pixel `(x, y)` with coverage: `c` */ });
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AutoHinter {
+    /// Alignment zones, see [`BlueZone`].
+    pub blue_zones: Vec<BlueZone>,
+}
+
+impl AutoHinter {
+    /// Creates a hinter measuring the font's blue zones from the outlines of latin
+    /// reference characters, e.g. _x_ & _o_ for the x-height.
+    pub fn new<F: Font>(font: F) -> Self {
+        let blue_zones = BLUE_CHARS
+            .iter()
+            .filter_map(|&(flat, round, top)| {
+                let reference = average_extreme(&font, flat, top)?;
+                let overshoot = average_extreme(&font, round, top).unwrap_or(reference);
+                Some(BlueZone {
+                    reference,
+                    overshoot,
+                })
+            })
+            .collect();
+        Self { blue_zones }
+    }
+}
+
+/// Returns the average top or bottom y of the outlines of `chars` available in the font.
+fn average_extreme<F: Font>(font: &F, chars: &str, top: bool) -> Option<f32> {
+    let (sum, count) = chars
+        .chars()
+        .filter_map(|c| font.try_glyph_id(c))
+        .filter_map(|id| font.outline(id))
+        .filter_map(|outline| {
+            let ys = curve_points(&outline.curves).map(|p| p.y);
+            match top {
+                true => ys.reduce(f32::max),
+                false => ys.reduce(f32::min),
+            }
+        })
+        .fold((0.0, 0), |(sum, count), y| (sum + y, count + 1));
+    match count {
+        0 => None,
+        _ => Some(sum / count as f32),
+    }
+}
+
+/// Iterates over all end & control points of `curves`.
+fn curve_points(curves: &[OutlineCurve]) -> impl Iterator<Item = Point> + '_ {
+    curves.iter().flat_map(|curve| {
+        let (points, len) = curve.control_points();
+        IntoIterator::into_iter(points).take(len)
+    })
+}
+
+/// A horizontal outline edge, made of near horizontal segments at the same height.
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    /// Unfitted y in pixels, up from the baseline.
+    y: f32,
+    /// Whether the glyph is filled below this edge.
+    top: bool,
+    min_x: f32,
+    max_x: f32,
+    /// Grid-fitted y in pixels.
+    fit: Option<f32>,
+}
+
+impl Edge {
+    fn overlap(&self, other: &Edge) -> f32 {
+        self.max_x.min(other.max_x) - self.min_x.max(other.min_x)
+    }
+}
+
+impl Outline {
+    /// Returns this outline auto-hinted for drawing at a given scale & position, see
+    /// [`AutoHinter`].
+    ///
+    /// Horizontal edges are detected from near horizontal curve tangents. Edges within blue
+    /// zones are aligned to them, stems formed by pairs of edges are snapped to whole pixel
+    /// heights & positions and other points are interpolated between the fitted edges.
+    /// Only y coordinates are changed.
+    pub fn autohint(
+        &self,
+        hinter: &AutoHinter,
+        scale_factor: PxScaleFactor,
+        position: Point,
+    ) -> Outline {
+        let scale = scale_factor.vertical;
+        if !scale.is_normal() || scale < 0.0 {
+            return self.clone();
+        }
+
+        // pixel rows are aligned to the glyph's subpixel position
+        let offset = position.y.fract();
+        let grid = |y: f32| (y - offset).round() + offset;
+
+        let mut edges = detect_edges(&self.curves, scale);
+        if edges.is_empty() {
+            return self.clone();
+        }
+
+        // align edges to blue zones
+        for edge in &mut edges {
+            edge.fit = hinter
+                .blue_zones
+                .iter()
+                .filter_map(|zone| {
+                    let (reference, overshoot) = (zone.reference * scale, zone.overshoot * scale);
+                    if reference != overshoot && (overshoot > reference) != edge.top {
+                        return None;
+                    }
+                    let min = reference.min(overshoot) - BLUE_THRESHOLD;
+                    let max = reference.max(overshoot) + BLUE_THRESHOLD;
+                    if edge.y < min || edge.y > max {
+                        return None;
+                    }
+
+                    let reference_fit = grid(reference);
+                    let (reference_dist, overshoot_dist) =
+                        ((edge.y - reference).abs(), (edge.y - overshoot).abs());
+                    if reference_dist <= overshoot_dist {
+                        return Some((reference_dist, reference_fit));
+                    }
+                    let shoot = (overshoot - reference).abs();
+                    let shoot_fit = match shoot {
+                        s if s < 0.5 => 0.0,
+                        s if s < 0.75 => 1.0,
+                        s => s.round(),
+                    };
+                    match overshoot > reference {
+                        true => Some((overshoot_dist, reference_fit + shoot_fit)),
+                        false => Some((overshoot_dist, reference_fit - shoot_fit)),
+                    }
+                })
+                .min_by(|a, b| total_cmp(a.0, b.0))
+                .map(|(_, fit)| fit);
+        }
+
+        // snap stems keeping their position relative to any aligned edge
+        for (bottom, top) in stems(&edges) {
+            let width = edges[top].y - edges[bottom].y;
+            let width_fit = width.round().max(1.0);
+            match (edges[bottom].fit, edges[top].fit) {
+                (Some(_), Some(_)) => {}
+                (Some(fit), None) => edges[top].fit = Some(fit + width_fit),
+                (None, Some(fit)) => edges[bottom].fit = Some(fit - width_fit),
+                (None, None) => {
+                    let fit = grid(edges[bottom].y + (width - width_fit) / 2.0);
+                    edges[bottom].fit = Some(fit);
+                    edges[top].fit = Some(fit + width_fit);
+                }
+            }
+        }
+
+        // fit remaining edges between their fitted neighbours
+        let fitted: Vec<_> = edges.iter().filter_map(|e| Some((e.y, e.fit?))).collect();
+        for edge in edges.iter_mut().filter(|e| e.fit.is_none()) {
+            edge.fit = Some(match fitted.is_empty() {
+                true => grid(edge.y),
+                false => interpolate(&fitted, edge.y),
+            });
+        }
+
+        // keep edges in order
+        let mut map: Vec<_> = edges.iter().map(|e| (e.y, e.fit.unwrap())).collect();
+        for i in 1..map.len() {
+            map[i].1 = map[i].1.max(map[i - 1].1);
+        }
+
+        let hint = |y: f32| {
+            let y = y * scale;
+            let nearest = map
+                .iter()
+                .map(|&(from, to)| ((y - from).abs(), to))
+                .min_by(|a, b| total_cmp(a.0, b.0));
+            match nearest {
+                Some((dist, to)) if dist <= EDGE_THRESHOLD => to / scale,
+                _ => interpolate(&map, y) / scale,
+            }
+        };
+        let hint_point = |p: Point| point(p.x, hint(p.y));
+
+        Outline {
+            bounds: Rect {
+                min: point(self.bounds.min.x, hint(self.bounds.min.y)),
+                max: point(self.bounds.max.x, hint(self.bounds.max.y)),
+            },
+            curves: self
+                .curves
+                .iter()
+                .map(|curve| match *curve {
+                    OutlineCurve::Line(p0, p1) => {
+                        OutlineCurve::Line(hint_point(p0), hint_point(p1))
+                    }
+                    OutlineCurve::Quad(p0, p1, p2) => {
+                        OutlineCurve::Quad(hint_point(p0), hint_point(p1), hint_point(p2))
+                    }
+                    OutlineCurve::Cubic(p0, p1, p2, p3) => OutlineCurve::Cubic(
+                        hint_point(p0),
+                        hint_point(p1),
+                        hint_point(p2),
+                        hint_point(p3),
+                    ),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Detects horizontal edges of `curves` sorted by y, in pixels at the vertical `scale`.
+fn detect_edges(curves: &[OutlineCurve], scale: f32) -> Vec<Edge> {
+    // outer contours are clockwise if the total signed area is negative
+    let area: f32 = curves
+        .iter()
+        .map(|curve| {
+            let (points, len) = curve.control_points();
+            points[..len]
+                .windows(2)
+                .map(|w| w[0].x * w[1].y - w[1].x * w[0].y)
+                .sum::<f32>()
+        })
+        .sum();
+    if area == 0.0 {
+        return Vec::new();
+    }
+    let clockwise = area < 0.0;
+
+    // segments are curve tangents at end points that are near horizontal
+    let mut segments: Vec<Edge> = curves
+        .iter()
+        .flat_map(|curve| IntoIterator::into_iter(tangents(curve)).flatten())
+        .filter_map(|(a, b)| {
+            let (dx, dy) = (b.x - a.x, b.y - a.y);
+            if dx == 0.0 || dy.abs() * 14.0 > dx.abs() {
+                return None;
+            }
+            Some(Edge {
+                y: (a.y + b.y) * 0.5 * scale,
+                top: (dx > 0.0) == clockwise,
+                min_x: a.x.min(b.x),
+                max_x: a.x.max(b.x),
+                fit: None,
+            })
+        })
+        .collect();
+    segments.sort_by(|a, b| total_cmp(a.y, b.y));
+
+    let mut edges: Vec<Edge> = Vec::new();
+    let mut weights: Vec<f32> = Vec::new();
+    for segment in segments {
+        let weight = segment.max_x - segment.min_x;
+        let existing = edges
+            .iter()
+            .rposition(|e| e.top == segment.top && segment.y - e.y <= EDGE_THRESHOLD);
+        match existing {
+            Some(idx) => {
+                let edge = &mut edges[idx];
+                let total = weights[idx] + weight;
+                if total > 0.0 {
+                    edge.y = (edge.y * weights[idx] + segment.y * weight) / total;
+                }
+                edge.min_x = edge.min_x.min(segment.min_x);
+                edge.max_x = edge.max_x.max(segment.max_x);
+                weights[idx] = total;
+            }
+            None => {
+                edges.push(segment);
+                weights.push(weight);
+            }
+        }
+    }
+    edges.sort_by(|a, b| total_cmp(a.y, b.y));
+    edges
+}
+
+/// Returns the control polygon lines of a curve touching its end points.
+fn tangents(curve: &OutlineCurve) -> [Option<(Point, Point)>; 2] {
+    match *curve {
+        OutlineCurve::Line(p0, p1) => [Some((p0, p1)), None],
+        OutlineCurve::Quad(p0, p1, p2) => [Some((p0, p1)), Some((p1, p2))],
+        OutlineCurve::Cubic(p0, p1, p2, p3) => [Some((p0, p1)), Some((p2, p3))],
+    }
+}
+
+/// Returns stems as `(bottom, top)` edge indices. Stems are pairs of overlapping edges
+/// bounding filled areas that are each other's nearest opposite edge.
+fn stems(edges: &[Edge]) -> Vec<(usize, usize)> {
+    let nearest = |idx: usize| {
+        let edge = &edges[idx];
+        edges
+            .iter()
+            .enumerate()
+            .filter(|(_, other)| other.top != edge.top && edge.overlap(other) > 0.0)
+            .filter(|(_, other)| match edge.top {
+                true => other.y < edge.y,
+                false => other.y > edge.y,
+            })
+            .min_by(|(_, a), (_, b)| total_cmp((a.y - edge.y).abs(), (b.y - edge.y).abs()))
+            .map(|(idx, _)| idx)
+    };
+
+    (0..edges.len())
+        .filter(|&idx| !edges[idx].top)
+        .filter_map(|bottom| {
+            let top = nearest(bottom)?;
+            (nearest(top) == Some(bottom)).then(|| (bottom, top))
+        })
+        .collect()
+}
+
+/// Linearly interpolates `y` between the sorted `(from, to)` mappings, shifting values
+/// outside the range by the nearest delta.
+fn interpolate(map: &[(f32, f32)], y: f32) -> f32 {
+    let (first, last) = (map[0], map[map.len() - 1]);
+    if y <= first.0 {
+        return y + first.1 - first.0;
+    }
+    if y >= last.0 {
+        return y + last.1 - last.0;
+    }
+    let idx = map.partition_point(|&(from, _)| from <= y);
+    let ((y0, fit0), (y1, fit1)) = (map[idx - 1], map[idx]);
+    match y1 - y0 {
+        d if d > f32::EPSILON => fit0 + (fit1 - fit0) * (y - y0) / d,
+        _ => fit0,
+    }
+}
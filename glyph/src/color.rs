@@ -0,0 +1,774 @@
+//! Color glyphs from `COLR` & `CPAL` tables.
+#[cfg(all(feature = "libm", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+use crate::{
+    outlined::{rasterize_curves, unscaled_px_bounds},
+    point, Font, Glyph, GlyphId, Outline, OutlineCurve, Point, PxScaleFactor, Rect, Transform,
+};
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec, vec::Vec};
+
+/// A non-premultiplied sRGB color with 8-bit components.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    /// Construct a color from non-premultiplied components.
+    #[inline]
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Premultiplied `[r, g, b, a]` components in the range `[0.0, 1.0]`
+    /// with an additional `alpha` multiplier.
+    #[inline]
+    fn premultiplied(self, alpha: f32) -> [f32; 4] {
+        let a = f32::from(self.a) / 255.0 * alpha.clamp(0.0, 1.0);
+        [
+            f32::from(self.r) / 255.0 * a,
+            f32::from(self.g) / 255.0 * a,
+            f32::from(self.b) / 255.0 * a,
+            a,
+        ]
+    }
+}
+
+/// Color glyph description from a font's `COLR` table with colors resolved
+/// from a `CPAL` palette.
+///
+/// See [`Font::color_glyph`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColorGlyph {
+    /// `COLR` version 0 glyph, a stack of solid color layers drawn bottom to top.
+    Layers(Vec<ColorLayer>),
+    /// `COLR` version 1 glyph, a graph of paint operations.
+    Paint {
+        /// Root paint.
+        paint: Paint,
+        /// Optional unscaled clip box, all painting happens inside this box.
+        clip_box: Option<Rect>,
+    },
+}
+
+/// A `COLR` version 0 layer, a glyph outline filled with a solid color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ColorLayer {
+    /// Glyph providing the layer outline.
+    pub id: GlyphId,
+    /// Fill color, `None` indicates the text foreground color.
+    pub color: Option<Color>,
+}
+
+/// A `COLR` version 1 paint operation.
+///
+/// Geometry is in unscaled font units. Variable paint formats are resolved
+/// to their default values.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Paint {
+    /// Paints each layer on top of the previous.
+    Layers(Vec<Paint>),
+    /// Solid color fill.
+    Solid {
+        /// Fill color, `None` indicates the text foreground color.
+        color: Option<Color>,
+        /// Alpha multiplier.
+        alpha: f32,
+    },
+    /// Linear gradient along `p0` -> `p1`, rotated by `p2`.
+    LinearGradient {
+        color_line: ColorLine,
+        p0: Point,
+        p1: Point,
+        p2: Point,
+    },
+    /// Two-point conical gradient between circles `(c0, r0)` & `(c1, r1)`.
+    RadialGradient {
+        color_line: ColorLine,
+        c0: Point,
+        r0: f32,
+        c1: Point,
+        r1: f32,
+    },
+    /// Sweep gradient around `center`. Angles are counter-clockwise degrees.
+    SweepGradient {
+        color_line: ColorLine,
+        center: Point,
+        start_angle: f32,
+        end_angle: f32,
+    },
+    /// Fills the outline of glyph `id` with `paint`.
+    Glyph { id: GlyphId, paint: Box<Paint> },
+    /// Applies an affine `[xx, yx, xy, yy, dx, dy]` transform to `paint`.
+    ///
+    /// Rotations, scales, skews & translations are all represented by this.
+    Transform { matrix: [f32; 6], paint: Box<Paint> },
+    /// Composites `source` onto `backdrop`.
+    Composite {
+        source: Box<Paint>,
+        mode: CompositeMode,
+        backdrop: Box<Paint>,
+    },
+}
+
+/// Gradient color stops & extend mode.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorLine {
+    pub extend: Extend,
+    /// Color stops ordered by offset.
+    pub stops: Vec<ColorStop>,
+}
+
+/// A gradient color stop.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorStop {
+    /// Position along the color line.
+    pub offset: f32,
+    /// Stop color, `None` indicates the text foreground color.
+    pub color: Option<Color>,
+    /// Alpha multiplier.
+    pub alpha: f32,
+}
+
+/// Gradient behaviour outside of the color line stops.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Extend {
+    /// Use the nearest stop color.
+    Pad,
+    /// Repeat the color line.
+    Repeat,
+    /// Repeat the color line, mirroring every other repetition.
+    Reflect,
+}
+
+/// Compositing & blending modes used by [`Paint::Composite`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompositeMode {
+    Clear,
+    Source,
+    Destination,
+    SourceOver,
+    DestinationOver,
+    SourceIn,
+    DestinationIn,
+    SourceOut,
+    DestinationOut,
+    SourceAtop,
+    DestinationAtop,
+    Xor,
+    Plus,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Multiply,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+/// A color glyph that has been outlined at a scale & position.
+///
+/// See [`Font::outline_color_glyph`].
+#[derive(Clone, Debug)]
+pub struct OutlinedColorGlyph {
+    glyph: Glyph,
+    px_bounds: Rect,
+    scale_factor: PxScaleFactor,
+    paint: Paint,
+    clip_box: Option<Rect>,
+    // outlines of all glyphs referenced by `paint`, ordered by id
+    outlines: Vec<(GlyphId, Outline)>,
+}
+
+impl OutlinedColorGlyph {
+    /// Constructs an `OutlinedColorGlyph` from the source `Glyph` & color glyph
+    /// description, using `font` to outline referenced glyphs.
+    pub fn new<F: Font>(
+        font: F,
+        glyph: Glyph,
+        color_glyph: ColorGlyph,
+        scale_factor: PxScaleFactor,
+    ) -> Self {
+        let (paint, clip_box) = match color_glyph {
+            ColorGlyph::Layers(layers) => {
+                let paints = layers
+                    .into_iter()
+                    .map(|ColorLayer { id, color }| Paint::Glyph {
+                        id,
+                        paint: Box::new(Paint::Solid { color, alpha: 1.0 }),
+                    })
+                    .collect();
+                (Paint::Layers(paints), None)
+            }
+            ColorGlyph::Paint { paint, clip_box } => (paint, clip_box),
+        };
+
+        let mut outlines = Vec::new();
+        let mut bounds = None;
+        collect_outlines(
+            &font,
+            &paint,
+            Transform::IDENTITY,
+            &mut outlines,
+            &mut bounds,
+        );
+        let bounds = clip_box.or(bounds).unwrap_or_default();
+        let px_bounds = unscaled_px_bounds(bounds, scale_factor, glyph.position);
+
+        Self {
+            glyph,
+            px_bounds,
+            scale_factor,
+            paint,
+            clip_box,
+            outlines,
+        }
+    }
+
+    /// Glyph info.
+    #[inline]
+    pub fn glyph(&self) -> &Glyph {
+        &self.glyph
+    }
+
+    /// Conservative whole number pixel bounding box for this glyph.
+    #[inline]
+    pub fn px_bounds(&self) -> Rect {
+        self.px_bounds
+    }
+
+    /// Draw this color glyph using a pixel & color handling function.
+    ///
+    /// The callback will be called for each `(x, y)` pixel coordinate inside the bounds
+    /// with premultiplied `[r, g, b, a]` components in the range `[0.0, 1.0]`.
+    ///
+    /// `foreground` is used for any layers & stops using the text foreground color.
+    pub fn draw<O: FnMut(u32, u32, [f32; 4])>(&self, foreground: Color, mut o: O) {
+        let (w, h) = (
+            self.px_bounds.width() as usize,
+            self.px_bounds.height() as usize,
+        );
+        let offset = self.glyph.position - self.px_bounds.min;
+        let to_px = Transform::from([
+            self.scale_factor.horizontal,
+            0.0,
+            0.0,
+            -self.scale_factor.vertical,
+            offset.x,
+            offset.y,
+        ]);
+
+        let painter = Painter {
+            outlines: &self.outlines,
+            foreground,
+            width: w,
+            height: h,
+        };
+        let mut pixels = painter.render(&self.paint, to_px, 0);
+
+        if let Some(Rect { min, max }) = self.clip_box {
+            let clip = [
+                OutlineCurve::Line(point(min.x, min.y), point(max.x, min.y)),
+                OutlineCurve::Line(point(max.x, min.y), point(max.x, max.y)),
+                OutlineCurve::Line(point(max.x, max.y), point(min.x, max.y)),
+                OutlineCurve::Line(point(min.x, max.y), point(min.x, min.y)),
+            ];
+            painter.mask(&mut pixels, &clip, to_px);
+        }
+
+        let w32 = w as u32;
+        for (idx, px) in pixels.into_iter().enumerate() {
+            o(idx as u32 % w32, idx as u32 / w32, px);
+        }
+    }
+}
+
+impl AsRef<Glyph> for OutlinedColorGlyph {
+    #[inline]
+    fn as_ref(&self) -> &Glyph {
+        self.glyph()
+    }
+}
+
+/// Maximum paint graph depth, protects against malicious fonts.
+const MAX_DEPTH: u8 = 64;
+
+/// Walks `paint` collecting referenced glyph outlines & their transformed union bounds.
+fn collect_outlines<F: Font>(
+    font: &F,
+    paint: &Paint,
+    transform: Transform,
+    outlines: &mut Vec<(GlyphId, Outline)>,
+    bounds: &mut Option<Rect>,
+) {
+    match paint {
+        Paint::Layers(layers) => {
+            for layer in layers {
+                collect_outlines(font, layer, transform, outlines, bounds);
+            }
+        }
+        Paint::Glyph { id, paint } => {
+            let outline = match outlines.binary_search_by_key(id, |(id, _)| *id) {
+                Ok(idx) => Some(&outlines[idx].1),
+                Err(idx) => font.outline(*id).map(|outline| {
+                    outlines.insert(idx, (*id, outline));
+                    &outlines[idx].1
+                }),
+            };
+            if let Some(outline) = outline {
+                let Rect { min, max } = outline.bounds;
+                let corners = [min, point(max.x, min.y), max, point(min.x, max.y)];
+                for corner in corners.iter().map(|p| transform.apply(*p)) {
+                    let b = bounds.get_or_insert(Rect {
+                        min: corner,
+                        max: corner,
+                    });
+                    // note: unscaled bounds have `min.y` as the top
+                    b.min.x = b.min.x.min(corner.x);
+                    b.max.x = b.max.x.max(corner.x);
+                    b.min.y = b.min.y.max(corner.y);
+                    b.max.y = b.max.y.min(corner.y);
+                }
+            }
+            collect_outlines(font, paint, transform, outlines, bounds);
+        }
+        Paint::Transform { matrix, paint } => {
+            collect_outlines(
+                font,
+                paint,
+                transform * Transform::from(*matrix),
+                outlines,
+                bounds,
+            );
+        }
+        Paint::Composite {
+            source, backdrop, ..
+        } => {
+            collect_outlines(font, source, transform, outlines, bounds);
+            collect_outlines(font, backdrop, transform, outlines, bounds);
+        }
+        Paint::Solid { .. }
+        | Paint::LinearGradient { .. }
+        | Paint::RadialGradient { .. }
+        | Paint::SweepGradient { .. } => {}
+    }
+}
+
+struct Painter<'a> {
+    outlines: &'a [(GlyphId, Outline)],
+    foreground: Color,
+    width: usize,
+    height: usize,
+}
+
+impl Painter<'_> {
+    /// Renders `paint` into a new premultiplied buffer using `transform` to map
+    /// font units to pixels.
+    fn render(&self, paint: &Paint, transform: Transform, depth: u8) -> Vec<[f32; 4]> {
+        let len = self.width * self.height;
+        if depth > MAX_DEPTH {
+            return vec![[0.0; 4]; len];
+        }
+
+        match paint {
+            Paint::Layers(layers) => {
+                let mut pixels = vec![[0.0; 4]; len];
+                for layer in layers {
+                    let src = self.render(layer, transform, depth + 1);
+                    for (d, s) in pixels.iter_mut().zip(src) {
+                        *d = blend(s, *d, CompositeMode::SourceOver);
+                    }
+                }
+                pixels
+            }
+            Paint::Solid { color, alpha } => {
+                let color = color.unwrap_or(self.foreground).premultiplied(*alpha);
+                vec![color; len]
+            }
+            Paint::LinearGradient {
+                color_line,
+                p0,
+                p1,
+                p2,
+            } => {
+                // project p1 onto the line through p0 perpendicular to p0 -> p2
+                let perp = point(p0.y - p2.y, p2.x - p0.x);
+                let perp_len_sq = dot(perp, perp);
+                let p1 = if perp_len_sq > 0.0 {
+                    let p = *p0 + scale(perp, dot(*p1 - *p0, perp) / perp_len_sq);
+                    if p == *p0 {
+                        *p1
+                    } else {
+                        p
+                    }
+                } else {
+                    *p1
+                };
+                let dir = p1 - *p0;
+                let dir_len_sq = dot(dir, dir);
+                self.gradient(color_line, transform, |p| {
+                    if dir_len_sq > 0.0 {
+                        Some(dot(p - *p0, dir) / dir_len_sq)
+                    } else {
+                        None
+                    }
+                })
+            }
+            Paint::RadialGradient {
+                color_line,
+                c0,
+                r0,
+                c1,
+                r1,
+            } => {
+                let cd = *c1 - *c0;
+                let dr = r1 - r0;
+                let a = dot(cd, cd) - dr * dr;
+                self.gradient(color_line, transform, |p| {
+                    let pd = p - *c0;
+                    let b = dot(pd, cd) + r0 * dr;
+                    let c = dot(pd, pd) - r0 * r0;
+                    let valid = |t: f32| r0 + t * dr >= 0.0;
+                    if a.abs() <= f32::EPSILON {
+                        if b.abs() <= f32::EPSILON {
+                            return None;
+                        }
+                        let t = c / (2.0 * b);
+                        return Some(t).filter(|t| valid(*t));
+                    }
+                    let discriminant = b * b - a * c;
+                    if discriminant < 0.0 {
+                        return None;
+                    }
+                    let sqrt = discriminant.sqrt();
+                    let (t0, t1) = ((b + sqrt) / a, (b - sqrt) / a);
+                    let (hi, lo) = if t0 > t1 { (t0, t1) } else { (t1, t0) };
+                    if valid(hi) {
+                        Some(hi)
+                    } else if valid(lo) {
+                        Some(lo)
+                    } else {
+                        None
+                    }
+                })
+            }
+            Paint::SweepGradient {
+                color_line,
+                center,
+                start_angle,
+                end_angle,
+            } => {
+                let range = end_angle - start_angle;
+                self.gradient(color_line, transform, |p| {
+                    if range.abs() <= f32::EPSILON {
+                        return None;
+                    }
+                    let d = p - *center;
+                    let mut angle = d.y.atan2(d.x).to_degrees();
+                    if angle < 0.0 {
+                        angle += 360.0;
+                    }
+                    Some((angle - start_angle) / range)
+                })
+            }
+            Paint::Glyph { id, paint } => {
+                let mut pixels = self.render(paint, transform, depth + 1);
+                match self.outlines.binary_search_by_key(id, |(id, _)| *id) {
+                    Ok(idx) => self.mask(&mut pixels, &self.outlines[idx].1.curves, transform),
+                    Err(_) => pixels.iter_mut().for_each(|px| *px = [0.0; 4]),
+                }
+                pixels
+            }
+            Paint::Transform { matrix, paint } => {
+                self.render(paint, transform * Transform::from(*matrix), depth + 1)
+            }
+            Paint::Composite {
+                source,
+                mode,
+                backdrop,
+            } => {
+                let mut pixels = self.render(backdrop, transform, depth + 1);
+                let src = self.render(source, transform, depth + 1);
+                for (d, s) in pixels.iter_mut().zip(src) {
+                    *d = blend(s, *d, *mode);
+                }
+                pixels
+            }
+        }
+    }
+
+    /// Fills a new buffer with `color_line` colors, using `t_at` to map unscaled
+    /// positions to color line offsets.
+    fn gradient(
+        &self,
+        color_line: &ColorLine,
+        transform: Transform,
+        t_at: impl Fn(Point) -> Option<f32>,
+    ) -> Vec<[f32; 4]> {
+        let mut pixels = vec![[0.0; 4]; self.width * self.height];
+        let inverse = match transform.invert() {
+            Some(inverse) => inverse,
+            None => return pixels,
+        };
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let p = inverse.apply(point(x as f32 + 0.5, y as f32 + 0.5));
+                if let Some(t) = t_at(p) {
+                    pixels[y * self.width + x] = color_line.color_at(t, self.foreground);
+                }
+            }
+        }
+        pixels
+    }
+
+    /// Multiplies `pixels` by the coverage of `curves`.
+    fn mask(&self, pixels: &mut [[f32; 4]], curves: &[OutlineCurve], transform: Transform) {
+        let rasterizer = rasterize_curves(curves, self.width, self.height, |p| transform.apply(p));
+        rasterizer.for_each_pixel(|idx, coverage| {
+            let coverage = coverage.min(1.0);
+            for c in &mut pixels[idx] {
+                *c *= coverage;
+            }
+        });
+    }
+}
+
+impl ColorLine {
+    /// Premultiplied color at color line offset `t`.
+    fn color_at(&self, t: f32, foreground: Color) -> [f32; 4] {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return [0.0; 4],
+        };
+        let premultiplied =
+            |stop: &ColorStop| stop.color.unwrap_or(foreground).premultiplied(stop.alpha);
+
+        let len = last.offset - first.offset;
+        let t = if len <= f32::EPSILON {
+            t
+        } else {
+            match self.extend {
+                Extend::Pad => t,
+                Extend::Repeat => {
+                    let u = (t - first.offset) / len;
+                    first.offset + (u - u.floor()) * len
+                }
+                Extend::Reflect => {
+                    let u = (t - first.offset) / len;
+                    let mut u = u - (u / 2.0).floor() * 2.0;
+                    if u > 1.0 {
+                        u = 2.0 - u;
+                    }
+                    first.offset + u * len
+                }
+            }
+        };
+
+        if t <= first.offset {
+            return premultiplied(first);
+        }
+        if t >= last.offset {
+            return premultiplied(last);
+        }
+        for pair in self.stops.windows(2) {
+            let (s0, s1) = (&pair[0], &pair[1]);
+            if t <= s1.offset {
+                let span = s1.offset - s0.offset;
+                if span <= f32::EPSILON {
+                    return premultiplied(s1);
+                }
+                let f = (t - s0.offset) / span;
+                let (c0, c1) = (premultiplied(s0), premultiplied(s1));
+                return [
+                    c0[0] + f * (c1[0] - c0[0]),
+                    c0[1] + f * (c1[1] - c0[1]),
+                    c0[2] + f * (c1[2] - c0[2]),
+                    c0[3] + f * (c1[3] - c0[3]),
+                ];
+            }
+        }
+        premultiplied(last)
+    }
+}
+
+#[inline]
+fn dot(a: Point, b: Point) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+
+#[inline]
+fn scale(p: Point, s: f32) -> Point {
+    point(p.x * s, p.y * s)
+}
+
+/// Composites premultiplied `src` onto premultiplied `dst`.
+fn blend(src: [f32; 4], dst: [f32; 4], mode: CompositeMode) -> [f32; 4] {
+    use CompositeMode::*;
+
+    let (sa, da) = (src[3], dst[3]);
+    let porter_duff = |fs: f32, fd: f32| {
+        [
+            src[0] * fs + dst[0] * fd,
+            src[1] * fs + dst[1] * fd,
+            src[2] * fs + dst[2] * fd,
+            sa * fs + da * fd,
+        ]
+    };
+
+    match mode {
+        Clear => [0.0; 4],
+        Source => src,
+        Destination => dst,
+        SourceOver => porter_duff(1.0, 1.0 - sa),
+        DestinationOver => porter_duff(1.0 - da, 1.0),
+        SourceIn => porter_duff(da, 0.0),
+        DestinationIn => porter_duff(0.0, sa),
+        SourceOut => porter_duff(1.0 - da, 0.0),
+        DestinationOut => porter_duff(0.0, 1.0 - sa),
+        SourceAtop => porter_duff(da, 1.0 - sa),
+        DestinationAtop => porter_duff(1.0 - da, sa),
+        Xor => porter_duff(1.0 - da, 1.0 - sa),
+        Plus => [
+            (src[0] + dst[0]).min(1.0),
+            (src[1] + dst[1]).min(1.0),
+            (src[2] + dst[2]).min(1.0),
+            (sa + da).min(1.0),
+        ],
+        _ => {
+            // W3C compositing & blending with source-over compositing
+            let unpremultiply = |c: [f32; 4]| {
+                if c[3] > 0.0 {
+                    [c[0] / c[3], c[1] / c[3], c[2] / c[3]]
+                } else {
+                    [0.0; 3]
+                }
+            };
+            let (cs, cd) = (unpremultiply(src), unpremultiply(dst));
+            let b = blend_colors(cs, cd, mode);
+            let channel = |i: usize| (1.0 - da) * src[i] + (1.0 - sa) * dst[i] + sa * da * b[i];
+            [channel(0), channel(1), channel(2), sa + da - sa * da]
+        }
+    }
+}
+
+/// Non-premultiplied blend function `B(cs, cd)`.
+fn blend_colors(cs: [f32; 3], cd: [f32; 3], mode: CompositeMode) -> [f32; 3] {
+    use CompositeMode::*;
+
+    fn multiply(s: f32, d: f32) -> f32 {
+        s * d
+    }
+    fn screen(s: f32, d: f32) -> f32 {
+        s + d - s * d
+    }
+    fn hard_light(s: f32, d: f32) -> f32 {
+        if s <= 0.5 {
+            multiply(d, 2.0 * s)
+        } else {
+            screen(d, 2.0 * s - 1.0)
+        }
+    }
+    fn separable(cs: [f32; 3], cd: [f32; 3], f: impl Fn(f32, f32) -> f32) -> [f32; 3] {
+        [f(cs[0], cd[0]), f(cs[1], cd[1]), f(cs[2], cd[2])]
+    }
+    fn lum(c: [f32; 3]) -> f32 {
+        0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+    }
+    fn clip_color(c: [f32; 3]) -> [f32; 3] {
+        let l = lum(c);
+        let n = c[0].min(c[1]).min(c[2]);
+        let x = c[0].max(c[1]).max(c[2]);
+        let mut c = c;
+        if n < 0.0 {
+            c = [
+                l + (c[0] - l) * l / (l - n),
+                l + (c[1] - l) * l / (l - n),
+                l + (c[2] - l) * l / (l - n),
+            ];
+        }
+        if x > 1.0 {
+            c = [
+                l + (c[0] - l) * (1.0 - l) / (x - l),
+                l + (c[1] - l) * (1.0 - l) / (x - l),
+                l + (c[2] - l) * (1.0 - l) / (x - l),
+            ];
+        }
+        c
+    }
+    fn set_lum(c: [f32; 3], l: f32) -> [f32; 3] {
+        let d = l - lum(c);
+        clip_color([c[0] + d, c[1] + d, c[2] + d])
+    }
+    fn sat(c: [f32; 3]) -> f32 {
+        c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+    }
+    fn set_sat(c: [f32; 3], s: f32) -> [f32; 3] {
+        let max = c[0].max(c[1]).max(c[2]);
+        let min = c[0].min(c[1]).min(c[2]);
+        if max > min {
+            let f = |v: f32| (v - min) * s / (max - min);
+            [f(c[0]), f(c[1]), f(c[2])]
+        } else {
+            [0.0; 3]
+        }
+    }
+
+    match mode {
+        Multiply => separable(cs, cd, multiply),
+        Screen => separable(cs, cd, screen),
+        Overlay => separable(cs, cd, |s, d| hard_light(d, s)),
+        Darken => separable(cs, cd, f32::min),
+        Lighten => separable(cs, cd, f32::max),
+        ColorDodge => separable(cs, cd, |s, d| {
+            if d <= 0.0 {
+                0.0
+            } else if s >= 1.0 {
+                1.0
+            } else {
+                (d / (1.0 - s)).min(1.0)
+            }
+        }),
+        ColorBurn => separable(cs, cd, |s, d| {
+            if d >= 1.0 {
+                1.0
+            } else if s <= 0.0 {
+                0.0
+            } else {
+                1.0 - ((1.0 - d) / s).min(1.0)
+            }
+        }),
+        HardLight => separable(cs, cd, hard_light),
+        SoftLight => separable(cs, cd, |s, d| {
+            if s <= 0.5 {
+                d - (1.0 - 2.0 * s) * d * (1.0 - d)
+            } else {
+                let dd = if d <= 0.25 {
+                    ((16.0 * d - 12.0) * d + 4.0) * d
+                } else {
+                    d.sqrt()
+                };
+                d + (2.0 * s - 1.0) * (dd - d)
+            }
+        }),
+        Difference => separable(cs, cd, |s, d| (s - d).abs()),
+        Exclusion => separable(cs, cd, |s, d| s + d - 2.0 * s * d),
+        Hue => set_lum(set_sat(cs, sat(cd)), lum(cd)),
+        Saturation => set_lum(set_sat(cd, sat(cs)), lum(cd)),
+        Color => set_lum(cs, lum(cd)),
+        Luminosity => set_lum(cd, lum(cs)),
+        // Porter-Duff modes are handled by `blend`
+        _ => cs,
+    }
+}
@@ -0,0 +1,178 @@
+//! Synthetic bold by offsetting outline contours.
+#[cfg(all(feature = "libm", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+use crate::{point, Outline, OutlineCurve, Point, Rect};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+impl Outline {
+    /// Returns this outline emboldened, "fake bold", by offsetting contours outward.
+    ///
+    /// The glyph grows by `strength_x` in width & `strength_y` in height, in unscaled font
+    /// units, keeping the bottom left in place. So advances should increase by the same
+    /// amounts, as [`PxScaleFont::embolden`](crate::PxScaleFont::embolden) does.
+    /// Negative strengths make the outline thinner.
+    ///
+    /// Each curve point is moved along the bisector of its neighbouring control polygon edges,
+    /// limited at sharp corners, so contour directions are preserved. This matches
+    /// FreeType's `FT_Outline_EmboldenXY`.
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf")).unwrap();
+    /// let outline = font.outline(font.glyph_id('I')).unwrap();
+    /// let bold = outline.embolden(40.0, 20.0);
+    ///
+    /// assert_eq!(bold.bounds.width(), outline.bounds.width() + 40.0);
+    /// assert_eq!(bold.bounds.max.y, outline.bounds.max.y); // baseline unchanged
+    /// ```
+    pub fn embolden(&self, strength_x: f32, strength_y: f32) -> Outline {
+        let (strength_x, strength_y) = (strength_x * 0.5, strength_y * 0.5);
+
+        // outer contours are clockwise if the total signed area is negative
+        let area: f32 = self
+            .curves
+            .iter()
+            .map(|curve| {
+                let (points, len) = curve.control_points();
+                points[..len]
+                    .windows(2)
+                    .map(|w| w[0].x * w[1].y - w[1].x * w[0].y)
+                    .sum::<f32>()
+            })
+            .sum();
+        let outward = if area < 0.0 { -1.0 } else { 1.0 };
+
+        let mut curves = Vec::with_capacity(self.curves.len());
+        let mut bounds = Rect {
+            min: point(self.bounds.min.x, self.bounds.min.y + 2.0 * strength_y),
+            max: point(self.bounds.max.x + 2.0 * strength_x, self.bounds.max.y),
+        };
+
+        for contour in contours(&self.curves) {
+            // contour control polygon, closed by the first point
+            let mut points: Vec<Point> = Vec::new();
+            for curve in contour {
+                let (p, len) = curve.control_points();
+                if points.is_empty() {
+                    points.push(p[0]);
+                }
+                points.extend_from_slice(&p[1..len]);
+            }
+            let closed = points.len() > 1 && points[0] == points[points.len() - 1];
+            if closed {
+                points.pop();
+            }
+
+            let shifted: Vec<Point> = (0..points.len())
+                .map(|idx| {
+                    let shift = shift(&points, idx, strength_x, strength_y, outward);
+                    points[idx] + point(strength_x + shift.x, strength_y + shift.y)
+                })
+                .collect();
+
+            let mut idx = 0;
+            let mut next = || {
+                let p = shifted[idx % shifted.len()];
+                idx += 1;
+                p
+            };
+            let first = next();
+            let mut start = first;
+            for curve in contour {
+                let curve = match curve {
+                    OutlineCurve::Line(..) => OutlineCurve::Line(start, next()),
+                    OutlineCurve::Quad(..) => OutlineCurve::Quad(start, next(), next()),
+                    OutlineCurve::Cubic(..) => OutlineCurve::Cubic(start, next(), next(), next()),
+                };
+                start = curve.end();
+                // end points may move outside the offset bounds at sharp corners
+                bounds.min.x = bounds.min.x.min(start.x);
+                bounds.min.y = bounds.min.y.max(start.y);
+                bounds.max.x = bounds.max.x.max(start.x);
+                bounds.max.y = bounds.max.y.min(start.y);
+                curves.push(curve);
+            }
+            if !closed && start != first {
+                // close the offset contour as the original wasn't
+                curves.push(OutlineCurve::Line(start, first));
+            }
+        }
+
+        Outline { bounds, curves }
+    }
+}
+
+/// Returns the outward offset of `points[idx]` along the bisector of its contour edges,
+/// excluding the uniform `strength` translation.
+fn shift(points: &[Point], idx: usize, strength_x: f32, strength_y: f32, outward: f32) -> Point {
+    let len = points.len();
+    let p = points[idx];
+    // nearest distinct neighbours
+    let prev = (1..len)
+        .map(|n| points[(idx + len - n) % len])
+        .find(|&q| q != p);
+    let next = (1..len).map(|n| points[(idx + n) % len]).find(|&q| q != p);
+    let (prev, next) = match (prev, next) {
+        (Some(prev), Some(next)) => (prev, next),
+        _ => return Point::default(),
+    };
+
+    let (d_in, d_out) = (p - prev, next - p);
+    let (l_in, l_out) = (length(d_in), length(d_out));
+    let (d_in, d_out) = (scale(d_in, l_in.recip()), scale(d_out, l_out.recip()));
+
+    // 1 + cos of the angle between edges, ignoring near reversals
+    let d = d_in.x * d_out.x + d_in.y * d_out.y;
+    if d <= -0.9375 {
+        return Point::default();
+    }
+    let d = d + 1.0;
+
+    // sum of edge normals, giving a unit offset along the bisector when divided by `d`
+    let mut shift = scale(point(d_in.y + d_out.y, -(d_in.x + d_out.x)), outward);
+    // sin of the turn angle, limiting offsets to the shortest edge
+    let q = outward * (d_out.x * d_in.y - d_out.y * d_in.x);
+    let l = l_in.min(l_out);
+
+    shift.x = match strength_x * q <= l * d {
+        true => shift.x * strength_x / d,
+        false => shift.x * l / q,
+    };
+    shift.y = match strength_y * q <= l * d {
+        true => shift.y * strength_y / d,
+        false => shift.y * l / q,
+    };
+    shift
+}
+
+/// Splits `curves` into contours of connected curves, ending where they close.
+fn contours(curves: &[OutlineCurve]) -> impl Iterator<Item = &[OutlineCurve]> {
+    let mut rest = curves;
+    core::iter::from_fn(move || {
+        let start = rest.first()?.control_points().0[0];
+        let mut end = rest[0].end();
+        let mut len = 1;
+        while let Some(curve) = rest.get(len).filter(|_| end != start) {
+            if curve.control_points().0[0] != end {
+                break;
+            }
+            end = curve.end();
+            len += 1;
+        }
+        let (contour, remaining) = rest.split_at(len);
+        rest = remaining;
+        Some(contour)
+    })
+}
+
+#[inline]
+fn scale(p: Point, s: f32) -> Point {
+    point(p.x * s, p.y * s)
+}
+
+#[inline]
+fn length(p: Point) -> f32 {
+    (p.x * p.x + p.y * p.y).sqrt()
+}
@@ -0,0 +1,175 @@
+use crate::{Font, FontArc, GlyphId};
+use core::fmt;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// An ordered chain of fonts resolving each character to the first font that has a glyph
+/// for it, avoiding `.notdef` "tofu" for characters missing from the primary font.
+///
+/// Resolutions are cached per character & glyph coverage per font & character. Use
+/// [`Layout::calculate_glyphs_with_fallback`](crate::Layout::calculate_glyphs_with_fallback)
+/// to lay out mixed-script text with the metrics & kerning of each resolved font.
+///
+/// # Example
+/// ```
+/// use ab_glyph::{FontArc, FontFallback, GlyphId};
+///
+/// # fn main() -> Result<(), ab_glyph::InvalidFont> {
+/// let latin = FontArc::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf"))?;
+/// let symbols = FontArc::try_from_slice(include_bytes!("../../dev/fonts/DejaVuSansMono.ttf"))?;
+/// let fonts = FontFallback::new(latin).with_fallback(symbols);
+///
+/// // 'a' is in the primary font
+/// assert_eq!(fonts.resolve('a').0, 0);
+/// // '☣' is only in the fallback
+/// let (font_index, id) = fonts.resolve('☣');
+/// assert_eq!(font_index, 1);
+/// assert_ne!(id, GlyphId(0));
+/// # Ok(()) }
+/// ```
+pub struct FontFallback {
+    fonts: Vec<FontArc>,
+    cache: RwLock<HashMap<char, (usize, GlyphId)>>,
+    /// Whether the font at an index has a glyph for a character.
+    coverage: RwLock<HashMap<(usize, char), bool>>,
+}
+
+impl FontFallback {
+    /// Creates a chain with a single, primary, font.
+    #[inline]
+    pub fn new(primary: FontArc) -> Self {
+        Self {
+            fonts: vec![primary],
+            cache: RwLock::default(),
+            coverage: RwLock::default(),
+        }
+    }
+
+    /// Appends a fallback font, used for characters missing from all previous fonts.
+    #[inline]
+    pub fn with_fallback(mut self, font: FontArc) -> Self {
+        self.push(font);
+        self
+    }
+
+    /// Appends a fallback font returning its index.
+    pub fn push(&mut self, font: FontArc) -> usize {
+        self.fonts.push(font);
+        // unresolved characters may be covered by the new font
+        self.cache
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|_, (_, id)| id.0 != 0);
+        self.fonts.len() - 1
+    }
+
+    /// The fonts in fallback order, the primary font first.
+    #[inline]
+    pub fn fonts(&self) -> &[FontArc] {
+        &self.fonts
+    }
+
+    /// Returns the index of the first font with a glyph for `c` & the glyph id.
+    ///
+    /// If no font has a glyph returns the primary font's `.notdef`, `(0, GlyphId(0))`.
+    pub fn resolve(&self, c: char) -> (usize, GlyphId) {
+        if let Some(resolved) = self.cache.read().unwrap_or_else(|e| e.into_inner()).get(&c) {
+            return *resolved;
+        }
+
+        let resolved = self
+            .fonts
+            .iter()
+            .enumerate()
+            .find_map(|(idx, font)| Some((idx, font.try_glyph_id(c)?)))
+            .unwrap_or((0, GlyphId(0)));
+        self.cache
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(c, resolved);
+        resolved
+    }
+
+    /// Returns the index of the first font with glyphs for all characters of a grapheme
+    /// `cluster`, so a base character & its combining marks use the same font.
+    ///
+    /// If no font covers the whole cluster, returns the font resolving the first character.
+    pub fn resolve_cluster(&self, cluster: &str) -> usize {
+        let mut chars = cluster.chars();
+        let first = match chars.next() {
+            Some(c) => self.resolve(c).0,
+            None => return 0,
+        };
+        if chars.as_str().is_empty() {
+            return first;
+        }
+        (first..self.fonts.len())
+            .find(|&idx| self.covers(idx, cluster))
+            .unwrap_or(first)
+    }
+
+    /// Returns `preferred` if it covers the whole `cluster`, otherwise resolves the cluster.
+    #[cfg(feature = "layout")]
+    pub(crate) fn resolve_cluster_preferring(&self, preferred: usize, cluster: &str) -> usize {
+        match self.covers(preferred, cluster) {
+            true => preferred,
+            false => self.resolve_cluster(cluster),
+        }
+    }
+
+    /// Whether the font at `idx` has glyphs for all characters in `text`.
+    #[inline]
+    fn covers(&self, idx: usize, text: &str) -> bool {
+        text.chars().all(|c| self.has_glyph(idx, c))
+    }
+
+    /// Whether the font at `idx` has a glyph for `c`, cached.
+    fn has_glyph(&self, idx: usize, c: char) -> bool {
+        let key = (idx, c);
+        if let Some(has) = self
+            .coverage
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+        {
+            return *has;
+        }
+
+        let has = self.fonts[idx].has_glyph(c);
+        self.coverage
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, has);
+        has
+    }
+}
+
+impl Clone for FontFallback {
+    fn clone(&self) -> Self {
+        Self {
+            fonts: self.fonts.clone(),
+            cache: RwLock::new(self.cache.read().unwrap_or_else(|e| e.into_inner()).clone()),
+            coverage: RwLock::new(
+                self.coverage
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .clone(),
+            ),
+        }
+    }
+}
+
+impl fmt::Debug for FontFallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FontFallback")
+            .field("fonts", &self.fonts.len())
+            .finish()
+    }
+}
+
+impl From<FontArc> for FontFallback {
+    #[inline]
+    fn from(primary: FontArc) -> Self {
+        Self::new(primary)
+    }
+}
@@ -0,0 +1,14 @@
+//! Total ordering of floats, as `f32::total_cmp` which requires Rust 1.62.
+use core::cmp::Ordering;
+
+/// Orders `a` & `b` by the IEEE 754 `totalOrder` predicate, so unlike `partial_cmp`
+/// NaN values also have a consistent order, suitable for sorting.
+#[inline]
+pub(crate) fn total_cmp(a: f32, b: f32) -> Ordering {
+    fn key(v: f32) -> i32 {
+        let bits = v.to_bits() as i32;
+        // flip all but the sign bit of negatives, so they order as two's complement
+        bits ^ (((bits >> 31) as u32) >> 1) as i32
+    }
+    key(a).cmp(&key(b))
+}
@@ -1,6 +1,8 @@
+#[cfg(all(feature = "libm", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
 use crate::{
-    point, Glyph, GlyphId, GlyphImage, Outline, OutlinedGlyph, PxScale, PxScaleFont, Rect,
-    ScaleFont,
+    point, ColorGlyph, DecodedGlyphImage, Glyph, GlyphId, GlyphImage, Outline, OutlinedColorGlyph,
+    OutlinedGlyph, PxScale, PxScaleFont, Rect, ScaleFont,
 };
 
 /// Functionality required from font data.
@@ -76,26 +78,91 @@ pub trait Font {
     /// Scaling can be done with [as_scaled](trait.Font.html#method.as_scaled).
     fn glyph_id(&self, c: char) -> GlyphId;
 
+    /// Lookup a `GlyphId` matching a given `char`, or `None` if the font has no glyph for it.
+    ///
+    /// Unlike [`Font::glyph_id`] this distinguishes missing chars from a real glyph,
+    /// as missing chars map to `.notdef`, `GlyphId(0)`.
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf")).unwrap();
+    /// assert_eq!(font.try_glyph_id('s'), Some(GlyphId(56)));
+    /// // no hebrew in this font
+    /// assert_eq!(font.try_glyph_id('א'), None);
+    /// assert_eq!(font.glyph_id('א'), GlyphId(0));
+    /// ```
+    #[inline]
+    fn try_glyph_id(&self, c: char) -> Option<GlyphId> {
+        Some(self.glyph_id(c)).filter(|id| id.0 != 0)
+    }
+
+    /// Returns `true` if the font has a glyph for `c`.
+    #[inline]
+    fn has_glyph(&self, c: char) -> bool {
+        self.try_glyph_id(c).is_some()
+    }
+
     /// Unscaled horizontal advance for a given glyph id.
     ///
     /// Scaling can be done with [as_scaled](trait.Font.html#method.as_scaled).
     fn h_advance_unscaled(&self, id: GlyphId) -> f32;
 
+    /// Unscaled horizontal advance for a given glyph id, or `None` for an invalid glyph id.
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf")).unwrap();
+    /// assert_eq!(font.try_h_advance_unscaled(GlyphId(56)), Some(505.0));
+    /// assert_eq!(font.try_h_advance_unscaled(GlyphId(u16::MAX)), None);
+    /// ```
+    #[inline]
+    fn try_h_advance_unscaled(&self, id: GlyphId) -> Option<f32> {
+        (usize::from(id.0) < self.glyph_count()).then(|| self.h_advance_unscaled(id))
+    }
+
     /// Unscaled horizontal side bearing for a given glyph id.
     ///
     /// Scaling can be done with [as_scaled](trait.Font.html#method.as_scaled).
     fn h_side_bearing_unscaled(&self, id: GlyphId) -> f32;
 
+    /// Unscaled horizontal side bearing for a given glyph id, or `None` for an invalid glyph id.
+    #[inline]
+    fn try_h_side_bearing_unscaled(&self, id: GlyphId) -> Option<f32> {
+        (usize::from(id.0) < self.glyph_count()).then(|| self.h_side_bearing_unscaled(id))
+    }
+
     /// Unscaled vertical advance for a given glyph id.
     ///
+    /// Fonts without vertical metrics derive the advance from the ascent & descent.
+    ///
     /// Scaling can be done with [as_scaled](trait.Font.html#method.as_scaled).
     fn v_advance_unscaled(&self, id: GlyphId) -> f32;
 
+    /// Unscaled vertical advance for a given glyph id, or `None` for an invalid glyph id.
+    ///
+    /// Fonts without vertical metrics derive the advance from the ascent & descent.
+    #[inline]
+    fn try_v_advance_unscaled(&self, id: GlyphId) -> Option<f32> {
+        (usize::from(id.0) < self.glyph_count()).then(|| self.v_advance_unscaled(id))
+    }
+
     /// Unscaled vertical side bearing for a given glyph id.
     ///
+    /// Fonts without vertical metrics derive the side bearing from the ascent & glyph top.
+    ///
     /// Scaling can be done with [as_scaled](trait.Font.html#method.as_scaled).
     fn v_side_bearing_unscaled(&self, id: GlyphId) -> f32;
 
+    /// Unscaled vertical side bearing for a given glyph id, or `None` for an invalid glyph id.
+    ///
+    /// Fonts without vertical metrics derive the side bearing from the ascent & glyph top.
+    #[inline]
+    fn try_v_side_bearing_unscaled(&self, id: GlyphId) -> Option<f32> {
+        (usize::from(id.0) < self.glyph_count()).then(|| self.v_side_bearing_unscaled(id))
+    }
+
     /// Returns additional unscaled kerning to apply for a particular pair of glyph ids.
     ///
     /// Scaling can be done with [as_scaled](trait.Font.html#method.as_scaled).
@@ -104,6 +171,22 @@ pub trait Font {
     /// Compute unscaled glyph outline curves & bounding box.
     fn outline(&self, id: GlyphId) -> Option<Outline>;
 
+    /// Compute glyph outline curves & bounding box grid-fitted by the font's TrueType
+    /// instructions at `ppem` pixels per em.
+    ///
+    /// Like [`Font::outline`] the result is in unscaled font units, so drawing it at the
+    /// same scale & a whole pixel position aligns hinted edges to the pixel grid,
+    /// see [`Font::outline_glyph_hinted`].
+    ///
+    /// Returns `None` for fonts without TrueType outlines, e.g. CFF fonts, & for variable
+    /// fonts with non-default variation coordinates.
+    #[cfg(feature = "hinting")]
+    #[inline]
+    fn hinted_outline(&self, id: GlyphId, ppem: f32) -> Option<Outline> {
+        let _ = (id, ppem);
+        None
+    }
+
     /// The number of glyphs present in this font. Glyph identifiers for this
     /// font will always be in the range `0..self.glyph_count()`
     fn glyph_count(&self) -> usize;
@@ -138,7 +221,22 @@ pub trait Font {
     /// used to select between multiple possible images (if present); the returned image will
     /// likely not match this value, requiring you to scale it to match the target resolution.
     /// To get the largest image use `u16::MAX`.
-    fn glyph_raster_image(&self, id: GlyphId, pixel_size: u16) -> Option<GlyphImage>;
+    ///
+    /// See also [`Font::decode_glyph_image`] to decode & scale the image.
+    fn glyph_raster_image(&self, id: GlyphId, pixel_size: u16) -> Option<GlyphImage<'_>>;
+
+    /// Returns the color glyph description from the `COLR` table, with colors taken
+    /// from `CPAL` palette index `palette`. Unavailable palettes fall back to the first.
+    ///
+    /// Returns `None` for glyphs without color data, these should be drawn
+    /// using [`Font::outline_glyph`] as normal.
+    ///
+    /// Use [`Font::outline_color_glyph`] to draw color glyphs.
+    #[inline]
+    fn color_glyph(&self, id: GlyphId, palette: u16) -> Option<ColorGlyph> {
+        let _ = (id, palette);
+        None
+    }
 
     /// Returns the layout bounds of this glyph. These are different to the outline `px_bounds()`.
     ///
@@ -168,6 +266,94 @@ pub trait Font {
         Some(OutlinedGlyph::new(glyph, outline, scale_factor))
     }
 
+    /// Compute glyph outline grid-fitted at the glyph's scale ready for drawing,
+    /// see [`Font::hinted_outline`].
+    ///
+    /// Hinting uses the vertical scale & assumes the glyph will be drawn at a whole pixel
+    /// position. Note [`PxScale`] is the font height, so `ppem` pixels per em corresponds to
+    /// a scale of `ppem * height_unscaled / units_per_em`.
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # fn main() -> Result<(), InvalidFont> {
+    /// let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/DejaVuSansMono.ttf"))?;
+    /// let glyph = font.glyph_id('H').with_scale(12.0);
+    ///
+    /// let hinted = font.outline_glyph_hinted(glyph).unwrap();
+    /// hinted.draw(|x, y, c| { /* draw pixel `(x, y)` with coverage: `c` */ });
+    /// # Ok(()) }
+    /// ```
+    #[cfg(feature = "hinting")]
+    #[inline]
+    fn outline_glyph_hinted(&self, glyph: Glyph) -> Option<OutlinedGlyph>
+    where
+        Self: Sized,
+    {
+        let scale_factor = self.as_scaled(glyph.scale).scale_factor();
+        let ppem = scale_factor.vertical * self.units_per_em()?;
+        let outline = self.hinted_outline(glyph.id, ppem)?;
+        Some(OutlinedGlyph::new(glyph, outline, scale_factor))
+    }
+
+    /// Compute color glyph layers & paints ready for drawing using `CPAL` palette `palette`.
+    ///
+    /// Returns `None` for glyphs without color data, see [`Font::color_glyph`].
+    #[inline]
+    fn outline_color_glyph(&self, glyph: Glyph, palette: u16) -> Option<OutlinedColorGlyph>
+    where
+        Self: Sized,
+    {
+        let color_glyph = self.color_glyph(glyph.id, palette)?;
+        let scale_factor = self.as_scaled(glyph.scale).scale_factor();
+        Some(OutlinedColorGlyph::new(
+            self,
+            glyph,
+            color_glyph,
+            scale_factor,
+        ))
+    }
+
+    /// Returns the pre-rendered image of the glyph decoded into RGBA pixels & resized
+    /// to match `scale`.
+    ///
+    /// The image is selected using [`Font::glyph_raster_image`] with the pixels per em
+    /// of `scale`, see [`GlyphImage::decode`] for supported formats.
+    ///
+    /// # Example
+    /// ```
+    /// use ab_glyph::{Font, FontRef};
+    ///
+    /// # fn main() -> Result<(), ab_glyph::InvalidFont> {
+    /// let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/bitmaps.ttf"))?;
+    ///
+    /// // scale 8.0 is 8 pixels per em in this font, matching the 5x4 8ppem image of 'a'
+    /// let image = font.decode_glyph_image(font.glyph_id('a'), 8.0).unwrap();
+    /// assert_eq!((image.width, image.height), (5, 4));
+    ///
+    /// // the largest 'a' image is 10x8 at 14ppem, scaled x2 for 28ppem
+    /// let image = font.decode_glyph_image(font.glyph_id('a'), 28.0).unwrap();
+    /// assert_eq!((image.width, image.height), (20, 16));
+    /// # Ok(()) }
+    /// ```
+    fn decode_glyph_image<S: Into<PxScale>>(
+        &self,
+        id: GlyphId,
+        scale: S,
+    ) -> Option<DecodedGlyphImage>
+    where
+        Self: Sized,
+    {
+        let scale = scale.into();
+        let px_per_unit = self.units_per_em()? / self.height_unscaled();
+        let (h_ppem, v_ppem) = (scale.x * px_per_unit, scale.y * px_per_unit);
+
+        let pixel_size = v_ppem.ceil().max(0.0).min(f32::from(u16::MAX)) as u16;
+        let image = self.glyph_raster_image(id, pixel_size)?;
+        let decoded = image.decode()?;
+        Some(decoded.resized(h_ppem / image.scale, v_ppem / image.scale))
+    }
+
     /// Construct a [`PxScaleFontRef`](struct.PxScaleFontRef.html) by associating with the
     /// given pixel `scale`.
     ///
@@ -234,26 +420,56 @@ impl<F: Font> Font for &F {
         (*self).glyph_id(c)
     }
 
+    #[inline]
+    fn try_glyph_id(&self, c: char) -> Option<GlyphId> {
+        (*self).try_glyph_id(c)
+    }
+
+    #[inline]
+    fn has_glyph(&self, c: char) -> bool {
+        (*self).has_glyph(c)
+    }
+
     #[inline]
     fn h_advance_unscaled(&self, id: GlyphId) -> f32 {
         (*self).h_advance_unscaled(id)
     }
 
+    #[inline]
+    fn try_h_advance_unscaled(&self, id: GlyphId) -> Option<f32> {
+        (*self).try_h_advance_unscaled(id)
+    }
+
     #[inline]
     fn h_side_bearing_unscaled(&self, id: GlyphId) -> f32 {
         (*self).h_side_bearing_unscaled(id)
     }
 
+    #[inline]
+    fn try_h_side_bearing_unscaled(&self, id: GlyphId) -> Option<f32> {
+        (*self).try_h_side_bearing_unscaled(id)
+    }
+
     #[inline]
     fn v_advance_unscaled(&self, id: GlyphId) -> f32 {
         (*self).v_advance_unscaled(id)
     }
 
+    #[inline]
+    fn try_v_advance_unscaled(&self, id: GlyphId) -> Option<f32> {
+        (*self).try_v_advance_unscaled(id)
+    }
+
     #[inline]
     fn v_side_bearing_unscaled(&self, id: GlyphId) -> f32 {
         (*self).v_side_bearing_unscaled(id)
     }
 
+    #[inline]
+    fn try_v_side_bearing_unscaled(&self, id: GlyphId) -> Option<f32> {
+        (*self).try_v_side_bearing_unscaled(id)
+    }
+
     #[inline]
     fn kern_unscaled(&self, first: GlyphId, second: GlyphId) -> f32 {
         (*self).kern_unscaled(first, second)
@@ -264,6 +480,12 @@ impl<F: Font> Font for &F {
         (*self).outline(glyph)
     }
 
+    #[cfg(feature = "hinting")]
+    #[inline]
+    fn hinted_outline(&self, id: GlyphId, ppem: f32) -> Option<Outline> {
+        (*self).hinted_outline(id, ppem)
+    }
+
     #[inline]
     fn glyph_count(&self) -> usize {
         (*self).glyph_count()
@@ -275,7 +497,12 @@ impl<F: Font> Font for &F {
     }
 
     #[inline]
-    fn glyph_raster_image(&self, id: GlyphId, size: u16) -> Option<GlyphImage> {
+    fn glyph_raster_image(&self, id: GlyphId, size: u16) -> Option<GlyphImage<'_>> {
         (*self).glyph_raster_image(id, size)
     }
+
+    #[inline]
+    fn color_glyph(&self, id: GlyphId, palette: u16) -> Option<ColorGlyph> {
+        (*self).color_glyph(id, palette)
+    }
 }
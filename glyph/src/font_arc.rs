@@ -1,4 +1,4 @@
-use crate::{Font, FontRef, FontVec, GlyphId, GlyphImage, InvalidFont, Outline};
+use crate::{ColorGlyph, Font, FontRef, FontVec, GlyphId, GlyphImage, InvalidFont, Outline};
 use alloc::sync::Arc;
 use core::fmt;
 
@@ -96,26 +96,56 @@ impl Font for FontArc {
         self.0.glyph_id(c)
     }
 
+    #[inline]
+    fn try_glyph_id(&self, c: char) -> Option<GlyphId> {
+        self.0.try_glyph_id(c)
+    }
+
+    #[inline]
+    fn has_glyph(&self, c: char) -> bool {
+        self.0.has_glyph(c)
+    }
+
     #[inline]
     fn h_advance_unscaled(&self, id: GlyphId) -> f32 {
         self.0.h_advance_unscaled(id)
     }
 
+    #[inline]
+    fn try_h_advance_unscaled(&self, id: GlyphId) -> Option<f32> {
+        self.0.try_h_advance_unscaled(id)
+    }
+
     #[inline]
     fn h_side_bearing_unscaled(&self, id: GlyphId) -> f32 {
         self.0.h_side_bearing_unscaled(id)
     }
 
+    #[inline]
+    fn try_h_side_bearing_unscaled(&self, id: GlyphId) -> Option<f32> {
+        self.0.try_h_side_bearing_unscaled(id)
+    }
+
     #[inline]
     fn v_advance_unscaled(&self, id: GlyphId) -> f32 {
         self.0.v_advance_unscaled(id)
     }
 
+    #[inline]
+    fn try_v_advance_unscaled(&self, id: GlyphId) -> Option<f32> {
+        self.0.try_v_advance_unscaled(id)
+    }
+
     #[inline]
     fn v_side_bearing_unscaled(&self, id: GlyphId) -> f32 {
         self.0.v_side_bearing_unscaled(id)
     }
 
+    #[inline]
+    fn try_v_side_bearing_unscaled(&self, id: GlyphId) -> Option<f32> {
+        self.0.try_v_side_bearing_unscaled(id)
+    }
+
     #[inline]
     fn kern_unscaled(&self, first: GlyphId, second: GlyphId) -> f32 {
         self.0.kern_unscaled(first, second)
@@ -126,6 +156,12 @@ impl Font for FontArc {
         self.0.outline(glyph)
     }
 
+    #[cfg(feature = "hinting")]
+    #[inline]
+    fn hinted_outline(&self, id: GlyphId, ppem: f32) -> Option<Outline> {
+        self.0.hinted_outline(id, ppem)
+    }
+
     #[inline]
     fn glyph_count(&self) -> usize {
         self.0.glyph_count()
@@ -137,9 +173,14 @@ impl Font for FontArc {
     }
 
     #[inline]
-    fn glyph_raster_image(&self, id: GlyphId, size: u16) -> Option<GlyphImage> {
+    fn glyph_raster_image(&self, id: GlyphId, size: u16) -> Option<GlyphImage<'_>> {
         self.0.glyph_raster_image(id, size)
     }
+
+    #[inline]
+    fn color_glyph(&self, id: GlyphId, palette: u16) -> Option<ColorGlyph> {
+        self.0.color_glyph(id, palette)
+    }
 }
 
 impl From<FontVec> for FontArc {
@@ -0,0 +1,529 @@
+#[cfg(all(feature = "libm", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+use crate::{
+    float_cmp::total_cmp, point, Font, Glyph, GlyphId, OutlinedGlyph, Point, PxScale, Rect,
+};
+use alloc::collections::{btree_map, BTreeMap};
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+/// Default number of subpixel position bins per axis.
+const SUBPIXEL_BINS: u8 = 4;
+
+/// Empty pixels around each glyph in the texture, avoiding bleeding when sampling.
+const PADDING: u32 = 1;
+
+/// A rectangular region of a [`GlyphCache`] texture in pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TextureRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Texture location & pixel placement of a glyph in a [`GlyphCache`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CachedGlyph {
+    /// Normalized `0.0..=1.0` texture coordinates of the glyph coverage.
+    pub tex_coords: Rect,
+    /// Region of the texture containing the glyph coverage.
+    pub texture_rect: TextureRect,
+    /// Pixel bounds to draw the glyph coverage at, `texture_rect` sized.
+    pub px_bounds: Rect,
+}
+
+/// [`GlyphCache::cache_queued`] error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GlyphCacheError {
+    /// A queued glyph is larger than the cache texture.
+    GlyphTooLarge,
+    /// The queued glyphs don't all fit in the cache texture at once.
+    NoRoomForWholeQueue,
+}
+
+impl fmt::Display for GlyphCacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GlyphTooLarge => write!(f, "Glyph is larger than the cache texture"),
+            Self::NoRoomForWholeQueue => {
+                write!(f, "Queued glyphs don't fit in the cache texture at once")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for GlyphCacheError {}
+
+/// Hashable cache key for a glyph rasterization, with the subpixel position quantized
+/// into bins.
+///
+/// Glyphs with the same font, id & scale whose positions fall into the same subpixel
+/// bins share a key, so a single rasterization at the [bin center](Self::glyph) can
+/// be reused, placed at the integer part of each position. Positions are quantized
+/// deterministically, the integer part is ignored.
+///
+/// # Example
+/// ```
+/// # use ab_glyph::*;
+/// # let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf")).unwrap();
+/// let a = font.glyph_id('a').with_scale_and_position(24.0, point(10.1, 20.0));
+/// let b = font.glyph_id('a').with_scale_and_position(24.0, point(55.2, 3.0));
+/// let c = font.glyph_id('a').with_scale_and_position(24.0, point(10.3, 20.0));
+///
+/// // 4 bins per axis: 0.1 & 0.2 are in the first bin, 0.3 is in the second
+/// assert_eq!(GlyphCacheKey::new(0, &a, 4), GlyphCacheKey::new(0, &b, 4));
+/// assert_ne!(GlyphCacheKey::new(0, &a, 4), GlyphCacheKey::new(0, &c, 4));
+///
+/// let key = GlyphCacheKey::new(0, &a, 4);
+/// assert_eq!(key.glyph().position, point(0.125, 0.125));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GlyphCacheKey {
+    /// Caller defined font id.
+    pub font_id: usize,
+    /// Glyph id.
+    pub id: GlyphId,
+    /// Horizontal pixel scale [`f32::to_bits`].
+    pub scale_x: u32,
+    /// Vertical pixel scale [`f32::to_bits`].
+    pub scale_y: u32,
+    /// Horizontal subpixel bin, `0..subpixel_bins`.
+    pub subpixel_x: u8,
+    /// Vertical subpixel bin, `0..subpixel_bins`.
+    pub subpixel_y: u8,
+    /// Number of subpixel bins per axis.
+    pub subpixel_bins: u8,
+}
+
+impl GlyphCacheKey {
+    /// Returns the key of a glyph using `subpixel_bins` per axis, at least 1.
+    pub fn new(font_id: usize, glyph: &Glyph, subpixel_bins: u8) -> Self {
+        let subpixel_bins = subpixel_bins.max(1);
+        let bin = |v: f32| {
+            let bin = ((v - v.floor()) * f32::from(subpixel_bins)) as u8;
+            bin.min(subpixel_bins - 1)
+        };
+        Self {
+            font_id,
+            id: glyph.id,
+            scale_x: glyph.scale.x.to_bits(),
+            scale_y: glyph.scale.y.to_bits(),
+            subpixel_x: bin(glyph.position.x),
+            subpixel_y: bin(glyph.position.y),
+            subpixel_bins,
+        }
+    }
+
+    /// Returns the glyph positioned at the center of the subpixel bins, relative to the
+    /// integer pixel origin `point(0.0, 0.0)`.
+    pub fn glyph(&self) -> Glyph {
+        let bins = f32::from(self.subpixel_bins.max(1));
+        let center = |bin: u8| (f32::from(bin) + 0.5) / bins;
+        Glyph {
+            id: self.id,
+            scale: PxScale {
+                x: f32::from_bits(self.scale_x),
+                y: f32::from_bits(self.scale_y),
+            },
+            position: point(center(self.subpixel_x), center(self.subpixel_y)),
+        }
+    }
+}
+
+/// Returns the integer part of a glyph position, the offset to place a rasterization
+/// of [`GlyphCacheKey::glyph`] at.
+#[inline]
+pub(crate) fn integer_position(position: Point) -> Point {
+    point(position.x.floor(), position.y.floor())
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    /// Allocated texture region including padding, `None` for glyphs without an outline.
+    region: Option<TextureRect>,
+    /// Pixel bounds relative to the integer part of the glyph position.
+    bounds: Rect,
+    /// Last [`GlyphCache::cache_queued`] batch this glyph was queued in.
+    last_used: u64,
+}
+
+/// Glyph texture atlas.
+///
+/// Rasterizes queued glyphs into a single-channel coverage texture, so each glyph is
+/// drawn once & then reused from the texture. Glyphs are keyed by [`GlyphCacheKey`],
+/// with 4 subpixel bins per axis by default, see
+/// [`with_subpixel_bins`](Self::with_subpixel_bins).
+///
+/// Glyphs are packed into horizontal shelves. When the texture is full the least
+/// recently queued glyphs are evicted to make room.
+///
+/// # Example
+/// ```
+/// use ab_glyph::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf"))?;
+/// let fonts = [font];
+/// let mut cache = GlyphCache::new(256, 256);
+///
+/// let glyph = fonts[0].glyph_id('a').with_scale_and_position(24.0, point(10.0, 30.0));
+/// cache.queue_glyph(0, glyph.clone());
+/// cache.cache_queued(&fonts)?;
+///
+/// // upload changed regions of `cache.texture()` to the gpu
+/// for rect in cache.take_dirty_rects() {
+///     # let _ = rect;
+/// }
+///
+/// let cached = cache.rect_for(0, &glyph).unwrap();
+/// // draw a quad at `cached.px_bounds` sampling `cached.tex_coords`
+/// # let _ = cached;
+/// # Ok(()) }
+/// ```
+#[derive(Clone)]
+pub struct GlyphCache {
+    width: u32,
+    height: u32,
+    texture: Vec<u8>,
+    packer: ShelfPacker,
+    entries: BTreeMap<GlyphCacheKey, Entry>,
+    queue: Vec<(usize, Glyph)>,
+    dirty: Vec<TextureRect>,
+    batch: u64,
+    subpixel_bins: u8,
+}
+
+impl fmt::Debug for GlyphCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GlyphCache")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("entries", &self.entries.len())
+            .field("queue", &self.queue.len())
+            .field("dirty", &self.dirty)
+            .field("subpixel_bins", &self.subpixel_bins)
+            .finish()
+    }
+}
+
+impl GlyphCache {
+    /// Creates an empty cache with a `width` x `height` texture.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            texture: vec![0; width as usize * height as usize],
+            packer: ShelfPacker::new(width, height),
+            entries: BTreeMap::new(),
+            queue: Vec::new(),
+            dirty: Vec::new(),
+            batch: 0,
+            subpixel_bins: SUBPIXEL_BINS,
+        }
+    }
+
+    /// Sets the number of subpixel position bins per axis, at least 1.
+    ///
+    /// More bins position glyphs more accurately, but cache more rasterizations.
+    pub fn with_subpixel_bins(mut self, subpixel_bins: u8) -> Self {
+        self.subpixel_bins = subpixel_bins.max(1);
+        self
+    }
+
+    /// Returns the texture `(width, height)`.
+    #[inline]
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the row-major texture coverage, one byte per pixel.
+    #[inline]
+    pub fn texture(&self) -> &[u8] {
+        &self.texture
+    }
+
+    /// Queues a glyph of the font `font_id` to be cached by the next call to
+    /// [`cache_queued`](Self::cache_queued).
+    pub fn queue_glyph(&mut self, font_id: usize, glyph: Glyph) {
+        self.queue.push((font_id, glyph));
+    }
+
+    /// Rasterizes all queued glyphs not already cached into the texture & clears the
+    /// queue. `fonts` are indexed by the queued font ids.
+    ///
+    /// Glyphs not queued in this batch are evicted, least recently queued first, when
+    /// there is no room for new glyphs. Changed texture regions are available from
+    /// [`take_dirty_rects`](Self::take_dirty_rects).
+    ///
+    /// # Panics
+    /// If a queued font id is out of bounds of `fonts`.
+    pub fn cache_queued<F: Font>(&mut self, fonts: &[F]) -> Result<(), GlyphCacheError> {
+        self.batch += 1;
+        let batch = self.batch;
+
+        let mut uncached: BTreeMap<GlyphCacheKey, OutlinedGlyph> = BTreeMap::new();
+        for (font_id, glyph) in self.queue.drain(..) {
+            let key = GlyphCacheKey::new(font_id, &glyph, self.subpixel_bins);
+            if let Some(entry) = self.entries.get_mut(&key) {
+                entry.last_used = batch;
+            } else if let btree_map::Entry::Vacant(vacant) = uncached.entry(key) {
+                match fonts[font_id].outline_glyph(key.glyph()) {
+                    Some(outlined) => {
+                        vacant.insert(outlined);
+                    }
+                    None => {
+                        let entry = Entry {
+                            region: None,
+                            bounds: Rect::default(),
+                            last_used: batch,
+                        };
+                        self.entries.insert(key, entry);
+                    }
+                }
+            }
+        }
+
+        // taller glyphs first packs shelves tighter
+        let mut uncached: Vec<_> = uncached
+            .into_iter()
+            .map(|(key, outlined)| (key, outlined.px_bounds(), outlined))
+            .collect();
+        uncached.sort_by(|(_, a, _), (_, b, _)| {
+            total_cmp(b.height(), a.height()).then(total_cmp(b.width(), a.width()))
+        });
+
+        let mut evictable: Option<Vec<GlyphCacheKey>> = None;
+        for (key, bounds, outlined) in uncached {
+            let width = bounds.width() as u32 + 2 * PADDING;
+            let height = bounds.height() as u32 + 2 * PADDING;
+            if width > self.width || height > self.height {
+                return Err(GlyphCacheError::GlyphTooLarge);
+            }
+
+            let region = loop {
+                if let Some(region) = self.packer.allocate(width, height) {
+                    break region;
+                }
+                let evictable = evictable.get_or_insert_with(|| {
+                    let mut lru: Vec<_> = self
+                        .entries
+                        .iter()
+                        .filter(|(_, e)| e.last_used < batch && e.region.is_some())
+                        .map(|(k, e)| (e.last_used, *k))
+                        .collect();
+                    // most recently used last, so popped last
+                    lru.sort_by(|a, b| b.cmp(a));
+                    lru.into_iter().map(|(_, k)| k).collect()
+                });
+                match evictable.pop().and_then(|k| self.entries.remove(&k)) {
+                    Some(Entry {
+                        region: Some(region),
+                        ..
+                    }) => self.packer.deallocate(region),
+                    Some(_) => {}
+                    None => return Err(GlyphCacheError::NoRoomForWholeQueue),
+                }
+            };
+
+            for row in region.y..region.y + region.height {
+                let start = (row * self.width + region.x) as usize;
+                self.texture[start..start + region.width as usize].fill(0);
+            }
+            let texture_width = self.width;
+            let texture = &mut self.texture;
+            outlined.draw(|x, y, coverage| {
+                let idx = (region.y + PADDING + y) * texture_width + region.x + PADDING + x;
+                texture[idx as usize] = (coverage * 255.0 + 0.5) as u8;
+            });
+
+            self.dirty.push(region);
+            let entry = Entry {
+                region: Some(region),
+                bounds,
+                last_used: batch,
+            };
+            self.entries.insert(key, entry);
+        }
+        Ok(())
+    }
+
+    /// Returns & clears the texture regions changed by [`cache_queued`](Self::cache_queued)
+    /// since the last call.
+    pub fn take_dirty_rects(&mut self) -> Vec<TextureRect> {
+        core::mem::take(&mut self.dirty)
+    }
+
+    /// Returns the texture location & pixel placement of a cached glyph.
+    ///
+    /// Returns `None` if the glyph is not cached or has no outline.
+    pub fn rect_for(&self, font_id: usize, glyph: &Glyph) -> Option<CachedGlyph> {
+        let key = GlyphCacheKey::new(font_id, glyph, self.subpixel_bins);
+        let offset = integer_position(glyph.position);
+        let entry = self.entries.get(&key)?;
+        let region = entry.region?;
+
+        let texture_rect = TextureRect {
+            x: region.x + PADDING,
+            y: region.y + PADDING,
+            width: region.width - 2 * PADDING,
+            height: region.height - 2 * PADDING,
+        };
+        let (w, h) = (self.width as f32, self.height as f32);
+        Some(CachedGlyph {
+            tex_coords: Rect {
+                min: point(texture_rect.x as f32 / w, texture_rect.y as f32 / h),
+                max: point(
+                    (texture_rect.x + texture_rect.width) as f32 / w,
+                    (texture_rect.y + texture_rect.height) as f32 / h,
+                ),
+            },
+            texture_rect,
+            px_bounds: Rect {
+                min: entry.bounds.min + offset,
+                max: entry.bounds.max + offset,
+            },
+        })
+    }
+}
+
+/// Allocates rectangles in horizontal shelves of varying height.
+#[derive(Clone, Debug)]
+struct ShelfPacker {
+    width: u32,
+    height: u32,
+    /// Shelves sorted by `y`, with no gaps between them.
+    shelves: Vec<Shelf>,
+}
+
+#[derive(Clone, Debug)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    /// Free `(x, width)` spans sorted by `x`.
+    free: Vec<(u32, u32)>,
+}
+
+impl Shelf {
+    fn new(y: u32, height: u32, width: u32) -> Self {
+        Self {
+            y,
+            height,
+            free: vec![(0, width)],
+        }
+    }
+
+    fn is_empty(&self, width: u32) -> bool {
+        self.free == [(0, width)]
+    }
+
+    /// Allocates `width` from the first free span wide enough.
+    fn allocate(&mut self, width: u32, height: u32) -> Option<TextureRect> {
+        let idx = self.free.iter().position(|&(_, w)| w >= width)?;
+        let (x, free) = &mut self.free[idx];
+        let rect = TextureRect {
+            x: *x,
+            y: self.y,
+            width,
+            height,
+        };
+        *x += width;
+        *free -= width;
+        if *free == 0 {
+            self.free.remove(idx);
+        }
+        Some(rect)
+    }
+
+    /// Frees a span, merging with adjacent free spans.
+    fn deallocate(&mut self, x: u32, width: u32) {
+        let idx = self.free.partition_point(|&(fx, _)| fx < x);
+        self.free.insert(idx, (x, width));
+        if idx + 1 < self.free.len() && x + width == self.free[idx + 1].0 {
+            self.free[idx].1 += self.free.remove(idx + 1).1;
+        }
+        if idx > 0 && self.free[idx - 1].0 + self.free[idx - 1].1 == x {
+            self.free[idx - 1].1 += self.free.remove(idx).1;
+        }
+    }
+}
+
+impl ShelfPacker {
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            shelves: Vec::new(),
+        }
+    }
+
+    fn allocate(&mut self, width: u32, height: u32) -> Option<TextureRect> {
+        // a used shelf of similar height
+        let similar = self
+            .shelves
+            .iter_mut()
+            .filter(|s| s.height >= height && s.height <= height + height / 2)
+            .filter(|s| s.free.iter().any(|&(_, w)| w >= width))
+            .min_by_key(|s| s.height);
+        if let Some(shelf) = similar {
+            return shelf.allocate(width, height);
+        }
+
+        // an empty shelf, shrunk to fit
+        let empty = self
+            .shelves
+            .iter()
+            .position(|s| s.height >= height && s.is_empty(self.width));
+        if let Some(idx) = empty {
+            let shelf = &mut self.shelves[idx];
+            if shelf.height > height {
+                let rest = Shelf::new(shelf.y + height, shelf.height - height, self.width);
+                shelf.height = height;
+                self.shelves.insert(idx + 1, rest);
+            }
+            return self.shelves[idx].allocate(width, height);
+        }
+
+        // a new shelf
+        let top = self.shelves.last().map_or(0, |s| s.y + s.height);
+        if self.height - top >= height {
+            let mut shelf = Shelf::new(top, height, self.width);
+            let rect = shelf.allocate(width, height);
+            self.shelves.push(shelf);
+            return rect;
+        }
+
+        // any taller shelf
+        self.shelves
+            .iter_mut()
+            .filter(|s| s.height >= height)
+            .find(|s| s.free.iter().any(|&(_, w)| w >= width))?
+            .allocate(width, height)
+    }
+
+    fn deallocate(&mut self, rect: TextureRect) {
+        let idx = match self.shelves.binary_search_by_key(&rect.y, |s| s.y) {
+            Ok(idx) => idx,
+            Err(_) => return,
+        };
+        self.shelves[idx].deallocate(rect.x, rect.width);
+        if !self.shelves[idx].is_empty(self.width) {
+            return;
+        }
+
+        // merge adjacent empty shelves
+        let mut idx = idx;
+        if idx + 1 < self.shelves.len() && self.shelves[idx + 1].is_empty(self.width) {
+            self.shelves[idx].height += self.shelves.remove(idx + 1).height;
+        }
+        if idx > 0 && self.shelves[idx - 1].is_empty(self.width) {
+            self.shelves[idx - 1].height += self.shelves.remove(idx).height;
+            idx -= 1;
+        }
+        // return trailing space to the unshelved area
+        if idx + 1 == self.shelves.len() {
+            self.shelves.pop();
+        }
+    }
+}
@@ -0,0 +1,286 @@
+#[cfg(all(feature = "libm", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+use crate::{point, GlyphImage, GlyphImageFormat, Point};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// A [`GlyphImage`] decoded into RGBA pixels.
+///
+/// Monochrome & grayscale bitmaps decode into black pixels with the bitmap value
+/// as alpha, so [`DecodedGlyphImage::alpha`] provides the coverage.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedGlyphImage {
+    /// Offset of the top-left pixel from the glyph origin on the baseline,
+    /// with y increasing downwards. So pixel `(x, y)` should be drawn at
+    /// `glyph.position + origin + (x, y)`.
+    pub origin: Point,
+    /// Scale of the image in pixels per em.
+    pub scale: f32,
+    /// Image width in pixels.
+    pub width: u32,
+    /// Image height in pixels.
+    pub height: u32,
+    /// Non-premultiplied RGBA pixel data, 4 bytes per pixel, in rows from the top-left.
+    pub rgba: Vec<u8>,
+}
+
+impl DecodedGlyphImage {
+    /// Alpha values of each pixel, in rows from the top-left.
+    pub fn alpha(&self) -> Vec<u8> {
+        self.rgba.chunks_exact(4).map(|px| px[3]).collect()
+    }
+
+    /// Returns the image resampled by the horizontal & vertical scale factors.
+    ///
+    /// Pixel dimensions are rounded, with a minimum of 1, `origin` & `scale` are
+    /// scaled accordingly. Empty images remain empty.
+    ///
+    /// # Example
+    /// ```
+    /// use ab_glyph::{Font, FontRef};
+    ///
+    /// # fn main() -> Result<(), ab_glyph::InvalidFont> {
+    /// let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/bitmaps.ttf"))?;
+    /// let image = font.glyph_raster_image(font.glyph_id('b'), 16).unwrap();
+    /// let decoded = image.decode().unwrap();
+    /// assert_eq!((decoded.width, decoded.height), (4, 4));
+    ///
+    /// let resized = decoded.resized(2.0, 1.5);
+    /// assert_eq!((resized.width, resized.height), (8, 6));
+    /// # Ok(()) }
+    /// ```
+    pub fn resized(&self, h_factor: f32, v_factor: f32) -> Self {
+        let src_w = self.width as usize;
+        let src_h = self.height as usize;
+        if src_w == 0 || src_h == 0 {
+            return Self {
+                origin: point(self.origin.x * h_factor, self.origin.y * v_factor),
+                scale: self.scale * v_factor,
+                width: 0,
+                height: 0,
+                rgba: Vec::new(),
+            };
+        }
+        let dst_w = ((src_w as f32 * h_factor).round() as usize).max(1);
+        let dst_h = ((src_h as f32 * v_factor).round() as usize).max(1);
+
+        // resample premultiplied values so transparent pixels don't bleed color
+        let premultiplied: Vec<[f32; 4]> = self
+            .rgba
+            .chunks_exact(4)
+            .map(|px| {
+                let a = f32::from(px[3]) / 255.0;
+                [
+                    f32::from(px[0]) * a,
+                    f32::from(px[1]) * a,
+                    f32::from(px[2]) * a,
+                    f32::from(px[3]),
+                ]
+            })
+            .collect();
+
+        let horizontal = resample(&premultiplied, src_w, src_h, dst_w, |x, y| y * src_w + x);
+        let resampled = resample(&horizontal, src_h, dst_w, dst_h, |y, x| y * dst_w + x);
+
+        // vertical resampling outputs columns, transpose back into rows
+        let mut rgba = Vec::with_capacity(dst_w * dst_h * 4);
+        for y in 0..dst_h {
+            for x in 0..dst_w {
+                let [r, g, b, a] = resampled[x * dst_h + y];
+                let unmultiply = |v: f32| match a {
+                    a if a > 0.0 => (v * 255.0 / a).round().min(255.0) as u8,
+                    _ => 0,
+                };
+                rgba.extend_from_slice(&[
+                    unmultiply(r),
+                    unmultiply(g),
+                    unmultiply(b),
+                    a.round().min(255.0) as u8,
+                ]);
+            }
+        }
+
+        Self {
+            origin: point(self.origin.x * h_factor, self.origin.y * v_factor),
+            scale: self.scale * v_factor,
+            width: dst_w as _,
+            height: dst_h as _,
+            rgba,
+        }
+    }
+}
+
+/// Resamples each line of `src` along one axis from `src_len` to `dst_len` pixels
+/// using a triangle filter, widened when downscaling to average all covered pixels.
+///
+/// `index(n, line)` returns the `src` index of the `n`th pixel along the axis in `line`.
+/// Output is ordered by line, with `dst_len` pixels per line.
+fn resample(
+    src: &[[f32; 4]],
+    src_len: usize,
+    lines: usize,
+    dst_len: usize,
+    index: impl Fn(usize, usize) -> usize,
+) -> Vec<[f32; 4]> {
+    let scale = dst_len as f32 / src_len as f32;
+    let radius = (1.0 / scale).max(1.0);
+
+    let mut out = Vec::with_capacity(lines * dst_len);
+    for line in 0..lines {
+        for n in 0..dst_len {
+            let center = (n as f32 + 0.5) / scale - 0.5;
+            let first = (center - radius).ceil().max(0.0) as usize;
+            let last = ((center + radius).floor() as usize).min(src_len - 1);
+
+            let mut px = [0.0; 4];
+            let mut total = 0.0;
+            for s in first..=last {
+                let weight = 1.0 - (s as f32 - center).abs() / radius;
+                if weight > 0.0 {
+                    let src = src[index(s, line)];
+                    for (v, s) in px.iter_mut().zip(src.iter()) {
+                        *v += s * weight;
+                    }
+                    total += weight;
+                }
+            }
+            if total > 0.0 {
+                px.iter_mut().for_each(|v| *v /= total);
+            }
+            out.push(px);
+        }
+    }
+    out
+}
+
+impl GlyphImage<'_> {
+    /// Decodes the image data into RGBA pixels at the image's own scale.
+    ///
+    /// Returns `None` if the data is invalid, or for [`GlyphImageFormat::Png`] images
+    /// when the `png` feature is not enabled.
+    ///
+    /// # Example
+    /// ```
+    /// use ab_glyph::{Font, FontRef, GlyphImageFormat};
+    ///
+    /// # fn main() -> Result<(), ab_glyph::InvalidFont> {
+    /// let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/bitmaps.ttf"))?;
+    /// let image = font.glyph_raster_image(font.glyph_id('a'), 14).unwrap();
+    /// assert_eq!(image.format, GlyphImageFormat::BitmapGray8);
+    ///
+    /// let decoded = image.decode().unwrap();
+    /// assert_eq!((decoded.width, decoded.height), (10, 8));
+    /// assert_eq!(decoded.alpha()[..4], [0, 0, 255, 255]);
+    /// # Ok(()) }
+    /// ```
+    pub fn decode(&self) -> Option<DecodedGlyphImage> {
+        let (width, height) = (u32::from(self.width), u32::from(self.height));
+
+        let (width, height, rgba) = match self.format {
+            GlyphImageFormat::Png => decode_png(self.data)?,
+            GlyphImageFormat::BitmapPremulBgra32 => {
+                let len = width as usize * height as usize * 4;
+                let rgba = self
+                    .data
+                    .get(..len)?
+                    .chunks_exact(4)
+                    .flat_map(|px| {
+                        let [b, g, r, a] = [px[0], px[1], px[2], px[3]];
+                        let unmultiply = |v: u8| match a {
+                            0 => 0,
+                            a => ((u32::from(v) * 255 + u32::from(a) / 2) / u32::from(a)).min(255)
+                                as u8,
+                        };
+                        [unmultiply(r), unmultiply(g), unmultiply(b), a]
+                    })
+                    .collect();
+                (width, height, rgba)
+            }
+            format => {
+                let (depth, packed) = match format {
+                    GlyphImageFormat::BitmapMono => (1, false),
+                    GlyphImageFormat::BitmapMonoPacked => (1, true),
+                    GlyphImageFormat::BitmapGray2 => (2, false),
+                    GlyphImageFormat::BitmapGray2Packed => (2, true),
+                    GlyphImageFormat::BitmapGray4 => (4, false),
+                    GlyphImageFormat::BitmapGray4Packed => (4, true),
+                    _ => (8, false),
+                };
+                let rgba = decode_gray(self.data, width as usize, height as usize, depth, packed)?
+                    .into_iter()
+                    .flat_map(|alpha| [0, 0, 0, alpha])
+                    .collect();
+                (width, height, rgba)
+            }
+        };
+
+        Some(DecodedGlyphImage {
+            origin: point(self.origin.x, -(self.origin.y + height as f32)),
+            scale: self.scale,
+            width,
+            height,
+            rgba,
+        })
+    }
+}
+
+/// Reads `depth` bit grayscale values, MSB first, into 8 bit values.
+/// Rows are byte aligned unless `packed`.
+fn decode_gray(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    depth: usize,
+    packed: bool,
+) -> Option<Vec<u8>> {
+    let max = (1_u16 << depth) - 1;
+    let row_bits = match packed {
+        true => width * depth,
+        false => (width * depth + 7) / 8 * 8,
+    };
+
+    let mut out = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            let bit = y * row_bits + x * depth;
+            let byte = *data.get(bit / 8)?;
+            let value = (u16::from(byte) >> (8 - depth - bit % 8)) & max;
+            out.push((value * 255 / max) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// Decodes PNG data into `(width, height, rgba)`.
+#[cfg(feature = "png")]
+fn decode_png(data: &[u8]) -> Option<(u32, u32, Vec<u8>)> {
+    use png2::{ColorType, Decoder, Transformations};
+
+    let mut decoder = Decoder::new(data);
+    decoder.set_transformations(Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().ok()?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).ok()?;
+    buf.truncate(info.buffer_size());
+
+    let rgba = match info.color_type {
+        ColorType::Rgba => buf,
+        ColorType::Rgb => buf
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        ColorType::Grayscale => buf.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+        // indexed color is expanded by `normalize_to_color8`
+        ColorType::Indexed => return None,
+    };
+    Some((info.width, info.height, rgba))
+}
+
+#[cfg(not(feature = "png"))]
+fn decode_png(_: &[u8]) -> Option<(u32, u32, Vec<u8>)> {
+    None
+}
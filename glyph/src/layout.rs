@@ -0,0 +1,694 @@
+//! Paragraph layout of text sections into positioned glyphs.
+mod bidi;
+mod caret;
+
+#[cfg(feature = "std")]
+use crate::FontFallback;
+use crate::{point, scale::is_mandatory_break, Font, Glyph, GlyphId, Point, PxScale, ScaleFont};
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+use core::ops::Range;
+use unicode_bidi::BidiInfo;
+#[cfg(feature = "std")]
+use unicode_segmentation::UnicodeSegmentation;
+use xi_unicode::LineBreakIterator;
+
+/// A section of text with a font & scale, laid out by [`Layout::calculate_glyphs`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SectionText<'a> {
+    /// Text to lay out.
+    pub text: &'a str,
+    /// Pixel scale of the text.
+    pub scale: PxScale,
+    /// Index of the font in the `fonts` passed to [`Layout::calculate_glyphs`].
+    pub font_id: usize,
+}
+
+impl Default for SectionText<'_> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            text: "",
+            scale: PxScale::from(16.0),
+            font_id: 0,
+        }
+    }
+}
+
+/// Layout position & bounds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SectionGeometry {
+    /// Position of the layout on the screen, in pixels.
+    ///
+    /// This is the anchor of the text according to the [`Layout`] alignment,
+    /// e.g. the top-left for [`HorizontalAlign::Left`] & [`VerticalAlign::Top`], or the
+    /// center for [`HorizontalAlign::Center`] & [`VerticalAlign::Center`].
+    pub screen_position: Point,
+    /// Max `(width, height)` of the text, in pixels, arranged around the `screen_position`
+    /// in the same way as the text.
+    ///
+    /// Lines are wrapped to fit the width. Lines starting below the height are omitted.
+    pub bounds: (f32, f32),
+}
+
+impl Default for SectionGeometry {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            screen_position: Point::default(),
+            bounds: (f32::INFINITY, f32::INFINITY),
+        }
+    }
+}
+
+/// Horizontal alignment of lines relative to the [`SectionGeometry::screen_position`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HorizontalAlign {
+    /// Lines start at the position.
+    Left,
+    /// Lines are centered on the position.
+    Center,
+    /// Lines end at the position.
+    Right,
+    /// Lines start at the position & wrapped lines are stretched to the bounds width by
+    /// widening whitespace. Lines ending in a hard break, or the last line, are left aligned.
+    Justify,
+}
+
+impl Default for HorizontalAlign {
+    #[inline]
+    fn default() -> Self {
+        Self::Left
+    }
+}
+
+/// Vertical alignment of all lines relative to the [`SectionGeometry::screen_position`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VerticalAlign {
+    /// The top of the first line is at the position.
+    Top,
+    /// The lines are centered on the position.
+    Center,
+    /// The bottom of the last line is at the position.
+    Bottom,
+}
+
+impl Default for VerticalAlign {
+    #[inline]
+    fn default() -> Self {
+        Self::Top
+    }
+}
+
+/// A glyph positioned by [`Layout::calculate_glyphs`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SectionGlyph {
+    /// Index of the [`SectionText`] this glyph is from.
+    pub section_index: usize,
+    /// Byte index of the glyph's character in the section text.
+    pub byte_index: usize,
+    /// Glyph with scale & baseline position.
+    pub glyph: Glyph,
+    /// Font id of the glyph, the section's font unless laid out with a fallback font.
+    pub font_id: usize,
+}
+
+/// Paragraph layout with line breaking & alignment.
+///
+/// Text is wrapped at [UAX #14](https://www.unicode.org/reports/tr14/) line break
+/// opportunities to fit the [`SectionGeometry::bounds`] width, with hard breaks, e.g. `'\n'`,
+/// always starting a new line. Words wider than the bounds are not broken.
+///
+/// Mixed left-to-right & right-to-left text is reordered per line using the
+/// [UAX #9](https://www.unicode.org/reports/tr9/) bidirectional algorithm, with each
+/// paragraph's direction taken from its first strong character. Characters in right-to-left
+/// runs, like brackets, are mirrored.
+///
+/// # Example
+/// ```
+/// use ab_glyph::{point, FontRef, HorizontalAlign, Layout, SectionGeometry, SectionText};
+///
+/// # fn main() -> Result<(), ab_glyph::InvalidFont> {
+/// let regular = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf"))?;
+/// let italic = FontRef::try_from_slice(include_bytes!("../../dev/fonts/OpenSans-Italic.ttf"))?;
+///
+/// let layout = Layout {
+///     h_align: HorizontalAlign::Center,
+///     ..Layout::default()
+/// };
+/// let geometry = SectionGeometry {
+///     screen_position: point(200.0, 10.0),
+///     bounds: (400.0, f32::INFINITY),
+/// };
+/// let sections = [
+///     SectionText {
+///         text: "Hello ",
+///         scale: 24.0.into(),
+///         font_id: 0,
+///     },
+///     SectionText {
+///         text: "italic\nworld",
+///         scale: 20.0.into(),
+///         font_id: 1,
+///     },
+/// ];
+///
+/// let glyphs = layout.calculate_glyphs(&[regular, italic], &geometry, &sections);
+/// // control characters, like '\n', have no glyph
+/// assert_eq!(glyphs.len(), 6 + 11);
+/// assert_eq!(glyphs[6].section_index, 1);
+/// assert_eq!(glyphs[12].byte_index, "italic\n".len());
+/// // "world" is on the second line
+/// assert!(glyphs[12].glyph.position.y > glyphs[0].glyph.position.y);
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Layout {
+    /// Horizontal alignment of each line.
+    pub h_align: HorizontalAlign,
+    /// Vertical alignment of the lines.
+    pub v_align: VerticalAlign,
+}
+
+impl Layout {
+    /// Lays out `sections` into positioned glyphs, line by line with each line in visual,
+    /// left-to-right, order. This is the text order unless there is right-to-left text.
+    ///
+    /// Each section's `font_id` indexes into `fonts`. Consecutive glyphs with the same
+    /// font & scale are kerned. Control & line break characters produce no glyph.
+    ///
+    /// # Panics
+    /// If a section `font_id` is not a valid index of `fonts`.
+    pub fn calculate_glyphs<F: Font>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[SectionText<'_>],
+    ) -> Vec<SectionGlyph> {
+        self.calculate_text(fonts, geometry, sections).into_glyphs()
+    }
+
+    /// Lays out `sections` like [`calculate_glyphs`](Self::calculate_glyphs), keeping the
+    /// text & lines for caret positioning, hit-testing & selection. See [`LaidOutText`].
+    ///
+    /// # Panics
+    /// If a section `font_id` is not a valid index of `fonts`.
+    pub fn calculate_text<F: Font>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[SectionText<'_>],
+    ) -> LaidOutText {
+        self.calculate(fonts, geometry, sections, |_, sc| {
+            sections[sc.section_index].font_id
+        })
+    }
+
+    /// Lays out `sections` like [`calculate_glyphs`](Self::calculate_glyphs) using the
+    /// [`FontFallback`] fonts, where each section's `font_id` is the preferred font index.
+    ///
+    /// Grapheme clusters the preferred font doesn't cover use the first covering font of the
+    /// fallback chain, with that font's advances, kerning & line metrics. The resolved font
+    /// is each glyph's [`SectionGlyph::font_id`].
+    ///
+    /// # Example
+    /// ```
+    /// use ab_glyph::{FontArc, FontFallback, Layout, SectionGeometry, SectionText};
+    ///
+    /// # fn main() -> Result<(), ab_glyph::InvalidFont> {
+    /// let latin = FontArc::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf"))?;
+    /// let symbols = FontArc::try_from_slice(include_bytes!("../../dev/fonts/DejaVuSansMono.ttf"))?;
+    /// let fonts = FontFallback::new(latin).with_fallback(symbols);
+    ///
+    /// let sections = [SectionText {
+    ///     text: "a☣b",
+    ///     ..SectionText::default()
+    /// }];
+    /// let glyphs =
+    ///     Layout::default().calculate_glyphs_with_fallback(&fonts, &SectionGeometry::default(), &sections);
+    ///
+    /// let font_ids: Vec<_> = glyphs.iter().map(|g| g.font_id).collect();
+    /// assert_eq!(font_ids, [0, 1, 0]);
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// # Panics
+    /// If a section `font_id` is not a valid index of the fallback fonts.
+    #[cfg(feature = "std")]
+    pub fn calculate_glyphs_with_fallback(
+        &self,
+        fallback: &FontFallback,
+        geometry: &SectionGeometry,
+        sections: &[SectionText<'_>],
+    ) -> Vec<SectionGlyph> {
+        self.calculate_text_with_fallback(fallback, geometry, sections)
+            .into_glyphs()
+    }
+
+    /// Lays out `sections` like
+    /// [`calculate_glyphs_with_fallback`](Self::calculate_glyphs_with_fallback), keeping the
+    /// text & lines for caret positioning, hit-testing & selection. See [`LaidOutText`].
+    ///
+    /// # Panics
+    /// If a section `font_id` is not a valid index of the fallback fonts.
+    #[cfg(feature = "std")]
+    pub fn calculate_text_with_fallback(
+        &self,
+        fallback: &FontFallback,
+        geometry: &SectionGeometry,
+        sections: &[SectionText<'_>],
+    ) -> LaidOutText {
+        // resolved font of each grapheme cluster, by start index in the whole text
+        let mut clusters = Vec::new();
+        let mut section_start = 0;
+        for section in sections {
+            for (idx, cluster) in section.text.grapheme_indices(true) {
+                let font_id = fallback.resolve_cluster_preferring(section.font_id, cluster);
+                clusters.push((section_start + idx, font_id));
+            }
+            section_start += section.text.len();
+        }
+
+        self.calculate(fallback.fonts(), geometry, sections, |text_index, _| {
+            let cluster = clusters.partition_point(|(start, _)| *start <= text_index) - 1;
+            clusters[cluster].1
+        })
+    }
+
+    /// Lays out `sections` using `font_id` to choose the font of each character, by its
+    /// index in the whole text.
+    fn calculate<F: Font>(
+        &self,
+        fonts: &[F],
+        geometry: &SectionGeometry,
+        sections: &[SectionText<'_>],
+        font_id: impl Fn(usize, &SectionChar) -> usize,
+    ) -> LaidOutText {
+        let (max_width, max_height) = geometry.bounds;
+
+        // breaking & bidi run over the whole text so they work across sections
+        let text: String = sections.iter().map(|s| s.text).collect();
+        let bidi = BidiInfo::new(&text, None);
+        let bidi = Some(&bidi).filter(|bidi| bidi.has_rtl());
+
+        let mut lines = break_lines(fonts, sections, &text, bidi, max_width, font_id);
+        if let Some(bidi) = bidi {
+            for line in &mut lines {
+                line.reorder(fonts, bidi);
+            }
+        }
+
+        let text_height = lines.iter().map(Line::height).sum::<f32>()
+            - lines.last().map_or(0.0, |line| line.line_gap);
+        let Point { x, y } = geometry.screen_position;
+        let (bounds_top, mut line_top) = match self.v_align {
+            VerticalAlign::Top => (y, y),
+            VerticalAlign::Center => (y - max_height * 0.5, y - text_height * 0.5),
+            VerticalAlign::Bottom => (y - max_height, y - text_height),
+        };
+
+        let last = lines.len().saturating_sub(1);
+        let mut visible = 0;
+        for (idx, line) in lines.iter_mut().enumerate() {
+            if max_height.is_finite() && line_top >= bounds_top + max_height {
+                break;
+            }
+            visible += 1;
+
+            let left = match self.h_align {
+                HorizontalAlign::Left | HorizontalAlign::Justify => x,
+                HorizontalAlign::Center => x - line.width * 0.5,
+                HorizontalAlign::Right => x - line.width,
+            };
+            if self.h_align == HorizontalAlign::Justify
+                && idx != last
+                && !line.hard_break
+                && max_width.is_finite()
+            {
+                line.justify(max_width);
+            }
+
+            line.position(point(left, line_top + line.ascent));
+            line.top = line_top;
+            line_top += line.height();
+        }
+        lines.truncate(visible);
+        LaidOutText { text, lines }
+    }
+}
+
+/// Text laid out by [`Layout::calculate_text`], providing positioned glyphs and mapping
+/// between positions & byte indices for text editing.
+///
+/// Byte indices are into the [`text`](Self::text), the concatenated text of all sections.
+///
+/// # Example
+/// ```
+/// use ab_glyph::{point, FontRef, Layout, SectionGeometry, SectionText};
+///
+/// # fn main() -> Result<(), ab_glyph::InvalidFont> {
+/// let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf"))?;
+/// let sections = [SectionText {
+///     text: "Hello\nworld",
+///     scale: 24.0.into(),
+///     font_id: 0,
+/// }];
+/// let text = Layout::default().calculate_text(&[font], &SectionGeometry::default(), &sections);
+///
+/// // caret before "world"
+/// let caret = text.caret(6).unwrap();
+/// assert_eq!(caret.min.x, 0.0);
+/// // clicking the caret position finds the same index
+/// assert_eq!(text.hit_test(point(caret.min.x, caret.min.y + 1.0)), 6);
+///
+/// // selecting "lo\nwo" spans two lines
+/// assert_eq!(text.selection_rects(3..8).len(), 2);
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+pub struct LaidOutText {
+    text: String,
+    lines: Vec<Line>,
+}
+
+impl LaidOutText {
+    /// The concatenated text of all sections.
+    #[inline]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Positioned glyphs, as returned by [`Layout::calculate_glyphs`].
+    #[inline]
+    pub fn glyphs(&self) -> impl Iterator<Item = &SectionGlyph> + '_ {
+        self.lines.iter().flat_map(|line| &line.glyphs)
+    }
+
+    /// Returns the positioned glyphs.
+    #[inline]
+    pub fn into_glyphs(self) -> Vec<SectionGlyph> {
+        self.lines
+            .into_iter()
+            .flat_map(|line| line.glyphs)
+            .collect()
+    }
+}
+
+/// A laid out line of glyphs.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Line {
+    /// Glyphs, positioned relative to the line start on the baseline until
+    /// [`Line::position`]ed.
+    pub(crate) glyphs: Vec<SectionGlyph>,
+    /// Layout info for each glyph.
+    info: Vec<GlyphInfo>,
+    /// Byte range of the line in the whole text, including control characters.
+    pub(crate) text_range: Range<usize>,
+    /// Left of the line start, after positioning.
+    pub(crate) left: f32,
+    /// Top of the line, after positioning.
+    pub(crate) top: f32,
+    /// Width excluding trailing whitespace.
+    pub(crate) width: f32,
+    pub(crate) ascent: f32,
+    pub(crate) descent: f32,
+    pub(crate) line_gap: f32,
+    /// Whether the line ends with a hard break.
+    pub(crate) hard_break: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct GlyphInfo {
+    /// Byte index of the glyph's character in the whole text.
+    text_index: usize,
+    advance: f32,
+    whitespace: bool,
+    /// Whether this is trailing whitespace, hanging outside the line width.
+    trailing: bool,
+    /// Whether this glyph is in a right-to-left run.
+    rtl: bool,
+}
+
+impl Line {
+    /// Height including the line gap.
+    #[inline]
+    pub(crate) fn height(&self) -> f32 {
+        self.ascent - self.descent + self.line_gap
+    }
+
+    fn include_metrics<F: Font>(&mut self, font: &F, scale: PxScale) {
+        let font = font.as_scaled(scale);
+        self.ascent = self.ascent.max(font.ascent());
+        self.descent = self.descent.min(font.descent());
+        self.line_gap = self.line_gap.max(font.line_gap());
+    }
+
+    /// Marks glyphs after the last visible glyph as trailing.
+    fn mark_trailing(&mut self) {
+        for info in self.info.iter_mut().rev() {
+            if !info.whitespace {
+                break;
+            }
+            info.trailing = true;
+        }
+    }
+
+    /// Reorders glyphs into visual order, with trailing whitespace hanging beyond
+    /// the line start or end according to the paragraph direction.
+    fn reorder<F: Font>(&mut self, fonts: &[F], bidi: &BidiInfo) {
+        if self.glyphs.is_empty() {
+            return;
+        }
+        let levels = bidi::line_levels(bidi, self.text_range.clone());
+        let glyph_levels: Vec<_> = self
+            .info
+            .iter()
+            .map(|info| levels[info.text_index - self.text_range.start])
+            .collect();
+        if glyph_levels.iter().all(|level| level.is_ltr()) {
+            return;
+        }
+
+        for (info, level) in self.info.iter_mut().zip(&glyph_levels) {
+            info.rtl = level.is_rtl();
+        }
+        let order = BidiInfo::reorder_visual(&glyph_levels);
+        let (glyphs, info) = (&self.glyphs, &self.info);
+        self.glyphs = order.iter().map(|idx| glyphs[*idx].clone()).collect();
+        self.info = order.iter().map(|idx| info[*idx]).collect();
+
+        let mut caret = 0.0;
+        let mut last_glyph = None;
+        let (mut visible_start, mut visible_end) = (f32::INFINITY, 0.0_f32);
+        for (glyph, info) in self.glyphs.iter_mut().zip(&self.info) {
+            let Glyph { id, scale, .. } = glyph.glyph;
+            caret += kern(fonts, last_glyph, glyph.font_id, scale, id);
+            last_glyph = Some((glyph.font_id, scale, id));
+            glyph.glyph.position.x = caret;
+            if !info.trailing {
+                visible_start = visible_start.min(caret);
+                visible_end = visible_end.max(caret + info.advance);
+            }
+            caret += info.advance;
+        }
+        if visible_start.is_finite() {
+            // start visible glyphs at zero, so trailing whitespace may be negative
+            for glyph in &mut self.glyphs {
+                glyph.glyph.position.x -= visible_start;
+            }
+            self.width = visible_end - visible_start;
+        }
+    }
+
+    /// Widens interior whitespace so the line fills `width`.
+    fn justify(&mut self, width: f32) {
+        let spaces = self
+            .info
+            .iter()
+            .filter(|info| info.whitespace && !info.trailing)
+            .count();
+        if spaces == 0 || self.width >= width {
+            return;
+        }
+        let extra = (width - self.width) / spaces as f32;
+        let mut shift = 0.0;
+        for (glyph, info) in self.glyphs.iter_mut().zip(&mut self.info) {
+            glyph.glyph.position.x += shift;
+            if info.whitespace && !info.trailing {
+                info.advance += extra;
+                shift += extra;
+            }
+        }
+        self.width = width;
+    }
+
+    /// Offsets all glyphs by the line's baseline start `position`.
+    fn position(&mut self, position: Point) {
+        self.left = position.x;
+        for glyph in &mut self.glyphs {
+            glyph.glyph.position += position;
+        }
+    }
+}
+
+/// A character of the section text.
+#[derive(Clone, Copy)]
+struct SectionChar {
+    section_index: usize,
+    byte_index: usize,
+    c: char,
+}
+
+/// Greedily fills lines with words between line break opportunities of the whole `text`.
+fn break_lines<F: Font>(
+    fonts: &[F],
+    sections: &[SectionText<'_>],
+    text: &str,
+    bidi: Option<&BidiInfo>,
+    max_width: f32,
+    font_id: impl Fn(usize, &SectionChar) -> usize,
+) -> Vec<Line> {
+    let chars = sections.iter().enumerate().flat_map(|(section_index, s)| {
+        s.text
+            .char_indices()
+            .map(move |(byte_index, c)| SectionChar {
+                section_index,
+                byte_index,
+                c,
+            })
+    });
+    let mut chars = text.char_indices().zip(chars).peekable();
+
+    let mut lines = Vec::new();
+    let mut line = Line::default();
+    let mut caret = 0.0;
+    let mut last_glyph: Option<(usize, PxScale, GlyphId)> = None;
+    let mut end_line = |line: &mut Line, next_start: usize| {
+        line.mark_trailing();
+        let mut next = Line {
+            text_range: next_start..next_start,
+            ..Line::default()
+        };
+        core::mem::swap(line, &mut next);
+        lines.push(next);
+    };
+
+    for (break_index, hard) in LineBreakIterator::new(text) {
+        let word_start = line.text_range.end;
+        // collect the word up to the break
+        let mut word: Vec<(usize, SectionChar, usize, Option<GlyphId>, f32)> = Vec::new();
+        while let Some(&((idx, c), sc)) = chars.peek() {
+            if idx >= break_index {
+                break;
+            }
+            chars.next();
+            let section = &sections[sc.section_index];
+            let font_id = font_id(idx, &sc);
+            let font = fonts[font_id].as_scaled(section.scale);
+            let id = match sc.c.is_control() || is_mandatory_break(sc.c) {
+                true => None,
+                false => Some(font.glyph_id(match bidi {
+                    Some(bidi) => bidi::mirror(c, bidi.levels[idx]),
+                    None => c,
+                })),
+            };
+            let advance = id.map_or(0.0, |id| font.h_advance(id));
+            word.push((idx, sc, font_id, id, advance));
+        }
+        // the final break is reported as hard, so also check for a break character
+        let hard = hard
+            && word
+                .last()
+                .map_or(false, |(_, sc, ..)| is_mandatory_break(sc.c));
+
+        // measure the word continuing the line to decide whether to wrap
+        let measure = |mut last: Option<(usize, PxScale, GlyphId)>, start: f32| {
+            let mut x = start;
+            let mut visible_end = start;
+            for (_, sc, font_id, id, advance) in &word {
+                let scale = sections[sc.section_index].scale;
+                if let Some(id) = *id {
+                    x += kern(fonts, last, *font_id, scale, id);
+                    last = Some((*font_id, scale, id));
+                    x += advance;
+                    if !sc.c.is_whitespace() {
+                        visible_end = x;
+                    }
+                }
+            }
+            visible_end
+        };
+        if !line.glyphs.is_empty() && measure(last_glyph, caret) > max_width {
+            end_line(&mut line, word_start);
+            caret = 0.0;
+            last_glyph = None;
+        }
+
+        for (text_index, sc, font_id, id, advance) in word {
+            let scale = sections[sc.section_index].scale;
+            line.include_metrics(&fonts[font_id], scale);
+            let id = match id {
+                Some(id) => id,
+                None => continue,
+            };
+            caret += kern(fonts, last_glyph, font_id, scale, id);
+            last_glyph = Some((font_id, scale, id));
+            line.glyphs.push(SectionGlyph {
+                section_index: sc.section_index,
+                byte_index: sc.byte_index,
+                glyph: id.with_scale_and_position(scale, point(caret, 0.0)),
+                font_id,
+            });
+            line.info.push(GlyphInfo {
+                text_index,
+                advance,
+                whitespace: sc.c.is_whitespace(),
+                trailing: false,
+                rtl: false,
+            });
+            caret += advance;
+            if !sc.c.is_whitespace() {
+                line.width = caret;
+            }
+        }
+        line.text_range.end = break_index;
+
+        if hard {
+            line.hard_break = true;
+            end_line(&mut line, break_index);
+            caret = 0.0;
+            last_glyph = None;
+        }
+    }
+
+    if !line.glyphs.is_empty() || lines.last().map_or(false, |l: &Line| l.hard_break) {
+        if line.glyphs.is_empty() {
+            // a trailing hard break starts an empty final line
+            if let Some(section) = sections.last() {
+                line.include_metrics(&fonts[section.font_id], section.scale);
+            }
+        }
+        line.mark_trailing();
+        lines.push(line);
+    }
+    lines
+}
+
+/// Kerning between the `last` glyph & `id` if they share the same font & scale.
+#[inline]
+fn kern<F: Font>(
+    fonts: &[F],
+    last: Option<(usize, PxScale, GlyphId)>,
+    font_id: usize,
+    scale: PxScale,
+    id: GlyphId,
+) -> f32 {
+    match last {
+        Some((last_font_id, last_scale, last))
+            if last_font_id == font_id && last_scale == scale =>
+        {
+            fonts[font_id].as_scaled(scale).kern(last, id)
+        }
+        _ => 0.0,
+    }
+}
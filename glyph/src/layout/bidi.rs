@@ -0,0 +1,59 @@
+//! Line levels & mirroring for the bidirectional algorithm, UAX #9 rules L1 & L4.
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::ops::Range;
+use unicode_bidi::{BidiClass, BidiInfo, Level};
+
+/// Returns the mirrored form of `c`, e.g. `(` -> `)`, when at a right-to-left `level`.
+#[inline]
+pub(super) fn mirror(c: char, level: Level) -> char {
+    match level.is_rtl() {
+        true => unicode_bidi_mirroring::get_mirrored(c).unwrap_or(c),
+        false => c,
+    }
+}
+
+/// Returns the resolved levels of each byte in the `line` range of the text with
+/// whitespace reset to the paragraph level, rule L1.
+pub(super) fn line_levels(bidi: &BidiInfo, line: Range<usize>) -> Vec<Level> {
+    let para_level = bidi
+        .paragraphs
+        .iter()
+        .find(|para| para.range.contains(&line.start))
+        .map_or(Level::ltr(), |para| para.level);
+
+    let mut levels = bidi.levels[line.clone()].to_vec();
+    let classes = &bidi.original_classes[line.clone()];
+
+    // whitespace before separators & the line end is reset
+    let mut reset = true;
+    for (idx, c) in bidi.text[line].char_indices().rev() {
+        let reset_char = match classes[idx] {
+            BidiClass::B | BidiClass::S => {
+                reset = true;
+                true
+            }
+            BidiClass::WS
+            | BidiClass::FSI
+            | BidiClass::LRI
+            | BidiClass::RLI
+            | BidiClass::PDI
+            | BidiClass::BN
+            | BidiClass::LRE
+            | BidiClass::RLE
+            | BidiClass::LRO
+            | BidiClass::RLO
+            | BidiClass::PDF => reset,
+            _ => {
+                reset = false;
+                false
+            }
+        };
+        if reset_char {
+            levels[idx..idx + c.len_utf8()]
+                .iter_mut()
+                .for_each(|level| *level = para_level);
+        }
+    }
+    levels
+}
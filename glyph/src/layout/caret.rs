@@ -0,0 +1,145 @@
+//! Caret positioning, hit-testing & selection of laid out text.
+use super::{GlyphInfo, LaidOutText, Line};
+use crate::{point, scale::is_mandatory_break, Point, Rect};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+impl LaidOutText {
+    /// Returns the caret rectangle at byte `index`, with zero width, from the top of the line
+    /// to its descent.
+    ///
+    /// Any char boundary is valid, a boundary at a line wrap is at the start of the next
+    /// line. Returns `None` if `index` is not a char boundary or is in a line omitted by the
+    /// bounds height.
+    ///
+    /// In bidirectional text the caret is at the leading edge of the character at `index`,
+    /// the left edge for left-to-right & the right edge for right-to-left.
+    pub fn caret(&self, index: usize) -> Option<Rect> {
+        if !self.text.is_char_boundary(index) {
+            return None;
+        }
+        let line = self
+            .lines
+            .iter()
+            .find(|line| index < line.text_range.end)
+            .or_else(|| self.lines.last().filter(|l| index == l.text_range.end))
+            .filter(|line| index >= line.text_range.start)?;
+
+        let x = line.caret_x(index);
+        Some(Rect {
+            min: point(x, line.top),
+            max: point(x, line.top + line.ascent - line.descent),
+        })
+    }
+
+    /// Returns the grapheme cluster boundary nearest to `position`, e.g. to place a caret
+    /// where the text was clicked.
+    ///
+    /// The line is chosen by the position's y, clamping to the first & last lines. Returns
+    /// `0` if there are no lines.
+    pub fn hit_test(&self, position: Point) -> usize {
+        let last = match self.lines.len().checked_sub(1) {
+            Some(last) => last,
+            None => return 0,
+        };
+        let (idx, line) = self
+            .lines
+            .iter()
+            .enumerate()
+            .find(|(_, line)| position.y < line.top + line.height())
+            .unwrap_or((last, &self.lines[last]));
+
+        let Range { start, end } = line.text_range;
+        // the end of a wrapped line is the start of the next
+        let content_end = match line.hard_break {
+            true => {
+                start
+                    + self.text[start..end]
+                        .trim_end_matches(is_mandatory_break)
+                        .len()
+            }
+            false => end,
+        };
+        let wrapped = idx != last && !line.hard_break;
+        let end_boundary = match wrapped {
+            true => None,
+            false => Some(content_end),
+        };
+
+        self.text[start..content_end]
+            .grapheme_indices(true)
+            .map(|(offset, _)| start + offset)
+            .chain(end_boundary)
+            .map(|index| (index, (line.caret_x(index) - position.x).abs()))
+            .fold(
+                None,
+                |nearest: Option<(usize, f32)>, (index, distance)| match nearest {
+                    Some((_, nearest_distance)) if nearest_distance <= distance => nearest,
+                    _ => Some((index, distance)),
+                },
+            )
+            .map_or(start, |(index, _)| index)
+    }
+
+    /// Returns rectangles covering the glyphs of the byte `range`, at least one per line
+    /// with selected glyphs. Rectangles span the full line height, including the line gap,
+    /// so selections of consecutive lines are continuous.
+    ///
+    /// Bidirectional text may produce multiple rectangles per line.
+    pub fn selection_rects(&self, range: Range<usize>) -> Vec<Rect> {
+        let mut rects = Vec::new();
+        for line in &self.lines {
+            if range.start >= line.text_range.end || range.end <= line.text_range.start {
+                continue;
+            }
+            let (top, bottom) = (line.top, line.top + line.height());
+            let mut current: Option<Rect> = None;
+            for (glyph, info) in line.glyphs.iter().zip(&line.info) {
+                if range.contains(&info.text_index) {
+                    let left = glyph.glyph.position.x;
+                    let right = left + info.advance;
+                    match &mut current {
+                        Some(rect) => rect.max.x = right,
+                        None => {
+                            current = Some(Rect {
+                                min: point(left, top),
+                                max: point(right, bottom),
+                            })
+                        }
+                    }
+                } else if let Some(rect) = current.take() {
+                    rects.push(rect);
+                }
+            }
+            rects.extend(current);
+        }
+        rects
+    }
+}
+
+impl Line {
+    /// Returns the caret x position before the character at `index`.
+    fn caret_x(&self, index: usize) -> f32 {
+        // the glyph logically before the index
+        let mut before: Option<(usize, f32, &GlyphInfo)> = None;
+        for (glyph, info) in self.glyphs.iter().zip(&self.info) {
+            let left = glyph.glyph.position.x;
+            if info.text_index == index {
+                return match info.rtl {
+                    true => left + info.advance,
+                    false => left,
+                };
+            }
+            if info.text_index < index && before.map_or(true, |(i, ..)| info.text_index > i) {
+                before = Some((info.text_index, left, info));
+            }
+        }
+        match before {
+            Some((_, left, info)) if info.rtl => left,
+            Some((_, left, info)) => left + info.advance,
+            None => self.left,
+        }
+    }
+}
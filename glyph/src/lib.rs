@@ -22,26 +22,55 @@
 extern crate alloc;
 extern crate core;
 
+mod autohint;
 mod codepoint_ids;
+mod color;
+mod embolden;
 mod err;
+#[cfg(feature = "std")]
+mod fallback;
+mod float_cmp;
 mod font;
 #[cfg(feature = "std")]
 mod font_arc;
 mod glyph;
+mod glyph_cache;
+mod image;
+#[cfg(feature = "layout")]
+mod layout;
+mod msdf;
 #[cfg(all(feature = "libm", not(feature = "std")))]
 mod nostd_float;
 mod outlined;
 mod scale;
+mod sdf;
+#[cfg(feature = "opentype-layout")]
+mod shape;
+mod transform;
 mod ttfp;
+#[cfg(feature = "variable-fonts")]
+mod variable;
 
-#[cfg(feature = "std")]
-pub use crate::font_arc::*;
+#[cfg(feature = "layout")]
+pub use crate::layout::*;
+#[cfg(feature = "opentype-layout")]
+pub use crate::shape::*;
+#[cfg(feature = "variable-fonts")]
+pub use crate::variable::*;
 pub use crate::{
+    autohint::*,
     codepoint_ids::*,
+    color::*,
     err::*,
     font::*,
     glyph::*,
+    glyph_cache::*,
+    image::*,
     outlined::*,
     scale::*,
-    ttfp::{FontRef, FontVec, GlyphImage, GlyphImageFormat},
+    sdf::DistanceField,
+    transform::*,
+    ttfp::{FontRef, FontVec, GlyphIdCache, GlyphImage, GlyphImageFormat},
 };
+#[cfg(feature = "std")]
+pub use crate::{fallback::*, font_arc::*};
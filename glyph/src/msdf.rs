@@ -0,0 +1,407 @@
+//! Multi-channel signed distance field generation.
+//!
+//! Based on the approach of [msdfgen](https://github.com/Chlumsky/msdfgen): edges are colored
+//! so that each corner joins edges sharing only one channel, the median of the channels then
+//! reconstructs sharp corners.
+use crate::{
+    float_cmp::total_cmp,
+    point,
+    sdf::{cross, dot, length, pad, scale, Segment, Shape},
+    DistanceField, Outline, Point, PxScaleFactor,
+};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+// Edge color channel bits.
+const BLACK: u8 = 0;
+const RED: u8 = 1;
+const GREEN: u8 = 2;
+const BLUE: u8 = 4;
+const YELLOW: u8 = RED | GREEN;
+const MAGENTA: u8 = RED | BLUE;
+const CYAN: u8 = GREEN | BLUE;
+const WHITE: u8 = RED | GREEN | BLUE;
+
+/// Sine of the minimum angle between edge directions that forms a corner, `sin(3)`.
+const CORNER_CROSS_THRESHOLD: f32 = 0.141_12;
+
+/// Minimum pixel distance difference between neighbouring pixel channels considered a clash.
+const CLASH_THRESHOLD: f32 = 1.001;
+
+impl Outline {
+    /// Generates a three-channel multi-channel signed distance field (MSDF) of this outline
+    /// at a given scale & position.
+    ///
+    /// Edges are colored so that sharp corners are preserved when rendering with the
+    /// median of the red, green & blue channels, even at small field sizes. Pixels where
+    /// interpolation between channels would produce artifacts are corrected.
+    ///
+    /// `spread` & `padding` work as in [`Outline::sdf`]. See [`DistanceField`] for the
+    /// output format.
+    pub fn msdf(
+        &self,
+        scale_factor: PxScaleFactor,
+        position: Point,
+        spread: f32,
+        padding: u32,
+    ) -> DistanceField {
+        multi_channel_field(self, scale_factor, position, spread, padding, 3)
+    }
+
+    /// Generates a four-channel multi-channel & true signed distance field (MTSDF) of this
+    /// outline at a given scale & position.
+    ///
+    /// The red, green & blue channels are the same as [`Outline::msdf`], the alpha channel
+    /// is the true signed distance as in [`Outline::sdf`].
+    pub fn mtsdf(
+        &self,
+        scale_factor: PxScaleFactor,
+        position: Point,
+        spread: f32,
+        padding: u32,
+    ) -> DistanceField {
+        multi_channel_field(self, scale_factor, position, spread, padding, 4)
+    }
+}
+
+fn multi_channel_field(
+    outline: &Outline,
+    scale_factor: PxScaleFactor,
+    position: Point,
+    spread: f32,
+    padding: u32,
+    channels: u8,
+) -> DistanceField {
+    let px_bounds = pad(outline.px_bounds(scale_factor, position), padding);
+    let shape = Shape::new(&outline.curves, scale_factor, position - px_bounds.min);
+    let spread = spread.max(f32::EPSILON);
+    let edges = color_edges(&shape.segments);
+    let orientation = shape.orientation();
+
+    let (width, height) = (px_bounds.width() as usize, px_bounds.height() as usize);
+    let mut distances = Vec::with_capacity(width * height);
+    let mut row = Vec::new();
+    for y in 0..height {
+        shape.inside_row(y as f32 + 0.5, width, &mut row);
+        for (x, &inside) in row.iter().enumerate() {
+            let p = point(x as f32 + 0.5, y as f32 + 0.5);
+            distances.push(pixel_distances(&edges, p, inside, orientation));
+        }
+    }
+    correct_clashes(&mut distances, width, height);
+
+    DistanceField::new(px_bounds, channels, spread, |x, y, out| {
+        let pixel = &distances[y as usize * width + x as usize];
+        out.copy_from_slice(&pixel[..out.len()]);
+    })
+}
+
+/// A segment with the color channels it contributes to.
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    segment: Segment,
+    color: u8,
+}
+
+/// Nearest edge candidate of a channel.
+#[derive(Debug, Clone, Copy)]
+struct Nearest {
+    edge: usize,
+    distance: f32,
+    t: f32,
+    /// Alignment of the edge direction & the direction to the pixel, lower is more orthogonal.
+    dot: f32,
+}
+
+impl Nearest {
+    const NONE: Self = Self {
+        edge: usize::MAX,
+        distance: f32::INFINITY,
+        t: 0.0,
+        dot: f32::INFINITY,
+    };
+
+    fn is_better_than(&self, other: &Self) -> bool {
+        self.distance < other.distance || (self.distance == other.distance && self.dot < other.dot)
+    }
+}
+
+/// Returns signed distances `[red, green, blue, true]` of a pixel center `p`.
+fn pixel_distances(edges: &[Edge], p: Point, inside: bool, orientation: f32) -> [f32; 4] {
+    let mut nearest = [Nearest::NONE; 3];
+    for (idx, edge) in edges.iter().enumerate() {
+        let channels = (0..3).filter(|c| edge.color & (1 << c) != 0);
+        let worst = channels
+            .clone()
+            .map(|c| nearest[c].distance)
+            .fold(0.0, f32::max);
+        if edge.segment.bounds_distance(p) > worst {
+            continue;
+        }
+
+        let (distance, t) = edge.segment.nearest(p);
+        let candidate = Nearest {
+            edge: idx,
+            distance,
+            t,
+            dot: match distance > 0.0 {
+                true => {
+                    let direction = edge.segment.direction(t);
+                    dot(direction, p - edge.segment.point(t)).abs() / (length(direction) * distance)
+                }
+                false => 0.0,
+            },
+        };
+        for c in channels {
+            if candidate.is_better_than(&nearest[c]) {
+                nearest[c] = candidate;
+            }
+        }
+    }
+
+    let true_distance = nearest
+        .iter()
+        .map(|n| n.distance)
+        .fold(f32::INFINITY, f32::min);
+    let true_distance = match inside {
+        true => true_distance,
+        false => -true_distance,
+    };
+
+    let mut out = [0.0; 4];
+    for (channel, n) in out.iter_mut().zip(&nearest) {
+        *channel = match edges.get(n.edge) {
+            Some(edge) => pseudo_distance(&edge.segment, p, n, orientation),
+            None => true_distance,
+        };
+    }
+    out[3] = true_distance;
+
+    // the median sign must match the true inside/outside, otherwise fall back to the true
+    // distance in all channels
+    if (median(out[0], out[1], out[2]) > 0.0) != inside {
+        out = [true_distance; 4];
+    }
+    out
+}
+
+/// Signed distance to the segment, extending the segment ends linearly so that
+/// neighbouring edges of a corner meet sharply.
+fn pseudo_distance(segment: &Segment, p: Point, nearest: &Nearest, orientation: f32) -> f32 {
+    let signed_to_line = |origin: Point, direction: Point| {
+        let len = length(direction);
+        match len > 0.0 {
+            true => {
+                let direction = scale(direction, 1.0 / len);
+                let aq = p - origin;
+                Some((dot(aq, direction), orientation * cross(direction, aq)))
+            }
+            false => None,
+        }
+    };
+
+    let near_point = segment.point(nearest.t);
+    let sign = match orientation * cross(segment.direction(nearest.t), p - near_point) < 0.0 {
+        true => -1.0,
+        false => 1.0,
+    };
+    let distance = sign * nearest.distance;
+
+    let pseudo = match nearest.t {
+        t if t <= 0.0 => signed_to_line(segment.start(), segment.direction(0.0))
+            .filter(|&(along, _)| along < 0.0),
+        t if t >= 1.0 => {
+            signed_to_line(segment.end(), segment.direction(1.0)).filter(|&(along, _)| along > 0.0)
+        }
+        _ => None,
+    };
+    match pseudo {
+        Some((_, pseudo)) if pseudo.abs() <= distance.abs() => pseudo,
+        _ => distance,
+    }
+}
+
+#[inline]
+fn median(a: f32, b: f32, c: f32) -> f32 {
+    a.min(b).max(a.max(b).min(c))
+}
+
+/// Splits segments into contours & assigns edge colors so that edges meeting at a corner
+/// share only one channel.
+fn color_edges(segments: &[Segment]) -> Vec<Edge> {
+    let mut edges = Vec::with_capacity(segments.len());
+    let mut seed = 0;
+    let mut contour: Vec<Segment> = Vec::new();
+    for segment in segments {
+        if contour
+            .last()
+            .map_or(false, |last| last.end() != segment.start())
+        {
+            color_contour(&contour, &mut seed, &mut edges);
+            contour.clear();
+        }
+        if !is_degenerate(segment) {
+            contour.push(*segment);
+        }
+    }
+    color_contour(&contour, &mut seed, &mut edges);
+    edges
+}
+
+fn is_degenerate(segment: &Segment) -> bool {
+    let start = segment.start();
+    match *segment {
+        Segment::Line(_, p1) => p1 == start,
+        Segment::Quad(_, p1, p2) => p1 == start && p2 == start,
+        Segment::Cubic(_, p1, p2, p3) => p1 == start && p2 == start && p3 == start,
+    }
+}
+
+fn color_contour(contour: &[Segment], seed: &mut u64, edges: &mut Vec<Edge>) {
+    let n = contour.len();
+    let corners: Vec<usize> = (0..n)
+        .filter(|&idx| {
+            let previous = contour[(idx + n - 1) % n];
+            is_corner(previous.direction(1.0), contour[idx].direction(0.0))
+        })
+        .collect();
+
+    match *corners.as_slice() {
+        [] => edges.extend(contour.iter().map(|&segment| Edge {
+            segment,
+            color: WHITE,
+        })),
+        // "teardrop" with a single corner, use three colors across the contour
+        [corner] => {
+            let mut colors = [WHITE; 3];
+            switch_color(&mut colors[0], seed, BLACK);
+            colors[2] = colors[0];
+            switch_color(&mut colors[2], seed, BLACK);
+
+            if n >= 3 {
+                edges.extend((0..n).map(|idx| Edge {
+                    segment: contour[(corner + idx) % n],
+                    color: colors[symmetrical_trichotomy(idx, n)],
+                }));
+            } else {
+                // too few edges, split each into thirds
+                let parts: Vec<_> = (0..n)
+                    .flat_map(|idx| thirds(&contour[(corner + idx) % n]))
+                    .collect();
+                let len = parts.len();
+                edges.extend(parts.into_iter().enumerate().map(|(idx, segment)| Edge {
+                    segment,
+                    color: colors[idx * 3 / len],
+                }));
+            }
+        }
+        _ => {
+            let mut color = WHITE;
+            switch_color(&mut color, seed, BLACK);
+            let initial = color;
+            let mut spline = 0;
+            for idx in 0..n {
+                let idx = (corners[0] + idx) % n;
+                if corners.get(spline + 1) == Some(&idx) {
+                    spline += 1;
+                    // avoid the last spline sharing the first's color
+                    let banned = match spline == corners.len() - 1 {
+                        true => initial,
+                        false => BLACK,
+                    };
+                    switch_color(&mut color, seed, banned);
+                }
+                edges.push(Edge {
+                    segment: contour[idx],
+                    color,
+                });
+            }
+        }
+    }
+}
+
+fn is_corner(a: Point, b: Point) -> bool {
+    let (a_len, b_len) = (length(a), length(b));
+    if a_len == 0.0 || b_len == 0.0 {
+        return false;
+    }
+    let (a, b) = (scale(a, 1.0 / a_len), scale(b, 1.0 / b_len));
+    dot(a, b) <= 0.0 || cross(a, b).abs() > CORNER_CROSS_THRESHOLD
+}
+
+/// Changes `color` to a different two channel color, avoiding `banned` if possible.
+fn switch_color(color: &mut u8, seed: &mut u64, banned: u8) {
+    let combined = *color & banned;
+    if combined == RED || combined == GREEN || combined == BLUE {
+        *color = combined ^ WHITE;
+        return;
+    }
+    if *color == BLACK || *color == WHITE {
+        *color = [CYAN, MAGENTA, YELLOW][(*seed % 3) as usize];
+        *seed /= 3;
+        return;
+    }
+    let shifted = *color << (1 + (*seed & 1));
+    *color = (shifted | shifted >> 3) & WHITE;
+    *seed >>= 1;
+}
+
+/// Maps `position` in `0..n` to color indices `0`, `1` or `2` symmetrically.
+fn symmetrical_trichotomy(position: usize, n: usize) -> usize {
+    (3.0 + 2.875 * position as f32 / (n - 1) as f32 - 1.4375 + 0.5) as usize - 2
+}
+
+fn thirds(segment: &Segment) -> [Segment; 3] {
+    let (first, rest) = segment.split(1.0 / 3.0);
+    let (second, third) = rest.split(0.5);
+    [first, second, third]
+}
+
+/// Replaces pixels whose channels would interpolate into artifacts with neighbours by the
+/// median of their channels.
+fn correct_clashes(distances: &mut [[f32; 4]], width: usize, height: usize) {
+    let mut clashes = Vec::new();
+    let idx = |x: usize, y: usize| y * width + x;
+    for y in 0..height {
+        for x in 0..width {
+            let a = &distances[idx(x, y)];
+            let neighbours = [
+                (x > 0).then(|| (idx(x - 1, y), CLASH_THRESHOLD)),
+                (x + 1 < width).then(|| (idx(x + 1, y), CLASH_THRESHOLD)),
+                (y > 0).then(|| (idx(x, y - 1), CLASH_THRESHOLD)),
+                (y + 1 < height).then(|| (idx(x, y + 1), CLASH_THRESHOLD)),
+                (x > 0 && y > 0).then(|| (idx(x - 1, y - 1), 2.0 * CLASH_THRESHOLD)),
+                (x + 1 < width && y > 0).then(|| (idx(x + 1, y - 1), 2.0 * CLASH_THRESHOLD)),
+                (x > 0 && y + 1 < height).then(|| (idx(x - 1, y + 1), 2.0 * CLASH_THRESHOLD)),
+                (x + 1 < width && y + 1 < height)
+                    .then(|| (idx(x + 1, y + 1), 2.0 * CLASH_THRESHOLD)),
+            ];
+            if neighbours
+                .iter()
+                .flatten()
+                .any(|&(b, threshold)| is_clash(a, &distances[b], threshold))
+            {
+                clashes.push(idx(x, y));
+            }
+        }
+    }
+    for idx in clashes {
+        let [r, g, b, a] = distances[idx];
+        let median = median(r, g, b);
+        distances[idx] = [median, median, median, a];
+    }
+}
+
+/// Whether interpolating between pixels `a` & `b` would produce an artifact, flagging only
+/// the pixel further from the edge.
+fn is_clash(a: &[f32; 4], b: &[f32; 4], threshold: f32) -> bool {
+    // sort channel pairs by descending difference
+    let mut pairs = [(a[0], b[0]), (a[1], b[1]), (a[2], b[2])];
+    pairs.sort_unstable_by(|x, y| total_cmp((y.1 - y.0).abs(), (x.1 - x.0).abs()));
+    let [_, (a1, b1), (a2, b2)] = pairs;
+
+    (b1 - a1).abs() >= threshold
+        // ignore pixels that have been equalized
+        && !(b[0] == b[1] && b[0] == b[2])
+        && a2.abs() >= b2.abs()
+}
@@ -9,6 +9,12 @@ pub(crate) trait FloatExt {
     fn abs(self) -> Self;
     fn trunc(self) -> Self;
     fn fract(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn acos(self) -> Self;
+    fn cbrt(self) -> Self;
 }
 
 impl FloatExt for f32 {
@@ -40,4 +46,28 @@ impl FloatExt for f32 {
     fn fract(self) -> Self {
         self - self.trunc()
     }
+    #[inline]
+    fn sin(self) -> Self {
+        libm::sinf(self)
+    }
+    #[inline]
+    fn cos(self) -> Self {
+        libm::cosf(self)
+    }
+    #[inline]
+    fn tan(self) -> Self {
+        libm::tanf(self)
+    }
+    #[inline]
+    fn atan2(self, other: Self) -> Self {
+        libm::atan2f(self, other)
+    }
+    #[inline]
+    fn acos(self) -> Self {
+        libm::acosf(self)
+    }
+    #[inline]
+    fn cbrt(self) -> Self {
+        libm::cbrtf(self)
+    }
 }
@@ -1,6 +1,11 @@
 #[cfg(all(feature = "libm", not(feature = "std")))]
 use crate::nostd_float::FloatExt;
-use crate::{point, Glyph, Point, PxScaleFactor};
+use crate::{
+    glyph_cache::integer_position, point, AutoHinter, DistanceField, Glyph, GlyphCacheKey, Point,
+    PxScaleFactor, Transform,
+};
+pub use ab_glyph_rasterizer::{LcdFilter, LineCap, LineJoin, Stroke};
+use ab_glyph_rasterizer::{LcdRasterizer, Rasterizer, Stroker};
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
@@ -16,26 +21,103 @@ pub struct Outline {
 impl Outline {
     /// Convert unscaled bounds into pixel bounds at a given scale & position.
     pub fn px_bounds(&self, scale_factor: PxScaleFactor, position: Point) -> Rect {
-        let Rect { min, max } = self.bounds;
-
-        // Use subpixel fraction in floor/ceil rounding to elimate rounding error
-        // from identical subpixel positions
-        let (x_trunc, x_fract) = (position.x.trunc(), position.x.fract());
-        let (y_trunc, y_fract) = (position.y.trunc(), position.y.fract());
-
-        Rect {
-            min: point(
-                (min.x * scale_factor.horizontal + x_fract).floor() + x_trunc,
-                (min.y * -scale_factor.vertical + y_fract).floor() + y_trunc,
-            ),
-            max: point(
-                (max.x * scale_factor.horizontal + x_fract).ceil() + x_trunc,
-                (max.y * -scale_factor.vertical + y_fract).ceil() + y_trunc,
-            ),
+        unscaled_px_bounds(self.bounds, scale_factor, position)
+    }
+
+    /// Returns the fillable outline of this outline's curves stroked at a given scale.
+    ///
+    /// The `stroke` width is in pixels at the scale, the result is unscaled & made of lines.
+    /// Contours are closed so are stroked with joins all round, see [`Stroke`].
+    pub fn stroke(&self, stroke: &Stroke, scale_factor: PxScaleFactor) -> Outline {
+        let (h_factor, v_factor) = (scale_factor.horizontal, scale_factor.vertical);
+        let scale_up = |Point { x, y }| point(x * h_factor, y * v_factor);
+        let scale_down = |Point { x, y }| point(x / h_factor, y / v_factor);
+
+        let mut stroker = Stroker::new(*stroke);
+        for curve in &self.curves {
+            match *curve {
+                OutlineCurve::Line(p0, p1) => stroker.draw_line(scale_up(p0), scale_up(p1)),
+                OutlineCurve::Quad(p0, p1, p2) => {
+                    stroker.draw_quad(scale_up(p0), scale_up(p1), scale_up(p2))
+                }
+                OutlineCurve::Cubic(p0, p1, p2, p3) => {
+                    stroker.draw_cubic(scale_up(p0), scale_up(p1), scale_up(p2), scale_up(p3))
+                }
+            }
+        }
+
+        let mut curves = Vec::new();
+        let mut bounds: Option<Rect> = None;
+        stroker.for_each_line(|p0, p1| {
+            let (p0, p1) = (scale_down(p0), scale_down(p1));
+            curves.push(OutlineCurve::Line(p0, p1));
+            let b = bounds.get_or_insert(Rect { min: p0, max: p0 });
+            b.min = point(b.min.x.min(p1.x), b.min.y.max(p1.y));
+            b.max = point(b.max.x.max(p1.x), b.max.y.min(p1.y));
+        });
+
+        Outline {
+            bounds: bounds.unwrap_or_default(),
+            curves,
         }
     }
 }
 
+/// Convert unscaled bounds into pixel bounds at a given scale & position.
+pub(crate) fn unscaled_px_bounds(
+    bounds: Rect,
+    scale_factor: PxScaleFactor,
+    position: Point,
+) -> Rect {
+    let Rect { min, max } = bounds;
+
+    // Use subpixel fraction in floor/ceil rounding to elimate rounding error
+    // from identical subpixel positions
+    let (x_trunc, x_fract) = (position.x.trunc(), position.x.fract());
+    let (y_trunc, y_fract) = (position.y.trunc(), position.y.fract());
+
+    Rect {
+        min: point(
+            (min.x * scale_factor.horizontal + x_fract).floor() + x_trunc,
+            (min.y * -scale_factor.vertical + y_fract).floor() + y_trunc,
+        ),
+        max: point(
+            (max.x * scale_factor.horizontal + x_fract).ceil() + x_trunc,
+            (max.y * -scale_factor.vertical + y_fract).ceil() + y_trunc,
+        ),
+    }
+}
+
+/// Draws `curves` into a new `width` x `height` rasterizer, using `map` to
+/// convert curve points into pixel coordinates.
+pub(crate) fn rasterize_curves(
+    curves: &[OutlineCurve],
+    width: usize,
+    height: usize,
+    map: impl Fn(Point) -> Point,
+) -> Rasterizer {
+    curves
+        .iter()
+        .fold(Rasterizer::new(width, height), |mut rasterizer, curve| {
+            match curve {
+                OutlineCurve::Line(p0, p1) => {
+                    // eprintln!("r.draw_line({:?}, {:?});", map(*p0), map(*p1));
+                    rasterizer.draw_line(map(*p0), map(*p1));
+                }
+                OutlineCurve::Quad(p0, p1, p2) => {
+                    // eprintln!("r.draw_quad({:?}, {:?}, {:?});", map(*p0), map(*p1), map(*p2));
+                    rasterizer.draw_quad(map(*p0), map(*p1), map(*p2));
+                }
+                OutlineCurve::Cubic(p0, p1, p2, p3) => {
+                    // eprintln!("r.draw_cubic({:?}, {:?}, {:?}, {:?});",
+                    //     map(*p0), map(*p1), map(*p2), map(*p3));
+                    rasterizer.draw_cubic(map(*p0), map(*p1), map(*p2), map(*p3));
+                }
+            }
+            rasterizer
+        })
+}
+
 /// A glyph that has been outlined at a scale & position.
 #[derive(Clone, Debug)]
 pub struct OutlinedGlyph {
@@ -82,13 +164,142 @@ impl OutlinedGlyph {
         self.px_bounds
     }
 
+    /// Returns the [`GlyphCacheKey`] of this glyph with `subpixel_bins` per axis.
+    #[inline]
+    pub fn cache_key(&self, font_id: usize, subpixel_bins: u8) -> GlyphCacheKey {
+        GlyphCacheKey::new(font_id, &self.glyph, subpixel_bins)
+    }
+
+    /// Returns this glyph positioned at the center of its subpixel bins, relative to the
+    /// integer pixel origin, as [`GlyphCacheKey::glyph`].
+    ///
+    /// Drawing the result produces the rasterization shared by all glyphs with the same
+    /// [`cache_key`](Self::cache_key), to be placed at [`integer_position`](Self::integer_position).
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf")).unwrap();
+    /// let glyph = font.glyph_id('a').with_scale_and_position(24.0, point(100.3, 50.9));
+    /// let outlined = font.outline_glyph(glyph).unwrap();
+    ///
+    /// let binned = outlined.at_subpixel_bin(4);
+    /// assert_eq!(binned.glyph().position, point(0.375, 0.875));
+    ///
+    /// // draw `binned` once & place it for each glyph with the same key
+    /// let offset = outlined.integer_position();
+    /// assert_eq!(offset, point(100.0, 50.0));
+    /// assert_eq!(binned.px_bounds().min + offset, outlined.px_bounds().min);
+    /// ```
+    pub fn at_subpixel_bin(&self, subpixel_bins: u8) -> OutlinedGlyph {
+        let glyph = self.cache_key(0, subpixel_bins).glyph();
+        Self::new(glyph, self.outline.clone(), self.scale_factor)
+    }
+
+    /// Returns the integer part of this glyph's position, the offset to place a
+    /// [subpixel binned](Self::at_subpixel_bin) rasterization at.
+    #[inline]
+    pub fn integer_position(&self) -> Point {
+        integer_position(self.glyph.position)
+    }
+
+    /// Returns this glyph auto-hinted, with horizontal stems & blue zone edges snapped to the
+    /// vertical pixel grid, see [`AutoHinter`].
+    ///
+    /// Hinting is chosen per draw, e.g. `outlined.autohinted(&hinter).draw(..)`, without
+    /// changing this glyph. The result may have different [`px_bounds`](Self::px_bounds).
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf")).unwrap();
+    /// let hinter = AutoHinter::new(&font);
+    /// let glyph = font.glyph_id('H').with_scale(14.0);
+    /// let outlined = font.outline_glyph(glyph).unwrap();
+    ///
+    /// let hinted = outlined.autohinted(&hinter);
+    /// // horizontal metrics are unchanged
+    /// assert_eq!(hinted.px_bounds().min.x, outlined.px_bounds().min.x);
+    /// assert_eq!(hinted.px_bounds().max.x, outlined.px_bounds().max.x);
+    /// ```
+    pub fn autohinted(&self, hinter: &AutoHinter) -> OutlinedGlyph {
+        let outline = self
+            .outline
+            .autohint(hinter, self.scale_factor, self.glyph.position);
+        Self::new(self.glyph.clone(), outline, self.scale_factor)
+    }
+
+    /// Returns this glyph's outline stroked with a `stroke` of pixel width, for drawing
+    /// outlined "hollow" text. See [`Outline::stroke`].
+    ///
+    /// The result has [`px_bounds`](Self::px_bounds) extended to fit the stroke.
+    pub fn stroked(&self, stroke: &Stroke) -> OutlinedGlyph {
+        let outline = self.outline.stroke(stroke, self.scale_factor);
+        Self::new(self.glyph.clone(), outline, self.scale_factor)
+    }
+
+    /// Draw this glyph's outline stroked with a `stroke` of pixel width using a pixel &
+    /// coverage handling function.
+    ///
+    /// The callback will be called for each `(x, y)` pixel coordinate inside the bounds of
+    /// the [`stroked`](Self::stroked) glyph, as with [`draw`](Self::draw).
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf")).unwrap();
+    /// let glyph = font.glyph_id('a').with_scale(32.0);
+    /// let outlined = font.outline_glyph(glyph).unwrap();
+    ///
+    /// let stroke = Stroke {
+    ///     join: LineJoin::Round,
+    ///     ..Stroke::new(1.5)
+    /// };
+    /// let bounds = outlined.stroked(&stroke).px_bounds();
+    /// outlined.draw_stroked(&stroke, |x, y, c| {
+    ///     // draw pixel `(x, y)` of `bounds` with coverage: `c`
+    /// });
+    /// ```
+    pub fn draw_stroked<O: FnMut(u32, u32, f32)>(&self, stroke: &Stroke, o: O) {
+        self.stroked(stroke).draw(o);
+    }
+
+    /// Returns this glyph with its outline transformed, for oblique, rotated or mirrored
+    /// drawing. The result has recomputed [`px_bounds`](Self::px_bounds).
+    ///
+    /// The `transform` is in pixels, y-up, relative to the glyph position, so it's
+    /// unaffected by the glyph scale & rotations are about the glyph origin.
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf")).unwrap();
+    /// let glyph = font.glyph_id('l').with_scale_and_position(24.0, point(20.0, 30.0));
+    /// let outlined = font.outline_glyph(glyph).unwrap();
+    ///
+    /// // rotate a quarter turn counter-clockwise about the origin
+    /// let rotated = outlined.with_transform(&Transform::rotate(core::f32::consts::FRAC_PI_2));
+    /// let bounds = rotated.px_bounds();
+    /// // the stem now runs left from the origin
+    /// assert!(bounds.max.x <= 21.0 && bounds.width() > bounds.height());
+    /// rotated.draw(|x, y, c| { /* draw pixel `(x, y)` with coverage: `c` */ });
+    /// ```
+    pub fn with_transform(&self, transform: &Transform) -> OutlinedGlyph {
+        let (h_factor, v_factor) = (self.scale_factor.horizontal, self.scale_factor.vertical);
+        // unscaled -> pixel, transform, then back to unscaled
+        let unscaled = Transform::scale(h_factor, v_factor)
+            .then(*transform)
+            .then(Transform::scale(h_factor.recip(), v_factor.recip()));
+        let outline = self.outline.transform(&unscaled);
+        Self::new(self.glyph.clone(), outline, self.scale_factor)
+    }
+
     /// Draw this glyph outline using a pixel & coverage handling function.
     ///
     /// The callback will be called for each `(x, y)` pixel coordinate inside the bounds
     /// with a coverage value in the range `[0.0, 1.0]` indicating how much the glyph covered
     /// that pixel.
     pub fn draw<O: FnMut(u32, u32, f32)>(&self, o: O) {
-        use ab_glyph_rasterizer::Rasterizer;
         let h_factor = self.scale_factor.horizontal;
         let v_factor = -self.scale_factor.vertical;
         let offset = self.glyph.position - self.px_bounds.min;
@@ -97,41 +308,135 @@ impl OutlinedGlyph {
             self.px_bounds.height() as usize,
         );
 
-        let scale_up = |&Point { x, y }| point(x * h_factor, y * v_factor);
+        let scale_up = |Point { x, y }| point(x * h_factor, y * v_factor);
 
-        self.outline
-            .curves
-            .iter()
-            .fold(Rasterizer::new(w, h), |mut rasterizer, curve| match curve {
-                OutlineCurve::Line(p0, p1) => {
-                    // eprintln!("r.draw_line({:?}, {:?});",
-                    //     scale_up(p0) + offset, scale_up(p1) + offset);
-                    rasterizer.draw_line(scale_up(p0) + offset, scale_up(p1) + offset);
-                    rasterizer
-                }
+        rasterize_curves(&self.outline.curves, w, h, |p| scale_up(p) + offset).for_each_pixel_2d(o);
+    }
+
+    /// Pixel bounds of [`draw_lcd`](Self::draw_lcd) for a subpixel `order`. These are the
+    /// [`px_bounds`](Self::px_bounds) extended by 1 pixel on each side across the subpixels,
+    /// to include coverage spread by filtering.
+    #[inline]
+    pub fn lcd_px_bounds(&self, order: LcdOrder) -> Rect {
+        let Rect { min, max } = self.px_bounds;
+        match order {
+            LcdOrder::Rgb | LcdOrder::Bgr => Rect {
+                min: point(min.x - 1.0, min.y),
+                max: point(max.x + 1.0, max.y),
+            },
+            LcdOrder::Vrgb | LcdOrder::Vbgr => Rect {
+                min: point(min.x, min.y - 1.0),
+                max: point(max.x, max.y + 1.0),
+            },
+        }
+    }
+
+    /// Draw this glyph outline with LCD subpixel antialiasing using a pixel & `[r, g, b]`
+    /// coverage handling function.
+    ///
+    /// The outline is rasterized at 3x resolution across the subpixels of the display
+    /// `order` & smoothed with a `filter`, see [`LcdFilter`]. The callback will be called
+    /// for each `(x, y)` pixel coordinate inside the [`lcd_px_bounds`](Self::lcd_px_bounds)
+    /// with red, green & blue coverage values in the range `[0.0, 1.0]`.
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf")).unwrap();
+    /// let glyph = font.glyph_id('a').with_scale(14.0);
+    /// let outlined = font.outline_glyph(glyph).unwrap();
+    ///
+    /// let bounds = outlined.lcd_px_bounds(LcdOrder::Rgb);
+    /// assert_eq!(bounds.width(), outlined.px_bounds().width() + 2.0);
+    ///
+    /// outlined.draw_lcd(LcdOrder::Rgb, LcdFilter::DEFAULT, |x, y, [r, g, b]| {
+    ///     // blend pixel `(x, y)` of `bounds` with per-channel coverage
+    /// });
+    /// ```
+    pub fn draw_lcd<O: FnMut(u32, u32, [f32; 3])>(
+        &self,
+        order: LcdOrder,
+        filter: LcdFilter,
+        mut o: O,
+    ) {
+        let bounds = self.lcd_px_bounds(order);
+        let h_factor = self.scale_factor.horizontal;
+        let v_factor = -self.scale_factor.vertical;
+        let offset = self.glyph.position - bounds.min;
+        let (w, h) = (bounds.width() as usize, bounds.height() as usize);
+
+        // vertical orders are drawn transposed
+        let vertical = matches!(order, LcdOrder::Vrgb | LcdOrder::Vbgr);
+        let map = |Point { x, y }| {
+            let p = point(x * h_factor, y * v_factor) + offset;
+            if vertical {
+                point(p.y, p.x)
+            } else {
+                p
+            }
+        };
+
+        let mut rasterizer = match vertical {
+            true => LcdRasterizer::new(h, w),
+            false => LcdRasterizer::new(w, h),
+        };
+        rasterizer.set_filter(filter);
+        for curve in &self.outline.curves {
+            match curve {
+                OutlineCurve::Line(p0, p1) => rasterizer.draw_line(map(*p0), map(*p1)),
                 OutlineCurve::Quad(p0, p1, p2) => {
-                    // eprintln!("r.draw_quad({:?}, {:?}, {:?});",
-                    //     scale_up(p0) + offset, scale_up(p1) + offset, scale_up(p2) + offset);
-                    rasterizer.draw_quad(
-                        scale_up(p0) + offset,
-                        scale_up(p1) + offset,
-                        scale_up(p2) + offset,
-                    );
-                    rasterizer
+                    rasterizer.draw_quad(map(*p0), map(*p1), map(*p2))
                 }
                 OutlineCurve::Cubic(p0, p1, p2, p3) => {
-                    // eprintln!("r.draw_cubic({:?}, {:?}, {:?}, {:?});",
-                    //     scale_up(p0) + offset, scale_up(p1) + offset, scale_up(p2) + offset, scale_up(p3) + offset);
-                    rasterizer.draw_cubic(
-                        scale_up(p0) + offset,
-                        scale_up(p1) + offset,
-                        scale_up(p2) + offset,
-                        scale_up(p3) + offset,
-                    );
-                    rasterizer
+                    rasterizer.draw_cubic(map(*p0), map(*p1), map(*p2), map(*p3))
                 }
-            })
-            .for_each_pixel_2d(o);
+            }
+        }
+
+        let bgr = matches!(order, LcdOrder::Bgr | LcdOrder::Vbgr);
+        rasterizer.for_each_pixel_rgb_2d(|x, y, [c0, c1, c2]| {
+            let (x, y) = if vertical { (y, x) } else { (x, y) };
+            o(x, y, if bgr { [c2, c1, c0] } else { [c0, c1, c2] });
+        });
+    }
+
+    /// Generates a single-channel signed distance field of this glyph.
+    ///
+    /// `spread` is the distance in pixels either side of the outline represented by the
+    /// field values. The field [`px_bounds`](DistanceField::px_bounds) are this glyph's
+    /// [`px_bounds`](Self::px_bounds) with `padding` pixels added to each side.
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf")).unwrap();
+    /// let glyph = font.glyph_id('a').with_scale(32.0);
+    /// let outlined = font.outline_glyph(glyph).unwrap();
+    ///
+    /// let sdf = outlined.sdf(4.0, 4);
+    /// assert_eq!(sdf.width as f32, outlined.px_bounds().width() + 8.0);
+    /// assert_eq!(sdf.data.len(), (sdf.width * sdf.height) as usize);
+    /// ```
+    pub fn sdf(&self, spread: f32, padding: u32) -> DistanceField {
+        self.outline
+            .sdf(self.scale_factor, self.glyph.position, spread, padding)
+    }
+
+    /// Generates a three-channel multi-channel signed distance field of this glyph,
+    /// which preserves sharp corners when rendered using the median of the channels.
+    ///
+    /// `spread` & `padding` work as in [`OutlinedGlyph::sdf`].
+    pub fn msdf(&self, spread: f32, padding: u32) -> DistanceField {
+        self.outline
+            .msdf(self.scale_factor, self.glyph.position, spread, padding)
+    }
+
+    /// Generates a four-channel multi-channel & true signed distance field of this glyph.
+    /// The same as [`OutlinedGlyph::msdf`] with an alpha channel of the
+    /// [single-channel distance](OutlinedGlyph::sdf).
+    pub fn mtsdf(&self, spread: f32, padding: u32) -> DistanceField {
+        self.outline
+            .mtsdf(self.scale_factor, self.glyph.position, spread, padding)
     }
 }
 
@@ -154,6 +459,25 @@ pub enum OutlineCurve {
     Cubic(Point, Point, Point, Point),
 }
 
+impl OutlineCurve {
+    /// Returns the end & control points of this curve, the first `len` of the array.
+    #[inline]
+    pub(crate) fn control_points(&self) -> ([Point; 4], usize) {
+        match *self {
+            OutlineCurve::Line(p0, p1) => ([p0, p1, p1, p1], 2),
+            OutlineCurve::Quad(p0, p1, p2) => ([p0, p1, p2, p2], 3),
+            OutlineCurve::Cubic(p0, p1, p2, p3) => ([p0, p1, p2, p3], 4),
+        }
+    }
+
+    /// Returns the end point of this curve.
+    #[inline]
+    pub(crate) fn end(&self) -> Point {
+        let (points, len) = self.control_points();
+        points[len - 1]
+    }
+}
+
 /// A rectangle, with top-left corner at `min`, and bottom-right corner at `max`.
 #[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
 pub struct Rect {
@@ -172,3 +496,16 @@ impl Rect {
         self.max.y - self.min.y
     }
 }
+
+/// Physical order of the subpixels of an LCD display, see [`OutlinedGlyph::draw_lcd`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LcdOrder {
+    /// Horizontal red, green, blue from left to right, the most common layout.
+    Rgb,
+    /// Horizontal blue, green, red from left to right.
+    Bgr,
+    /// Vertical red, green, blue from top to bottom.
+    Vrgb,
+    /// Vertical blue, green, red from top to bottom.
+    Vbgr,
+}
@@ -1,4 +1,6 @@
-use crate::{Font, Glyph, GlyphId, OutlinedGlyph, Rect};
+use crate::{
+    point, DecodedGlyphImage, Font, Glyph, GlyphId, OutlinedColorGlyph, OutlinedGlyph, Point, Rect,
+};
 
 /// Pixel scale.
 ///
@@ -120,6 +122,19 @@ pub trait ScaleFont<F: Font> {
         self.font().glyph_id(c)
     }
 
+    /// Lookup a `GlyphId` matching a given `char`, or `None` if the font has no glyph for it.
+    /// See [`Font::try_glyph_id`].
+    #[inline]
+    fn try_glyph_id(&self, c: char) -> Option<GlyphId> {
+        self.font().try_glyph_id(c)
+    }
+
+    /// Returns `true` if the font has a glyph for `c`.
+    #[inline]
+    fn has_glyph(&self, c: char) -> bool {
+        self.font().has_glyph(c)
+    }
+
     /// Construct a [`Glyph`](struct.Glyph.html) with the font's pixel scale at
     /// position `point(0.0, 0.0)`.
     ///
@@ -147,24 +162,50 @@ pub trait ScaleFont<F: Font> {
         self.h_scale_factor() * self.font().h_advance_unscaled(id)
     }
 
+    /// Pixel scaled horizontal advance for a given glyph, or `None` for an invalid glyph id.
+    #[inline]
+    fn try_h_advance(&self, id: GlyphId) -> Option<f32> {
+        Some(self.h_scale_factor() * self.font().try_h_advance_unscaled(id)?)
+    }
+
     /// Pixel scaled horizontal side bearing for a given glyph.
     #[inline]
     fn h_side_bearing(&self, id: GlyphId) -> f32 {
         self.h_scale_factor() * self.font().h_side_bearing_unscaled(id)
     }
 
+    /// Pixel scaled horizontal side bearing for a given glyph, or `None` for an invalid
+    /// glyph id.
+    #[inline]
+    fn try_h_side_bearing(&self, id: GlyphId) -> Option<f32> {
+        Some(self.h_scale_factor() * self.font().try_h_side_bearing_unscaled(id)?)
+    }
+
     /// Pixel scaled vertical advance for a given glyph.
     #[inline]
     fn v_advance(&self, id: GlyphId) -> f32 {
         self.v_scale_factor() * self.font().v_advance_unscaled(id)
     }
 
+    /// Pixel scaled vertical advance for a given glyph, or `None` for an invalid glyph id.
+    #[inline]
+    fn try_v_advance(&self, id: GlyphId) -> Option<f32> {
+        Some(self.v_scale_factor() * self.font().try_v_advance_unscaled(id)?)
+    }
+
     /// Pixel scaled vertical side bearing for a given glyph.
     #[inline]
     fn v_side_bearing(&self, id: GlyphId) -> f32 {
         self.v_scale_factor() * self.font().v_side_bearing_unscaled(id)
     }
 
+    /// Pixel scaled vertical side bearing for a given glyph, or `None` for an invalid
+    /// glyph id.
+    #[inline]
+    fn try_v_side_bearing(&self, id: GlyphId) -> Option<f32> {
+        Some(self.v_scale_factor() * self.font().try_v_side_bearing_unscaled(id)?)
+    }
+
     /// Returns additional pixel scaled kerning to apply for a particular pair of glyphs.
     #[inline]
     fn kern(&self, first: GlyphId, second: GlyphId) -> f32 {
@@ -203,6 +244,180 @@ pub trait ScaleFont<F: Font> {
     fn outline_glyph(&self, glyph: Glyph) -> Option<OutlinedGlyph> {
         self.font().outline_glyph(glyph)
     }
+
+    /// Compute color glyph layers & paints ready for drawing.
+    ///
+    /// Note this method does not make use of the associated scale, as `Glyph`
+    /// already includes one of it's own.
+    #[inline]
+    fn outline_color_glyph(&self, glyph: Glyph, palette: u16) -> Option<OutlinedColorGlyph> {
+        self.font().outline_color_glyph(glyph, palette)
+    }
+
+    /// Returns the pre-rendered image of the glyph decoded into RGBA pixels & resized
+    /// to this scale. See [`Font::decode_glyph_image`].
+    #[inline]
+    fn decode_glyph_image(&self, id: GlyphId) -> Option<DecodedGlyphImage> {
+        self.font().decode_glyph_image(id, self.scale())
+    }
+
+    /// Pixel distance between the baselines of consecutive lines, `height + line_gap`.
+    #[inline]
+    fn line_height(&self) -> f32 {
+        self.height() + self.line_gap()
+    }
+
+    /// Returns the advance width of a single line of `text`, with kerning, including
+    /// whitespace. Control characters & line breaks are ignored.
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf")).unwrap();
+    /// let scaled_font = font.as_scaled(24.0);
+    ///
+    /// let (a, v) = (scaled_font.glyph_id('A'), scaled_font.glyph_id('V'));
+    /// let width = scaled_font.h_advance(a) + scaled_font.kern(a, v) + scaled_font.h_advance(v);
+    /// assert_eq!(scaled_font.text_width("AV"), width);
+    /// ```
+    fn text_width(&self, text: &str) -> f32 {
+        line_glyphs(self, text, Point::default())
+            .last()
+            .map_or(0.0, |(glyph, advance)| glyph.position.x + advance)
+    }
+
+    /// Returns the whole pixel bounds of the outlines of a single line of `text` drawn with
+    /// the baseline starting at `position`. These are the union of each glyph's
+    /// [`OutlinedGlyph::px_bounds`], or `None` if no glyph has an outline.
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf")).unwrap();
+    /// let scaled_font = font.as_scaled(24.0);
+    ///
+    /// let bounds = scaled_font.text_px_bounds("Hello", point(10.0, 50.0)).unwrap();
+    /// assert!(bounds.min.y < 50.0 && bounds.max.y <= 51.0);
+    /// assert!(bounds.width() <= scaled_font.text_width("Hello"));
+    ///
+    /// assert_eq!(scaled_font.text_px_bounds("  ", point(10.0, 50.0)), None);
+    /// ```
+    fn text_px_bounds(&self, text: &str, position: Point) -> Option<Rect> {
+        line_glyphs(self, text, position)
+            .filter_map(|(glyph, _)| self.outline_glyph(glyph))
+            .map(|outlined| outlined.px_bounds())
+            .reduce(union)
+    }
+
+    /// Returns the `(width, height)` of multi-line `text`, split at line breaks like
+    /// `'\n'`. The width is the widest [`text_width`](Self::text_width) & the height
+    /// is from the top of the first line to the bottom of the last, using
+    /// [`line_height`](Self::line_height) between lines.
+    ///
+    /// Empty text measures as a single empty line.
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf")).unwrap();
+    /// let scaled_font = font.as_scaled(24.0);
+    ///
+    /// let (width, height) = scaled_font.multiline_size("Hello\nWorld!");
+    /// assert_eq!(width, scaled_font.text_width("World!"));
+    /// assert_eq!(height, scaled_font.line_height() + scaled_font.height());
+    /// ```
+    fn multiline_size(&self, text: &str) -> (f32, f32) {
+        let (lines, width) = split_lines(text).fold((0, 0.0_f32), |(lines, width), line| {
+            (lines + 1, width.max(self.text_width(line)))
+        });
+        let height = (lines - 1) as f32 * self.line_height() + self.height();
+        (width, height)
+    }
+
+    /// Returns the whole pixel bounds of the outlines of multi-line `text`, split at line
+    /// breaks like `'\n'`, with the first baseline starting at `position` & subsequent
+    /// lines [`line_height`](Self::line_height) below. See
+    /// [`text_px_bounds`](Self::text_px_bounds).
+    fn multiline_px_bounds(&self, text: &str, position: Point) -> Option<Rect> {
+        let line_height = self.line_height();
+        split_lines(text)
+            .enumerate()
+            .filter_map(|(idx, line)| {
+                let position = point(position.x, position.y + idx as f32 * line_height);
+                self.text_px_bounds(line, position)
+            })
+            .reduce(union)
+    }
+}
+
+/// Positions the glyphs of a single line of `text` from a baseline `position` with
+/// kerning, as paragraph layout does, returning each glyph & its advance.
+fn line_glyphs<'a, F, SF>(
+    font: &'a SF,
+    text: &'a str,
+    position: Point,
+) -> impl Iterator<Item = (Glyph, f32)> + 'a
+where
+    F: Font,
+    SF: ScaleFont<F> + ?Sized,
+{
+    let mut caret = position;
+    let mut last: Option<GlyphId> = None;
+    // skip the chars paragraph layout doesn't position as glyphs
+    let chars = text
+        .chars()
+        .filter(|&c| !c.is_control() && !is_mandatory_break(c));
+    chars.map(move |c| {
+        let mut glyph = font.scaled_glyph(c);
+        if let Some(last) = last {
+            caret.x += font.kern(last, glyph.id);
+        }
+        glyph.position = caret;
+        let advance = font.h_advance(glyph.id);
+        caret.x += advance;
+        last = Some(glyph.id);
+        (glyph, advance)
+    })
+}
+
+/// Splits `text` into lines at mandatory line breaks, treating `"\r\n"` as one break.
+/// A trailing break is followed by an empty line.
+fn split_lines(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = Some(text);
+    core::iter::from_fn(move || {
+        let text = rest?;
+        match text.char_indices().find(|(_, c)| is_mandatory_break(*c)) {
+            Some((idx, c)) => {
+                let mut end = idx + c.len_utf8();
+                if c == '\r' && text[end..].starts_with('\n') {
+                    end += 1;
+                }
+                rest = Some(&text[end..]);
+                Some(&text[..idx])
+            }
+            None => {
+                rest = None;
+                Some(text)
+            }
+        }
+    })
+}
+
+/// Characters that end a line, UAX #14 classes BK, CR, LF & NL.
+#[inline]
+pub(crate) fn is_mandatory_break(c: char) -> bool {
+    matches!(
+        c,
+        '\n' | '\r' | '\u{0B}' | '\u{0C}' | '\u{85}' | '\u{2028}' | '\u{2029}'
+    )
+}
+
+#[inline]
+fn union(a: Rect, b: Rect) -> Rect {
+    Rect {
+        min: point(a.min.x.min(b.min.x), a.min.y.min(b.min.y)),
+        max: point(a.max.x.max(b.max.x), a.max.y.max(b.max.y)),
+    }
 }
 
 impl<F: Font, SF: ScaleFont<F>> ScaleFont<F> for &SF {
@@ -216,10 +431,40 @@ impl<F: Font, SF: ScaleFont<F>> ScaleFont<F> for &SF {
         (*self).font()
     }
 
+    #[inline]
+    fn h_advance(&self, id: GlyphId) -> f32 {
+        (*self).h_advance(id)
+    }
+
+    #[inline]
+    fn v_advance(&self, id: GlyphId) -> f32 {
+        (*self).v_advance(id)
+    }
+
+    #[inline]
+    fn try_h_advance(&self, id: GlyphId) -> Option<f32> {
+        (*self).try_h_advance(id)
+    }
+
+    #[inline]
+    fn try_v_advance(&self, id: GlyphId) -> Option<f32> {
+        (*self).try_v_advance(id)
+    }
+
+    #[inline]
+    fn glyph_bounds(&self, glyph: &Glyph) -> Rect {
+        (*self).glyph_bounds(glyph)
+    }
+
     #[inline]
     fn codepoint_ids(&self) -> crate::CodepointIdIter<'_> {
         (*self).codepoint_ids()
     }
+
+    #[inline]
+    fn outline_glyph(&self, glyph: Glyph) -> Option<OutlinedGlyph> {
+        (*self).outline_glyph(glyph)
+    }
 }
 
 /// A [`Font`](trait.Font.html) and an associated pixel scale.
@@ -235,6 +480,17 @@ impl<F> PxScaleFont<F> {
         self.scale = scale.into();
         self
     }
+
+    /// Returns a synthetic "fake" bold version of this font, emboldening glyphs by unscaled
+    /// `strength_x` & `strength_y`. See [`EmboldenedPxScaleFont`].
+    #[inline]
+    pub fn embolden(self, strength_x: f32, strength_y: f32) -> EmboldenedPxScaleFont<F> {
+        EmboldenedPxScaleFont {
+            scale_font: self,
+            strength_x,
+            strength_y,
+        }
+    }
 }
 
 impl<F: Font> ScaleFont<F> for PxScaleFont<F> {
@@ -253,3 +509,111 @@ impl<F: Font> ScaleFont<F> for PxScaleFont<F> {
         self.font.codepoint_ids()
     }
 }
+
+/// A [`PxScaleFont`] drawing synthetic "fake" bold glyphs, for fonts without a bold face.
+///
+/// Outlines are emboldened using [`Outline::embolden`](crate::Outline::embolden) and
+/// advances increased to match, so this can be used anywhere a [`ScaleFont`] is.
+///
+/// # Example
+/// ```
+/// use ab_glyph::{Font, FontRef, ScaleFont};
+///
+/// # fn main() -> Result<(), ab_glyph::InvalidFont> {
+/// let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf"))?;
+/// let scaled_font = font.as_scaled(45.0);
+///
+/// // embolden by 1/24 em, as FreeType does
+/// let strength = font.units_per_em().unwrap() / 24.0;
+/// let bold_font = scaled_font.embolden(strength, strength);
+///
+/// let b = scaled_font.glyph_id('b');
+/// let extra_advance = bold_font.h_advance(b) - scaled_font.h_advance(b);
+/// assert!((extra_advance - strength * scaled_font.h_scale_factor()).abs() < 1e-4);
+///
+/// let bold_b = bold_font.outline_glyph(bold_font.scaled_glyph('b')).unwrap();
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct EmboldenedPxScaleFont<F> {
+    /// The font & scale glyphs are emboldened at.
+    pub scale_font: PxScaleFont<F>,
+    /// Unscaled increase in glyph width & horizontal advance.
+    pub strength_x: f32,
+    /// Unscaled increase in glyph height & vertical advance.
+    pub strength_y: f32,
+}
+
+impl<F> EmboldenedPxScaleFont<F> {
+    /// Returns this font at a new `scale`, keeping the unscaled embolden strengths.
+    #[inline]
+    pub fn with_scale<S: Into<PxScale>>(mut self, scale: S) -> Self {
+        self.scale_font.scale = scale.into();
+        self
+    }
+}
+
+impl<F: Font> ScaleFont<F> for EmboldenedPxScaleFont<F> {
+    #[inline]
+    fn scale(&self) -> PxScale {
+        self.scale_font.scale
+    }
+
+    #[inline]
+    fn font(&self) -> &F {
+        &self.scale_font.font
+    }
+
+    #[inline]
+    fn codepoint_ids(&self) -> crate::CodepointIdIter<'_> {
+        self.scale_font.font.codepoint_ids()
+    }
+
+    #[inline]
+    fn h_advance(&self, id: GlyphId) -> f32 {
+        self.h_scale_factor() * (self.font().h_advance_unscaled(id) + self.strength_x)
+    }
+
+    #[inline]
+    fn v_advance(&self, id: GlyphId) -> f32 {
+        self.v_scale_factor() * (self.font().v_advance_unscaled(id) + self.strength_y)
+    }
+
+    #[inline]
+    fn try_h_advance(&self, id: GlyphId) -> Option<f32> {
+        Some(self.h_scale_factor() * (self.font().try_h_advance_unscaled(id)? + self.strength_x))
+    }
+
+    #[inline]
+    fn try_v_advance(&self, id: GlyphId) -> Option<f32> {
+        Some(self.v_scale_factor() * (self.font().try_v_advance_unscaled(id)? + self.strength_y))
+    }
+
+    /// Returns the layout bounds of this glyph, including the increased advance.
+    #[inline]
+    fn glyph_bounds(&self, glyph: &Glyph) -> Rect {
+        let sf = self
+            .font()
+            .as_scaled(glyph.scale)
+            .embolden(self.strength_x, self.strength_y);
+        let pos = glyph.position;
+        Rect {
+            min: point(pos.x - sf.h_side_bearing(glyph.id), pos.y - sf.ascent()),
+            max: point(pos.x + sf.h_advance(glyph.id), pos.y - sf.descent()),
+        }
+    }
+
+    /// Compute emboldened glyph outline ready for drawing.
+    ///
+    /// Note this method does not make use of the associated scale, as `Glyph`
+    /// already includes one of it's own.
+    #[inline]
+    fn outline_glyph(&self, glyph: Glyph) -> Option<OutlinedGlyph> {
+        let outline = self
+            .font()
+            .outline(glyph.id)?
+            .embolden(self.strength_x, self.strength_y);
+        let scale_factor = self.font().as_scaled(glyph.scale).scale_factor();
+        Some(OutlinedGlyph::new(glyph, outline, scale_factor))
+    }
+}
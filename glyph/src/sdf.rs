@@ -0,0 +1,518 @@
+//! Signed distance field generation from exact outline curves.
+#[cfg(all(feature = "libm", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+use crate::{float_cmp::total_cmp, point, Outline, OutlineCurve, Point, PxScaleFactor, Rect};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// A distance field image of a glyph outline.
+///
+/// Produced by [`OutlinedGlyph::sdf`](crate::OutlinedGlyph::sdf),
+/// [`OutlinedGlyph::msdf`](crate::OutlinedGlyph::msdf),
+/// [`OutlinedGlyph::mtsdf`](crate::OutlinedGlyph::mtsdf) & the equivalent [`Outline`] methods.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistanceField {
+    /// Whole number pixel bounds of the field, the glyph pixel bounds expanded by the padding.
+    pub px_bounds: Rect,
+    /// Field width in pixels.
+    pub width: u32,
+    /// Field height in pixels.
+    pub height: u32,
+    /// Number of channels per pixel.
+    ///
+    /// * `1` for a single-channel signed distance field.
+    /// * `3` for a multi-channel signed distance field, rendered using the median of the
+    ///   red, green & blue channels.
+    /// * `4` for a multi-channel & true signed distance field, the same as `3` plus an alpha
+    ///   channel containing the single-channel distance.
+    pub channels: u8,
+    /// Distance in pixels from the outline that maps to `0` outside & `255` inside.
+    pub spread: f32,
+    /// Row-major distance values, `channels` bytes per pixel starting at the top-left.
+    ///
+    /// Values map signed pixel distances `d` in the range `[-spread, spread]`, positive inside
+    /// the outline, to `(0.5 + d / (2 * spread)) * 255`. So the outline edge lies at `127.5`.
+    pub data: Vec<u8>,
+}
+
+impl DistanceField {
+    /// Creates a field covering `px_bounds`, computing each pixel's `channels` values with
+    /// `f(x, y)` returning signed pixel distances.
+    pub(crate) fn new(
+        px_bounds: Rect,
+        channels: u8,
+        spread: f32,
+        mut f: impl FnMut(u32, u32, &mut [f32]),
+    ) -> Self {
+        let (width, height) = (px_bounds.width() as u32, px_bounds.height() as u32);
+        let mut data = Vec::with_capacity(width as usize * height as usize * usize::from(channels));
+        let mut distances = [0.0; 4];
+        let distances = &mut distances[..usize::from(channels)];
+        for y in 0..height {
+            for x in 0..width {
+                f(x, y, distances);
+                data.extend(distances.iter().map(|&d| encode_distance(d, spread)));
+            }
+        }
+        Self {
+            px_bounds,
+            width,
+            height,
+            channels,
+            spread,
+            data,
+        }
+    }
+}
+
+/// Maps a signed pixel distance into a `u8` value, see [`DistanceField::data`].
+#[inline]
+pub(crate) fn encode_distance(distance: f32, spread: f32) -> u8 {
+    ((0.5 + distance / (2.0 * spread)).clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+impl Outline {
+    /// Generates a single-channel signed distance field of this outline at a given scale &
+    /// position.
+    ///
+    /// Distances are computed exactly from the line, quadratic & cubic curves.
+    /// `spread` is the distance in pixels either side of the outline represented by the field
+    /// values. `padding` pixels are added to each side of the glyph's pixel bounds, and should
+    /// usually be at least the `spread`.
+    ///
+    /// See [`DistanceField`] for the output format.
+    pub fn sdf(
+        &self,
+        scale_factor: PxScaleFactor,
+        position: Point,
+        spread: f32,
+        padding: u32,
+    ) -> DistanceField {
+        let px_bounds = pad(self.px_bounds(scale_factor, position), padding);
+        let shape = Shape::new(&self.curves, scale_factor, position - px_bounds.min);
+        let spread = spread.max(f32::EPSILON);
+
+        let mut row = Vec::new();
+        let mut row_y = None;
+        DistanceField::new(px_bounds, 1, spread, |x, y, out| {
+            if row_y != Some(y) {
+                shape.inside_row(y as f32 + 0.5, px_bounds.width() as usize, &mut row);
+                row_y = Some(y);
+            }
+            let p = point(x as f32 + 0.5, y as f32 + 0.5);
+            let distance = shape.distance(p, spread);
+            out[0] = match row[x as usize] {
+                true => distance,
+                false => -distance,
+            };
+        })
+    }
+}
+
+/// Expands whole number pixel bounds by `padding` pixels on each side.
+pub(crate) fn pad(bounds: Rect, padding: u32) -> Rect {
+    let padding = padding as f32;
+    Rect {
+        min: point(bounds.min.x - padding, bounds.min.y - padding),
+        max: point(bounds.max.x + padding, bounds.max.y + padding),
+    }
+}
+
+/// Maximum distance in pixels between curves and their flattened lines.
+const FLATTEN_TOLERANCE: f32 = 0.01;
+
+/// Outline curves in field pixel coordinates, with y increasing downwards.
+#[derive(Debug)]
+pub(crate) struct Shape {
+    pub(crate) segments: Vec<Segment>,
+    /// Flattened segment lines used to determine winding.
+    lines: Vec<(Point, Point)>,
+}
+
+impl Shape {
+    /// Scales & offsets unscaled outline curves into pixel coordinates.
+    pub(crate) fn new(curves: &[OutlineCurve], scale_factor: PxScaleFactor, offset: Point) -> Self {
+        let map = |p: Point| {
+            point(
+                p.x * scale_factor.horizontal + offset.x,
+                p.y * -scale_factor.vertical + offset.y,
+            )
+        };
+        let segments: Vec<_> = curves
+            .iter()
+            .map(|curve| match *curve {
+                OutlineCurve::Line(p0, p1) => Segment::Line(map(p0), map(p1)),
+                OutlineCurve::Quad(p0, p1, p2) => Segment::Quad(map(p0), map(p1), map(p2)),
+                OutlineCurve::Cubic(p0, p1, p2, p3) => {
+                    Segment::Cubic(map(p0), map(p1), map(p2), map(p3))
+                }
+            })
+            .collect();
+
+        let mut lines = Vec::new();
+        for segment in &segments {
+            segment.flatten(&mut lines);
+        }
+        Self { segments, lines }
+    }
+
+    /// Fills `row` with whether each pixel center at `y` is inside the outline using the
+    /// non-zero winding rule.
+    pub(crate) fn inside_row(&self, y: f32, width: usize, row: &mut Vec<bool>) {
+        let mut crossings: Vec<(f32, i32)> = self
+            .lines
+            .iter()
+            .filter_map(|&(a, b)| {
+                let (top, bottom, dir) = match a.y < b.y {
+                    true => (a, b, 1),
+                    false => (b, a, -1),
+                };
+                if y < top.y || y >= bottom.y {
+                    return None;
+                }
+                let t = (y - top.y) / (bottom.y - top.y);
+                Some((top.x + t * (bottom.x - top.x), dir))
+            })
+            .collect();
+        crossings.sort_unstable_by(|a, b| total_cmp(a.0, b.0));
+
+        row.clear();
+        let (mut winding, mut crossings) = (0, crossings.into_iter().peekable());
+        for x in 0..width {
+            let x = x as f32 + 0.5;
+            while let Some((_, dir)) = crossings.next_if(|(cx, _)| *cx < x) {
+                winding += dir;
+            }
+            row.push(winding != 0);
+        }
+    }
+
+    /// Returns `1.0` if the outline winds clockwise, in pixel coordinates, otherwise `-1.0`.
+    pub(crate) fn orientation(&self) -> f32 {
+        let area: f32 = self.lines.iter().map(|&(a, b)| cross(a, b)).sum();
+        match area < 0.0 {
+            true => -1.0,
+            false => 1.0,
+        }
+    }
+
+    /// Returns the unsigned distance from `p` to the nearest segment, up to `max`.
+    pub(crate) fn distance(&self, p: Point, max: f32) -> f32 {
+        self.segments.iter().fold(max, |best, segment| {
+            match segment.bounds_distance(p) < best {
+                true => segment.nearest(p).0.min(best),
+                false => best,
+            }
+        })
+    }
+}
+
+/// An outline curve in pixel coordinates.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Segment {
+    Line(Point, Point),
+    Quad(Point, Point, Point),
+    Cubic(Point, Point, Point, Point),
+}
+
+impl Segment {
+    pub(crate) fn start(&self) -> Point {
+        match *self {
+            Self::Line(p0, ..) | Self::Quad(p0, ..) | Self::Cubic(p0, ..) => p0,
+        }
+    }
+
+    pub(crate) fn end(&self) -> Point {
+        match *self {
+            Self::Line(_, p1) => p1,
+            Self::Quad(.., p2) => p2,
+            Self::Cubic(.., p3) => p3,
+        }
+    }
+
+    /// Point on the segment at `t` in `[0, 1]`.
+    pub(crate) fn point(&self, t: f32) -> Point {
+        let mt = 1.0 - t;
+        match *self {
+            // exact end points so adjoining segments are equidistant at corners
+            _ if t <= 0.0 => self.start(),
+            _ if t >= 1.0 => self.end(),
+            Self::Line(p0, p1) => lerp(p0, p1, t),
+            Self::Quad(p0, p1, p2) => add3(
+                scale(p0, mt * mt),
+                scale(p1, 2.0 * mt * t),
+                scale(p2, t * t),
+            ),
+            Self::Cubic(p0, p1, p2, p3) => add3(
+                scale(p0, mt * mt * mt),
+                scale(p1, 3.0 * mt * mt * t),
+                add3(
+                    scale(p2, 3.0 * mt * t * t),
+                    scale(p3, t * t * t),
+                    Point::default(),
+                ),
+            ),
+        }
+    }
+
+    /// Derivative of the segment at `t`.
+    pub(crate) fn direction(&self, t: f32) -> Point {
+        let mt = 1.0 - t;
+        let direction = match *self {
+            Self::Line(p0, p1) => p1 - p0,
+            Self::Quad(p0, p1, p2) => add3(
+                scale(p1 - p0, 2.0 * mt),
+                scale(p2 - p1, 2.0 * t),
+                Point::default(),
+            ),
+            Self::Cubic(p0, p1, p2, p3) => add3(
+                scale(p1 - p0, 3.0 * mt * mt),
+                scale(p2 - p1, 6.0 * mt * t),
+                scale(p3 - p2, 3.0 * t * t),
+            ),
+        };
+        if direction == Point::default() {
+            // degenerate control points, use the overall direction instead
+            return self.end() - self.start();
+        }
+        direction
+    }
+
+    /// Splits the segment at `t` into two segments.
+    pub(crate) fn split(&self, t: f32) -> (Self, Self) {
+        match *self {
+            Self::Line(p0, p1) => {
+                let m = lerp(p0, p1, t);
+                (Self::Line(p0, m), Self::Line(m, p1))
+            }
+            Self::Quad(p0, p1, p2) => {
+                let (a, b) = (lerp(p0, p1, t), lerp(p1, p2, t));
+                let m = lerp(a, b, t);
+                (Self::Quad(p0, a, m), Self::Quad(m, b, p2))
+            }
+            Self::Cubic(p0, p1, p2, p3) => {
+                let (a, b, c) = (lerp(p0, p1, t), lerp(p1, p2, t), lerp(p2, p3, t));
+                let (ab, bc) = (lerp(a, b, t), lerp(b, c, t));
+                let m = lerp(ab, bc, t);
+                (Self::Cubic(p0, a, ab, m), Self::Cubic(m, bc, c, p3))
+            }
+        }
+    }
+
+    /// Distance from `p` to the bounding box of the control points, a lower bound of
+    /// the distance to the segment.
+    pub(crate) fn bounds_distance(&self, p: Point) -> f32 {
+        let (min, max) = match *self {
+            Self::Line(p0, p1) => (min(p0, p1), max(p0, p1)),
+            Self::Quad(p0, p1, p2) => (min(min(p0, p1), p2), max(max(p0, p1), p2)),
+            Self::Cubic(p0, p1, p2, p3) => {
+                (min(min(p0, p1), min(p2, p3)), max(max(p0, p1), max(p2, p3)))
+            }
+        };
+        let dx = (min.x - p.x).max(p.x - max.x).max(0.0);
+        let dy = (min.y - p.y).max(p.y - max.y).max(0.0);
+        (dx * dx + dy * dy).sqrt()
+    }
+
+    /// Returns the distance from `p` to the nearest point on the segment & its `t` value.
+    pub(crate) fn nearest(&self, p: Point) -> (f32, f32) {
+        let mut best = (f32::INFINITY, 0.0);
+        let mut check = |t: f32| {
+            let t = t.clamp(0.0, 1.0);
+            let distance = length(self.point(t) - p);
+            if distance < best.0 {
+                best = (distance, t);
+            }
+        };
+
+        match *self {
+            Self::Line(p0, p1) => {
+                let ab = p1 - p0;
+                let len_sq = dot(ab, ab);
+                check(match len_sq > 0.0 {
+                    true => dot(p - p0, ab) / len_sq,
+                    false => 0.0,
+                });
+            }
+            Self::Quad(p0, p1, p2) => {
+                // roots of d/dt |B(t) - p|² = 0
+                let a = p1 - p0;
+                let b = add3(p2, scale(p1, -2.0), p0);
+                let m = p0 - p;
+                check(0.0);
+                check(1.0);
+                for t in solve_cubic(
+                    dot(b, b),
+                    3.0 * dot(a, b),
+                    2.0 * dot(a, a) + dot(m, b),
+                    dot(m, a),
+                )
+                .iter()
+                .flatten()
+                {
+                    // polish roots, which may be imprecise for near linear curves
+                    check(self.newton(p, *t, 2));
+                }
+            }
+            Self::Cubic(..) => {
+                // Newton's method from multiple starting points
+                const STARTS: u8 = 8;
+                const STEPS: u8 = 6;
+                check(0.0);
+                check(1.0);
+                for start in 0..=STARTS {
+                    check(self.newton(p, f32::from(start) / f32::from(STARTS), STEPS));
+                }
+            }
+        }
+        best
+    }
+
+    /// Refines `t` towards a local minimum distance to `p` using Newton's method.
+    fn newton(&self, p: Point, mut t: f32, steps: u8) -> f32 {
+        for _ in 0..steps {
+            let qe = self.point(t) - p;
+            let d1 = self.direction(t);
+            let d2 = self.second_derivative(t);
+            let denominator = dot(d1, d1) + dot(qe, d2);
+            if denominator == 0.0 {
+                break;
+            }
+            t = (t - dot(qe, d1) / denominator).clamp(0.0, 1.0);
+        }
+        t
+    }
+
+    fn second_derivative(&self, t: f32) -> Point {
+        match *self {
+            Self::Line(..) => Point::default(),
+            Self::Quad(p0, p1, p2) => scale(add3(p2, scale(p1, -2.0), p0), 2.0),
+            Self::Cubic(p0, p1, p2, p3) => {
+                let a = add3(p2, scale(p1, -2.0), p0);
+                let b = add3(p3, scale(p2, -2.0), p1);
+                scale(lerp(a, b, t), 6.0)
+            }
+        }
+    }
+
+    /// Appends lines approximating the segment within [`FLATTEN_TOLERANCE`].
+    fn flatten(&self, lines: &mut Vec<(Point, Point)>) {
+        // maximum second difference of the control points bounds the flattening error
+        let dd = match *self {
+            Self::Line(p0, p1) => return lines.push((p0, p1)),
+            Self::Quad(p0, p1, p2) => 2.0 * length(add3(p2, scale(p1, -2.0), p0)),
+            Self::Cubic(p0, p1, p2, p3) => {
+                6.0 * length(add3(p2, scale(p1, -2.0), p0)).max(length(add3(
+                    p3,
+                    scale(p2, -2.0),
+                    p1,
+                )))
+            }
+        };
+        let n = (dd / (8.0 * FLATTEN_TOLERANCE))
+            .sqrt()
+            .ceil()
+            .clamp(1.0, 256.0) as u32;
+        let mut last = self.start();
+        for i in 1..=n {
+            let next = match i == n {
+                true => self.end(),
+                false => self.point(i as f32 / n as f32),
+            };
+            lines.push((last, next));
+            last = next;
+        }
+    }
+}
+
+/// Returns the real roots of `a t³ + b t² + c t + d`.
+fn solve_cubic(a: f32, b: f32, c: f32, d: f32) -> [Option<f32>; 3] {
+    const EPSILON: f32 = 1e-6;
+    if a.abs() < EPSILON {
+        // quadratic
+        if b.abs() < EPSILON {
+            return match c.abs() < EPSILON {
+                true => [None; 3],
+                false => [Some(-d / c), None, None],
+            };
+        }
+        let discriminant = c * c - 4.0 * b * d;
+        if discriminant < 0.0 {
+            return [None; 3];
+        }
+        let sqrt = discriminant.sqrt();
+        return [
+            Some((-c + sqrt) / (2.0 * b)),
+            Some((-c - sqrt) / (2.0 * b)),
+            None,
+        ];
+    }
+
+    // normalized, depressed cubic using Cardano's & trigonometric methods
+    let (b, c, d) = (b / a, c / a, d / a);
+    let b_sq = b * b;
+    let q = (b_sq - 3.0 * c) / 9.0;
+    let r = (b * (2.0 * b_sq - 9.0 * c) + 27.0 * d) / 54.0;
+    let (r_sq, q_cubed) = (r * r, q * q * q);
+    let offset = b / 3.0;
+    if r_sq < q_cubed {
+        let theta = (r / q_cubed.sqrt()).clamp(-1.0, 1.0).acos();
+        let m = -2.0 * q.sqrt();
+        let tau = core::f32::consts::PI * 2.0;
+        [
+            Some(m * (theta / 3.0).cos() - offset),
+            Some(m * ((theta + tau) / 3.0).cos() - offset),
+            Some(m * ((theta - tau) / 3.0).cos() - offset),
+        ]
+    } else {
+        let mut u = (r.abs() + (r_sq - q_cubed).sqrt()).cbrt();
+        if r >= 0.0 {
+            u = -u;
+        }
+        let v = match u == 0.0 {
+            true => 0.0,
+            false => q / u,
+        };
+        [Some(u + v - offset), None, None]
+    }
+}
+
+#[inline]
+pub(crate) fn dot(a: Point, b: Point) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+
+#[inline]
+pub(crate) fn cross(a: Point, b: Point) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+#[inline]
+pub(crate) fn length(p: Point) -> f32 {
+    dot(p, p).sqrt()
+}
+
+#[inline]
+pub(crate) fn scale(p: Point, s: f32) -> Point {
+    point(p.x * s, p.y * s)
+}
+
+#[inline]
+fn add3(a: Point, b: Point, c: Point) -> Point {
+    point(a.x + b.x + c.x, a.y + b.y + c.y)
+}
+
+#[inline]
+fn lerp(a: Point, b: Point, t: f32) -> Point {
+    point(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+}
+
+#[inline]
+fn min(a: Point, b: Point) -> Point {
+    point(a.x.min(b.x), a.y.min(b.y))
+}
+
+#[inline]
+fn max(a: Point, b: Point) -> Point {
+    point(a.x.max(b.x), a.y.max(b.y))
+}
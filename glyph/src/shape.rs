@@ -0,0 +1,111 @@
+use crate::{Font, GlyphId};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Text shaping using OpenType `GSUB` & `GPOS` layout tables.
+///
+/// Requires feature `opentype-layout` (enabled by default).
+pub trait ShapeFont: Font {
+    /// Shapes a single run of left-to-right `text` into positioned glyphs.
+    ///
+    /// Features are looked up using the `script` & `language` tags, falling back to
+    /// the font's `DFLT` script & the script's default language.
+    /// Supported lookups are applied for [`DEFAULT_FEATURES`] plus any enabled
+    /// `features`, which may also disable default features.
+    ///
+    /// Supported lookups:
+    /// * `GSUB` single, multiple & ligature substitution.
+    /// * `GPOS` single, pair, cursive & mark-to-base positioning.
+    ///
+    /// Fonts without `GPOS` kerning fall back to the legacy `kern` table.
+    ///
+    /// # Example
+    /// ```
+    /// use ab_glyph::{point, Feature, Font, FontRef, PxScale, ScaleFont, ShapeFont};
+    ///
+    /// # fn main() -> Result<(), ab_glyph::InvalidFont> {
+    /// let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf"))?;
+    ///
+    /// let shaped = font.shape("fit", &[], b"latn", None);
+    /// // "fi" forms a ligature
+    /// assert_eq!(shaped.len(), 2);
+    /// assert_eq!(shaped[0].cluster, 0);
+    /// assert_eq!(shaped[1].cluster, 2);
+    ///
+    /// let shaped = font.shape("fit", &[Feature::off(b"liga")], b"latn", None);
+    /// assert_eq!(shaped.len(), 3);
+    ///
+    /// // scale & position glyphs
+    /// let scale = PxScale::from(24.0);
+    /// let sf = font.as_scaled(scale).scale_factor();
+    /// let mut caret = point(0.0, 20.0);
+    /// for g in shaped {
+    ///     let position = caret + point(g.x_offset * sf.horizontal, -g.y_offset * sf.vertical);
+    ///     let _glyph = g.id.with_scale_and_position(scale, position);
+    ///     caret.x += g.x_advance * sf.horizontal;
+    /// }
+    /// # Ok(()) }
+    /// ```
+    fn shape(
+        &self,
+        text: &str,
+        features: &[Feature],
+        script: &[u8; 4],
+        language: Option<&[u8; 4]>,
+    ) -> Vec<ShapedGlyph>;
+}
+
+/// Features applied by [`ShapeFont::shape`] unless disabled.
+pub const DEFAULT_FEATURES: &[[u8; 4]] = &[
+    *b"ccmp", *b"locl", *b"rlig", *b"liga", *b"clig", *b"kern", *b"mark", *b"mkmk", *b"curs",
+];
+
+/// An OpenType feature setting used in [`ShapeFont::shape`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Feature {
+    /// Feature tag, e.g. `*b"liga"`.
+    pub tag: [u8; 4],
+    /// Whether the feature should be applied.
+    pub enabled: bool,
+}
+
+impl Feature {
+    /// Enables a feature.
+    #[inline]
+    pub fn on(tag: &[u8; 4]) -> Self {
+        Self {
+            tag: *tag,
+            enabled: true,
+        }
+    }
+
+    /// Disables a feature, which may be a [default feature](DEFAULT_FEATURES).
+    #[inline]
+    pub fn off(tag: &[u8; 4]) -> Self {
+        Self {
+            tag: *tag,
+            enabled: false,
+        }
+    }
+}
+
+/// A glyph output by [`ShapeFont::shape`].
+///
+/// Values are unscaled, in font units, with y increasing upwards.
+/// Scaling can be done with [`ScaleFont::scale_factor`](crate::ScaleFont::scale_factor).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShapedGlyph {
+    /// Glyph id.
+    pub id: GlyphId,
+    /// Byte index into the text of the first char this glyph was shaped from.
+    /// Ligatures & multiple substituted glyphs share the cluster of their first char.
+    pub cluster: usize,
+    /// Horizontal pen advance after drawing this glyph.
+    pub x_advance: f32,
+    /// Vertical pen advance after drawing this glyph.
+    pub y_advance: f32,
+    /// Horizontal offset from the pen position to draw this glyph at.
+    pub x_offset: f32,
+    /// Vertical offset from the pen position to draw this glyph at.
+    pub y_offset: f32,
+}
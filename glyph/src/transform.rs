@@ -0,0 +1,224 @@
+//! 2x3 affine transforms of outlines.
+#[cfg(all(feature = "libm", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+use crate::{point, Outline, OutlineCurve, Point, Rect};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// A 2x3 affine transform mapping `(x, y)` to
+/// `(xx * x + xy * y + dx, yx * x + yy * y + dy)`.
+///
+/// Transforms use the y-up orientation of font outlines, so positive rotations are
+/// counter-clockwise & a positive [`skew`](Self::skew) leans glyphs to the right.
+///
+/// # Example
+/// ```
+/// # use ab_glyph::*;
+/// // synthetic italic, leaning 12 degrees
+/// let oblique = Transform::skew(12_f32.to_radians(), 0.0);
+/// // rotate a quarter turn, then move 10 along x
+/// let rotate = Transform::rotate(core::f32::consts::FRAC_PI_2).then(Transform::translate(10.0, 0.0));
+///
+/// let p = rotate.apply(point(1.0, 0.0));
+/// assert!((p.x - 10.0).abs() < 1e-6 && (p.y - 1.0).abs() < 1e-6);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    pub xx: f32,
+    pub yx: f32,
+    pub xy: f32,
+    pub yy: f32,
+    pub dx: f32,
+    pub dy: f32,
+}
+
+impl Default for Transform {
+    #[inline]
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    /// The transform that leaves points unchanged.
+    pub const IDENTITY: Self = Self {
+        xx: 1.0,
+        yx: 0.0,
+        xy: 0.0,
+        yy: 1.0,
+        dx: 0.0,
+        dy: 0.0,
+    };
+
+    /// Translation by `(dx, dy)`.
+    #[inline]
+    pub fn translate(dx: f32, dy: f32) -> Self {
+        Self {
+            dx,
+            dy,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Scaling about the origin. Negative factors mirror, e.g. `scale(-1.0, 1.0)`
+    /// flips horizontally about `x = 0`.
+    #[inline]
+    pub fn scale(sx: f32, sy: f32) -> Self {
+        Self {
+            xx: sx,
+            yy: sy,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Counter-clockwise rotation by `radians` about the origin.
+    #[inline]
+    pub fn rotate(radians: f32) -> Self {
+        let (sin, cos) = (radians.sin(), radians.cos());
+        Self {
+            xx: cos,
+            yx: sin,
+            xy: -sin,
+            yy: cos,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Skew by angles in radians. `x_radians` shifts x in proportion to y, leaning
+    /// upright strokes right for positive angles, `y_radians` shifts y in proportion to x.
+    #[inline]
+    pub fn skew(x_radians: f32, y_radians: f32) -> Self {
+        Self {
+            xy: x_radians.tan(),
+            yx: y_radians.tan(),
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Returns the transform applying `self` then `next`.
+    #[inline]
+    pub fn then(self, next: Self) -> Self {
+        next * self
+    }
+
+    /// Transforms a point.
+    #[inline]
+    pub fn apply(&self, p: Point) -> Point {
+        point(
+            self.xx * p.x + self.xy * p.y + self.dx,
+            self.yx * p.x + self.yy * p.y + self.dy,
+        )
+    }
+
+    /// Returns the inverse transform, or `None` if this transform is degenerate.
+    pub fn invert(&self) -> Option<Self> {
+        let Self {
+            xx,
+            yx,
+            xy,
+            yy,
+            dx,
+            dy,
+        } = *self;
+        let det = xx * yy - yx * xy;
+        if det.abs() <= f32::EPSILON {
+            return None;
+        }
+        let inv_det = det.recip();
+        Some(Self {
+            xx: yy * inv_det,
+            yx: -yx * inv_det,
+            xy: -xy * inv_det,
+            yy: xx * inv_det,
+            dx: (xy * dy - yy * dx) * inv_det,
+            dy: (yx * dx - xx * dy) * inv_det,
+        })
+    }
+}
+
+impl From<[f32; 6]> for Transform {
+    /// From a `[xx, yx, xy, yy, dx, dy]` matrix, as used by `COLR` & `PostScript`.
+    #[inline]
+    fn from([xx, yx, xy, yy, dx, dy]: [f32; 6]) -> Self {
+        Self {
+            xx,
+            yx,
+            xy,
+            yy,
+            dx,
+            dy,
+        }
+    }
+}
+
+impl From<Transform> for [f32; 6] {
+    #[inline]
+    fn from(t: Transform) -> Self {
+        [t.xx, t.yx, t.xy, t.yy, t.dx, t.dy]
+    }
+}
+
+impl core::ops::Mul for Transform {
+    type Output = Self;
+
+    /// Combined transform applying `rhs` first, then `self`.
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            xx: self.xx * rhs.xx + self.xy * rhs.yx,
+            yx: self.yx * rhs.xx + self.yy * rhs.yx,
+            xy: self.xx * rhs.xy + self.xy * rhs.yy,
+            yy: self.yx * rhs.xy + self.yy * rhs.yy,
+            dx: self.xx * rhs.dx + self.xy * rhs.dy + self.dx,
+            dy: self.yx * rhs.dx + self.yy * rhs.dy + self.dy,
+        }
+    }
+}
+
+impl Outline {
+    /// Returns this outline with all curves transformed, in unscaled font units.
+    ///
+    /// The bounds are recomputed from the transformed control points, so remain
+    /// conservative for rotations & skews.
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # let font = FontRef::try_from_slice(include_bytes!("../../dev/fonts/Exo2-Light.otf")).unwrap();
+    /// let outline = font.outline(font.glyph_id('l')).unwrap();
+    /// let italic = outline.transform(&Transform::skew(0.2, 0.0));
+    ///
+    /// // the top of the stem moves right, the baseline doesn't move
+    /// assert!(italic.bounds.max.x > outline.bounds.max.x);
+    /// assert_eq!(italic.bounds.max.y, outline.bounds.max.y);
+    /// ```
+    pub fn transform(&self, transform: &Transform) -> Outline {
+        let t = |p| transform.apply(p);
+        let curves: Vec<_> = self
+            .curves
+            .iter()
+            .map(|curve| match *curve {
+                OutlineCurve::Line(p0, p1) => OutlineCurve::Line(t(p0), t(p1)),
+                OutlineCurve::Quad(p0, p1, p2) => OutlineCurve::Quad(t(p0), t(p1), t(p2)),
+                OutlineCurve::Cubic(p0, p1, p2, p3) => {
+                    OutlineCurve::Cubic(t(p0), t(p1), t(p2), t(p3))
+                }
+            })
+            .collect();
+
+        let mut bounds: Option<Rect> = None;
+        for curve in &curves {
+            let (points, len) = curve.control_points();
+            for p in &points[..len] {
+                let b = bounds.get_or_insert(Rect { min: *p, max: *p });
+                b.min = point(b.min.x.min(p.x), b.min.y.max(p.y));
+                b.max = point(b.max.x.max(p.x), b.max.y.min(p.y));
+            }
+        }
+
+        Outline {
+            bounds: bounds.unwrap_or_default(),
+            curves,
+        }
+    }
+}
@@ -1,19 +1,28 @@
 //! ttf-parser crate specific code. ttf-parser types should not be leaked publicly.
+mod bitmap;
+mod colr;
+mod glyph_ids;
+#[cfg(feature = "hinting")]
+mod hinting;
+#[cfg(feature = "hinting")]
+mod interpreter;
+#[cfg(feature = "opentype-layout")]
+mod kern;
 mod outliner;
+mod read;
+#[cfg(feature = "opentype-layout")]
+mod shaping;
 
-use crate::{point, Font, GlyphId, InvalidFont, Outline, Point, Rect};
+use crate::{point, ColorGlyph, Font, GlyphId, InvalidFont, Outline, Point, Rect};
 use alloc::boxed::Box;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
-use core::borrow::BorrowMut;
 use core::convert::TryFrom;
 use core::fmt;
-use std::cell::RefCell;
-use std::collections::hash_map::Entry;
-use std::collections::HashMap;
-use std::iter::Map;
-use std::sync::{Arc, RwLock};
-use owned_ttf_parser::{self as ttfp, AsFaceRef, Face, OwnedFace, PreParsedSubtables};
+use glyph_ids::GlyphIds;
+use owned_ttf_parser::{self as ttfp, AsFaceRef, PreParsedSubtables};
+
+pub use glyph_ids::GlyphIdCache;
 
 impl From<GlyphId> for ttfp::GlyphId {
     #[inline]
@@ -24,14 +33,24 @@ impl From<GlyphId> for ttfp::GlyphId {
 
 /// A pre-rendered image of a glyph, usually used for emojis or other glyphs
 /// that can't be represented only using an outline.
+///
+/// Use [`GlyphImage::decode`] to convert the raw data into pixels.
 #[derive(Debug, Clone)]
 pub struct GlyphImage<'a> {
-    /// Offset of the image from the normal origin (top at the baseline plus
-    /// ascent), measured in pixels at the image's current scale.
+    /// Offset of the image's bottom-left corner from the glyph origin on the baseline,
+    /// with y increasing upwards, measured in pixels at the image's current scale.
     pub origin: Point,
     /// Current scale of the image in pixels per em.
     pub scale: f32,
-    /// Raw image data (not a bitmap).
+    /// Image width in pixels.
+    ///
+    /// Taken from the font, so may not match the image `data` if the font is invalid.
+    pub width: u16,
+    /// Image height in pixels.
+    ///
+    /// Taken from the font, so may not match the image `data` if the font is invalid.
+    pub height: u16,
+    /// Raw image data, see [`format`](#structfield.format) for the encoding.
     pub data: &'a [u8],
     /// Format of the raw data.
     pub format: GlyphImageFormat,
@@ -42,6 +61,8 @@ impl<'a> From<ttfp::RasterGlyphImage<'a>> for GlyphImage<'a> {
         GlyphImage {
             origin: point(img.x.into(), img.y.into()),
             scale: img.pixels_per_em.into(),
+            width: img.width,
+            height: img.height,
             data: img.data,
             format: match img.format {
                 ttfp::RasterImageFormat::PNG => GlyphImageFormat::Png,
@@ -53,9 +74,102 @@ impl<'a> From<ttfp::RasterGlyphImage<'a>> for GlyphImage<'a> {
 /// Valid formats for a [`GlyphImage`].
 // Possible future formats:  SVG, JPEG, TIFF
 #[non_exhaustive]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GlyphImageFormat {
     Png,
+
+    /// A monochrome bitmap.
+    ///
+    /// The most significant bit of the first byte corresponds to the top-left pixel, proceeding
+    /// through succeeding bits moving left to right. The data for each row is padded to a byte
+    /// boundary, so the next row begins with the most significant bit of a new byte. 1 corresponds
+    /// to black, and 0 to white.
+    BitmapMono,
+
+    /// A packed monochrome bitmap.
+    ///
+    /// The most significant bit of the first byte corresponds to the top-left pixel, proceeding
+    /// through succeeding bits moving left to right. Data is tightly packed with no padding. 1
+    /// corresponds to black, and 0 to white.
+    BitmapMonoPacked,
+
+    /// A grayscale bitmap with 2 bits per pixel.
+    ///
+    /// The most significant bits of the first byte corresponds to the top-left pixel, proceeding
+    /// through succeeding bits moving left to right. The data for each row is padded to a byte
+    /// boundary, so the next row begins with the most significant bit of a new byte.
+    BitmapGray2,
+
+    /// A packed grayscale bitmap with 2 bits per pixel.
+    ///
+    /// The most significant bits of the first byte corresponds to the top-left pixel, proceeding
+    /// through succeeding bits moving left to right. Data is tightly packed with no padding.
+    BitmapGray2Packed,
+
+    /// A grayscale bitmap with 4 bits per pixel.
+    ///
+    /// The most significant bits of the first byte corresponds to the top-left pixel, proceeding
+    /// through succeeding bits moving left to right. The data for each row is padded to a byte
+    /// boundary, so the next row begins with the most significant bit of a new byte.
+    BitmapGray4,
+
+    /// A packed grayscale bitmap with 4 bits per pixel.
+    ///
+    /// The most significant bits of the first byte corresponds to the top-left pixel, proceeding
+    /// through succeeding bits moving left to right. Data is tightly packed with no padding.
+    BitmapGray4Packed,
+
+    /// A grayscale bitmap with 8 bits per pixel.
+    ///
+    /// The first byte corresponds to the top-left pixel, proceeding through succeeding bytes
+    /// moving left to right.
+    BitmapGray8,
+
+    /// A color bitmap with 32 bits per pixel.
+    ///
+    /// The first group of four bytes corresponds to the top-left pixel, proceeding through
+    /// succeeding pixels moving left to right. Each byte corresponds to a color channel and the
+    /// channels within a pixel are in blue, green, red, alpha order. Color values are
+    /// pre-multiplied by the alpha. For example, the color "full-green with half translucency"
+    /// is encoded as `\x00\x80\x00\x80`, and not `\x00\xFF\x00\x80`.
+    BitmapPremulBgra32,
+}
+
+/// Data derived from the face on load, or on variation changes, stored alongside the
+/// pre-parsed subtables of a [`FontRef`] or [`FontVec`] to speed up common lookups.
+#[derive(Clone)]
+pub struct FontCache {
+    glyph_ids: GlyphIds,
+    ascent_unscaled: f32,
+    descent_unscaled: f32,
+    line_gap_unscaled: f32,
+    #[cfg(feature = "opentype-layout")]
+    gpos_kerning: kern::GposKerning,
+    #[cfg(feature = "hinting")]
+    hinting: hinting::HintingCache,
+}
+
+impl FontCache {
+    /// `GPOS` kerning fallback for fonts without a `kern` table.
+    #[inline]
+    fn gpos_kern(&self, face: &ttfp::Face<'_>, first: GlyphId, second: GlyphId) -> Option<f32> {
+        #[cfg(feature = "opentype-layout")]
+        {
+            self.gpos_kerning.kern(face, first, second)
+        }
+        #[cfg(not(feature = "opentype-layout"))]
+        {
+            let _ = (face, first, second);
+            None
+        }
+    }
+
+    /// Re-reads metrics that depend on the face's variation coordinates.
+    fn update_metrics(&mut self, face: &ttfp::Face<'_>) {
+        self.ascent_unscaled = face.ascender().into();
+        self.descent_unscaled = face.descender().into();
+        self.line_gap_unscaled = face.line_gap().into();
+    }
 }
 
 /// Font data handle stored as a `&[u8]` + parsed data.
@@ -74,21 +188,10 @@ pub enum GlyphImageFormat {
 /// # Ok(()) }
 /// ```
 #[derive(Clone)]
-pub struct FontCache {
-    cache: HashMap<char, GlyphId>,
-    ascent_unscaled: f32,
-    descent_unscaled: f32,
-    line_gap_unscaled: f32,
-}
-
-impl FontCache {
-    pub fn get_glyph_id(&self, c: char) -> Option<&GlyphId> {
-        self.cache.get(&c)
-    }
-}
-
-#[derive(Clone)]
-pub struct FontRef<'font>(ttfp::PreParsedSubtables<'font, ttfp::Face<'font>>, FontCache);
+pub struct FontRef<'font>(
+    ttfp::PreParsedSubtables<'font, ttfp::Face<'font>>,
+    FontCache,
+);
 
 impl fmt::Debug for FontRef<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -129,10 +232,32 @@ impl<'font> FontRef<'font> {
     /// ```
     #[inline]
     pub fn try_from_slice_and_index(data: &'font [u8], index: u32) -> Result<Self, InvalidFont> {
+        Self::try_from_slice_and_index_with_cache(data, index, GlyphIdCache::default())
+    }
+
+    /// Creates an `FontRef` from byte-slice, with a [`GlyphIdCache`] strategy.
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # fn main() -> Result<(), InvalidFont> {
+    /// let font = FontRef::try_from_slice_and_index_with_cache(
+    ///     include_bytes!("../../dev/fonts/Exo2-Light.otf"),
+    ///     0,
+    ///     GlyphIdCache::Bmp,
+    /// )?;
+    /// # Ok(()) }
+    /// ```
+    #[inline]
+    pub fn try_from_slice_and_index_with_cache(
+        data: &'font [u8],
+        index: u32,
+        cache: GlyphIdCache,
+    ) -> Result<Self, InvalidFont> {
         let pre_parsed_subtables = ttfp::PreParsedSubtables::from(
             ttfp::Face::from_slice(data, index).map_err(|_| InvalidFont)?,
         );
-        let cache = font_init(&pre_parsed_subtables);
+        let cache = font_init(&pre_parsed_subtables, &cache);
         Ok(Self(pre_parsed_subtables, cache))
     }
 }
@@ -153,7 +278,10 @@ impl<'font> FontRef<'font> {
 /// assert_eq!(font.glyph_id('s'), ab_glyph::GlyphId(56));
 /// # Ok(()) }
 /// ```
-pub struct FontVec(ttfp::PreParsedSubtables<'static, ttfp::OwnedFace>, FontCache);
+pub struct FontVec(
+    ttfp::PreParsedSubtables<'static, ttfp::OwnedFace>,
+    FontCache,
+);
 
 impl fmt::Debug for FontVec {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -195,34 +323,97 @@ impl FontVec {
     /// ```
     #[inline]
     pub fn try_from_vec_and_index(data: Vec<u8>, index: u32) -> Result<Self, InvalidFont> {
+        Self::try_from_vec_and_index_with_cache(data, index, GlyphIdCache::default())
+    }
+
+    /// Creates an `FontVec` from owned data, with a [`GlyphIdCache`] strategy.
+    ///
+    /// # Example
+    /// ```
+    /// # use ab_glyph::*;
+    /// # fn main() -> Result<(), InvalidFont> {
+    /// # let owned_font_data = include_bytes!("../../dev/fonts/Exo2-Light.otf").to_vec();
+    /// let font = FontVec::try_from_vec_and_index_with_cache(owned_font_data, 0, GlyphIdCache::Lazy)?;
+    /// # Ok(()) }
+    /// ```
+    #[inline]
+    pub fn try_from_vec_and_index_with_cache(
+        data: Vec<u8>,
+        index: u32,
+        cache: GlyphIdCache,
+    ) -> Result<Self, InvalidFont> {
         let pre_parsed_subtables = ttfp::PreParsedSubtables::from(
             ttfp::OwnedFace::from_vec(data, index).map_err(|_| InvalidFont)?,
         );
-        let cache = font_init(&pre_parsed_subtables);
+        let cache = font_init(&pre_parsed_subtables, &cache);
         Ok(Self(pre_parsed_subtables, cache))
     }
 }
 
+fn font_init<F>(pre_parsed_subtables: &PreParsedSubtables<F>, cache: &GlyphIdCache) -> FontCache
+where
+    F: AsFaceRef,
+{
+    let mut cache = FontCache {
+        glyph_ids: GlyphIds::new(cache, pre_parsed_subtables),
+        ascent_unscaled: 0.0,
+        descent_unscaled: 0.0,
+        line_gap_unscaled: 0.0,
+        #[cfg(feature = "opentype-layout")]
+        gpos_kerning: kern::GposKerning::new(pre_parsed_subtables.as_face_ref()),
+        #[cfg(feature = "hinting")]
+        hinting: <_>::default(),
+    };
+    cache.update_metrics(pre_parsed_subtables.as_face_ref());
+    cache
+}
+/// Looks up the glyph of `c`, treating `.notdef` mappings as missing.
+#[inline]
+fn glyph_index<F>(pre_parsed_subtables: &PreParsedSubtables<F>, c: char) -> Option<GlyphId>
+where
+    F: AsFaceRef,
+{
+    // Note: Using `PreParsedSubtables` method for better performance.
+    pre_parsed_subtables
+        .glyph_index(c)
+        .map(|id| GlyphId(id.0))
+        .filter(|id| id.0 != 0)
+}
 
-fn font_init<F>(pre_parsed_subtables: &PreParsedSubtables<F>) -> FontCache
-    where F: AsFaceRef {
-    let chars_str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789#+*ç%&/()=?`~'-.,;:_<>!¨$€\\/\"|§°[]{}üèöé \n\t\r";
-    let chars = chars_str.chars();
-    let mut glyph_index_cache = HashMap::<char, GlyphId>::with_capacity(chars_str.len());
-    chars
-        .for_each(|c| {
-            let maybe_glyph_index = pre_parsed_subtables.glyph_index(c);
-            if maybe_glyph_index.is_some() {
-                glyph_index_cache.insert(c, GlyphId(maybe_glyph_index.map(|id| id.0).unwrap_or(0)));
-            }
-        });
-    FontCache {
-        cache: glyph_index_cache,
-        ascent_unscaled: pre_parsed_subtables.as_face_ref().ascender().into(),
-        descent_unscaled: pre_parsed_subtables.as_face_ref().descender().into(),
-        line_gap_unscaled: pre_parsed_subtables.as_face_ref().line_gap().into(),
+/// Vertical `(top side bearing, advance)` of a glyph with top `y_max`, derived from the
+/// ascender & descender for fonts without vertical metrics, as TrueType phantom points are.
+///
+/// Glyphs without a bounding box, `y_max = None`, have a zero top side bearing.
+fn derived_ver_metrics(face: &ttfp::Face<'_>, y_max: Option<i16>) -> (i32, i32) {
+    let ascender = face
+        .typographic_ascender()
+        .unwrap_or_else(|| face.ascender());
+    let descender = face
+        .typographic_descender()
+        .unwrap_or_else(|| face.descender());
+    (
+        i32::from(ascender) - i32::from(y_max.unwrap_or(ascender)),
+        i32::from(ascender) - i32::from(descender),
+    )
+}
+
+/// Vertical `(top side bearing, advance)` of glyph `id` from `vmtx`, or derived if the
+/// font has no vertical metrics. `None` for invalid glyph ids.
+fn glyph_ver_metrics(face: &ttfp::Face<'_>, id: GlyphId) -> Option<(f32, f32)> {
+    let id = ttfp::GlyphId::from(id);
+    if id.0 >= face.number_of_glyphs() {
+        return None;
+    }
+    match (face.glyph_ver_side_bearing(id), face.glyph_ver_advance(id)) {
+        (Some(tsb), Some(advance)) => Some((tsb.into(), advance.into())),
+        _ => {
+            let y_max = face.glyph_bounding_box(id).map(|b| b.y_max);
+            let (tsb, advance) = derived_ver_metrics(face, y_max);
+            Some((tsb as f32, advance as f32))
+        }
     }
 }
+
 /// Implement `Font` for `Self(AsFontRef)` types.
 macro_rules! impl_font {
     ($font:ty) => {
@@ -250,50 +441,62 @@ macro_rules! impl_font {
 
             #[inline]
             fn glyph_id(&self, c: char) -> GlyphId {
-                // Note: Using `PreParsedSubtables` method for better performance.
-                let maybe_cached_index = self.1.get_glyph_id(c);
-                if maybe_cached_index.is_some() {
-                    maybe_cached_index.unwrap().clone()
-                } else {
-                   let index = self.0.glyph_index(c).map(|id| id.0).unwrap_or(0);
-                   GlyphId(index)
-                }
+                self.try_glyph_id(c).unwrap_or(GlyphId(0))
+            }
+
+            #[inline]
+            fn try_glyph_id(&self, c: char) -> Option<GlyphId> {
+                self.1.glyph_ids.get(c, |c| glyph_index(&self.0, c))
             }
 
             #[inline]
             fn h_advance_unscaled(&self, id: GlyphId) -> f32 {
+                self.try_h_advance_unscaled(id)
+                    .expect("Invalid glyph_hor_advance")
+            }
+
+            #[inline]
+            fn try_h_advance_unscaled(&self, id: GlyphId) -> Option<f32> {
                 self.0
                     .as_face_ref()
                     .glyph_hor_advance(id.into())
-                    .expect("Invalid glyph_hor_advance")
-                    .into()
+                    .map(f32::from)
             }
 
             #[inline]
             fn h_side_bearing_unscaled(&self, id: GlyphId) -> f32 {
+                self.try_h_side_bearing_unscaled(id)
+                    .expect("Invalid glyph_hor_side_bearing")
+            }
+
+            #[inline]
+            fn try_h_side_bearing_unscaled(&self, id: GlyphId) -> Option<f32> {
                 self.0
                     .as_face_ref()
                     .glyph_hor_side_bearing(id.into())
-                    .expect("Invalid glyph_hor_side_bearing")
-                    .into()
+                    .map(f32::from)
             }
 
             #[inline]
             fn v_advance_unscaled(&self, id: GlyphId) -> f32 {
-                self.0
-                    .as_face_ref()
-                    .glyph_ver_advance(id.into())
+                self.try_v_advance_unscaled(id)
                     .expect("Invalid glyph_ver_advance")
-                    .into()
+            }
+
+            #[inline]
+            fn try_v_advance_unscaled(&self, id: GlyphId) -> Option<f32> {
+                glyph_ver_metrics(self.0.as_face_ref(), id).map(|(_, advance)| advance)
             }
 
             #[inline]
             fn v_side_bearing_unscaled(&self, id: GlyphId) -> f32 {
-                self.0
-                    .as_face_ref()
-                    .glyph_ver_side_bearing(id.into())
+                self.try_v_side_bearing_unscaled(id)
                     .expect("Invalid glyph_ver_side_bearing")
-                    .into()
+            }
+
+            #[inline]
+            fn try_v_side_bearing_unscaled(&self, id: GlyphId) -> Option<f32> {
+                glyph_ver_metrics(self.0.as_face_ref(), id).map(|(tsb, _)| tsb)
             }
 
             #[inline]
@@ -302,6 +505,7 @@ macro_rules! impl_font {
                 self.0
                     .glyphs_hor_kerning(first.into(), second.into())
                     .map(f32::from)
+                    .or_else(|| self.1.gpos_kern(self.0.as_face_ref(), first, second))
                     .unwrap_or_default()
             }
 
@@ -330,6 +534,12 @@ macro_rules! impl_font {
                 Some(Outline { bounds, curves })
             }
 
+            #[cfg(feature = "hinting")]
+            #[inline]
+            fn hinted_outline(&self, id: GlyphId, ppem: f32) -> Option<Outline> {
+                hinting::hinted_outline(self.0.as_face_ref(), &self.1.hinting, id, ppem)
+            }
+
             #[inline]
             fn glyph_count(&self) -> usize {
                 self.0.as_face_ref().number_of_glyphs() as _
@@ -369,11 +579,13 @@ macro_rules! impl_font {
                 crate::CodepointIdIter { inner }
             }
 
-            fn glyph_raster_image(&self, id: GlyphId, size: u16) -> Option<GlyphImage> {
-                self.0
-                    .as_face_ref()
-                    .glyph_raster_image(id.into(), size)
-                    .map(Into::into)
+            fn glyph_raster_image(&self, id: GlyphId, size: u16) -> Option<GlyphImage<'_>> {
+                bitmap::glyph_image(self.0.as_face_ref(), id, size)
+            }
+
+            #[inline]
+            fn color_glyph(&self, id: GlyphId, palette: u16) -> Option<ColorGlyph> {
+                colr::color_glyph(self.0.as_face_ref(), id, palette)
             }
         }
     };
@@ -381,3 +593,79 @@ macro_rules! impl_font {
 
 impl_font!(FontRef<'_>);
 impl_font!(FontVec);
+
+/// Implement `VariableFont` for `Self(AsFontRef + FaceMut, FontCache)` types.
+#[cfg(feature = "variable-fonts")]
+macro_rules! impl_variable_font {
+    ($font:ty) => {
+        impl crate::VariableFont for $font {
+            fn set_variation(&mut self, axis: &[u8; 4], value: f32) -> bool {
+                use ttfp::FaceMut;
+
+                let tag = ttfp::Tag::from_bytes(axis);
+                if self.0.set_variation(tag, value).is_some() {
+                    // ascent, descent & line gap may have MVAR deltas
+                    self.1.update_metrics(self.0.as_face_ref());
+                    true
+                } else {
+                    false
+                }
+            }
+
+            fn variations(&self) -> Vec<crate::VariationAxis> {
+                let face = self.0.as_face_ref();
+                face.variation_axes()
+                    .into_iter()
+                    .map(|axis| {
+                        #[cfg(feature = "std")]
+                        let name = face.names().into_iter().find_map(|n| {
+                            if n.name_id == axis.name_id {
+                                n.to_string()
+                            } else {
+                                None
+                            }
+                        });
+                        crate::VariationAxis {
+                            tag: axis.tag.to_bytes(),
+                            #[cfg(feature = "std")]
+                            name,
+                            min_value: axis.min_value,
+                            default_value: axis.def_value,
+                            max_value: axis.max_value,
+                            hidden: axis.hidden,
+                        }
+                    })
+                    .collect()
+            }
+        }
+    };
+}
+
+#[cfg(feature = "variable-fonts")]
+impl_variable_font!(FontRef<'_>);
+#[cfg(feature = "variable-fonts")]
+impl_variable_font!(FontVec);
+
+/// Implement `ShapeFont` for `Self(AsFontRef, FontCache)` types.
+#[cfg(feature = "opentype-layout")]
+macro_rules! impl_shape_font {
+    ($font:ty) => {
+        impl crate::ShapeFont for $font {
+            #[inline]
+            fn shape(
+                &self,
+                text: &str,
+                features: &[crate::Feature],
+                script: &[u8; 4],
+                language: Option<&[u8; 4]>,
+            ) -> Vec<crate::ShapedGlyph> {
+                shaping::shape(self, self.0.as_face_ref(), text, features, script, language)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "opentype-layout")]
+impl_shape_font!(FontRef<'_>);
+#[cfg(feature = "opentype-layout")]
+impl_shape_font!(FontVec);
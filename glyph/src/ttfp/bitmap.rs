@@ -0,0 +1,278 @@
+//! Embedded bitmap parsing from `sbix`, `CBLC`/`CBDT` & `EBLC`/`EBDT` tables.
+//!
+//! See <https://docs.microsoft.com/en-us/typography/opentype/spec/sbix>,
+//! <https://docs.microsoft.com/en-us/typography/opentype/spec/cbdt> &
+//! <https://docs.microsoft.com/en-us/typography/opentype/spec/ebdt>.
+use super::read::ReadBe;
+use crate::{point, GlyphId, GlyphImage, GlyphImageFormat};
+use owned_ttf_parser::{self as ttfp, Tag};
+
+/// Maximum number of followed `sbix` "dupe" records, protects against cycles.
+const MAX_DUPE_DEPTH: u8 = 8;
+
+/// Returns the embedded image for `id` from the strike best matching `pixel_size`.
+///
+/// Tables are tried in order `sbix`, `CBLC`/`CBDT` then `EBLC`/`EBDT`.
+pub(crate) fn glyph_image<'a>(
+    face: &ttfp::Face<'a>,
+    id: GlyphId,
+    pixel_size: u16,
+) -> Option<GlyphImage<'a>> {
+    if let Some(sbix) = face.table_data(Tag::from_bytes(b"sbix")) {
+        if let Some(image) = sbix_image(sbix, face.number_of_glyphs(), id, pixel_size) {
+            return Some(image);
+        }
+    }
+
+    [(b"CBLC", b"CBDT"), (b"EBLC", b"EBDT")]
+        .iter()
+        .find_map(|(location, data)| {
+            let location = face.table_data(Tag::from_bytes(location))?;
+            let data = face.table_data(Tag::from_bytes(data))?;
+            bdt_image(location, data, id, pixel_size)
+        })
+}
+
+/// Whether a strike of `ppem` is a better match for `target` than the current `best`.
+///
+/// The smallest strike at least as large as the target is preferred,
+/// otherwise the largest available.
+fn is_better_strike(best: Option<u16>, ppem: u16, target: u16) -> bool {
+    match best {
+        None => true,
+        Some(best) if best >= target => ppem >= target && ppem < best,
+        Some(best) => ppem > best,
+    }
+}
+
+/// Reads the `(width, height)` from a PNG `IHDR` chunk.
+fn png_size(data: &[u8]) -> Option<(u16, u16)> {
+    let width = data.read_u32(16)?;
+    let height = data.read_u32(20)?;
+    Some((
+        width.min(u16::MAX.into()) as _,
+        height.min(u16::MAX.into()) as _,
+    ))
+}
+
+fn sbix_image(
+    data: &[u8],
+    num_glyphs: u16,
+    id: GlyphId,
+    pixel_size: u16,
+) -> Option<GlyphImage<'_>> {
+    if id.0 >= num_glyphs {
+        return None;
+    }
+    let num_strikes = data.read_u32(4)? as usize;
+
+    let mut best = None;
+    for idx in 0..num_strikes {
+        let strike = data.read_u32(8 + 4 * idx)? as usize;
+        let ppem = data.read_u16(strike)?;
+        let has_glyph = sbix_glyph_range(data, strike, id).map_or(false, |(s, e)| s < e);
+        if has_glyph && is_better_strike(best.map(|(ppem, _)| ppem), ppem, pixel_size) {
+            best = Some((ppem, strike));
+        }
+    }
+
+    let (ppem, strike) = best?;
+    sbix_glyph(data, strike, num_glyphs, id, ppem, 0)
+}
+
+/// Returns the `[start, end)` data offsets of a glyph record in a `sbix` strike.
+fn sbix_glyph_range(data: &[u8], strike: usize, id: GlyphId) -> Option<(usize, usize)> {
+    let offsets = strike + 4 + 4 * usize::from(id.0);
+    let start = strike + data.read_u32(offsets)? as usize;
+    let end = strike + data.read_u32(offsets + 4)? as usize;
+    Some((start, end))
+}
+
+fn sbix_glyph(
+    data: &[u8],
+    strike: usize,
+    num_glyphs: u16,
+    id: GlyphId,
+    ppem: u16,
+    depth: u8,
+) -> Option<GlyphImage<'_>> {
+    if id.0 >= num_glyphs || depth > MAX_DUPE_DEPTH {
+        return None;
+    }
+    let (start, end) = sbix_glyph_range(data, strike, id)?;
+    let image = data.get(start.checked_add(8)?..end)?;
+    let x = data.read_i16(start)?;
+    let y = data.read_i16(start + 2)?;
+
+    match data.get(start + 4..start + 8)? {
+        b"png " => {
+            let (width, height) = png_size(image)?;
+            Some(GlyphImage {
+                origin: point(x.into(), y.into()),
+                scale: ppem.into(),
+                width,
+                height,
+                data: image,
+                format: GlyphImageFormat::Png,
+            })
+        }
+        b"dupe" => {
+            let id = GlyphId(image.read_u16(0)?);
+            sbix_glyph(data, strike, num_glyphs, id, ppem, depth + 1)
+        }
+        // jpg, tiff & others are unsupported
+        _ => None,
+    }
+}
+
+/// Glyph bitmap metrics in pixels.
+#[derive(Clone, Copy)]
+struct Metrics {
+    width: u8,
+    height: u8,
+    bearing_x: i8,
+    bearing_y: i8,
+}
+
+impl Metrics {
+    /// Reads small or big glyph metrics, the horizontal fields are shared by both.
+    fn read(data: &[u8], offset: usize) -> Option<Self> {
+        Some(Self {
+            height: data.read_u8(offset)?,
+            width: data.read_u8(offset + 1)?,
+            bearing_x: data.read_i8(offset + 2)?,
+            bearing_y: data.read_i8(offset + 3)?,
+        })
+    }
+}
+
+/// Size of `smallGlyphMetrics` & `bigGlyphMetrics` records.
+const SMALL_METRICS: usize = 5;
+const BIG_METRICS: usize = 8;
+
+/// Finds the image of `id` using `CBLC`/`EBLC` `location` data, reading from
+/// `CBDT`/`EBDT` `data`.
+fn bdt_image<'a>(
+    location: &[u8],
+    data: &'a [u8],
+    id: GlyphId,
+    pixel_size: u16,
+) -> Option<GlyphImage<'a>> {
+    let num_sizes = location.read_u32(4)? as usize;
+
+    let mut best = None;
+    for idx in 0..num_sizes {
+        let record = 8 + 48 * idx;
+        let start_glyph = location.read_u16(record + 40)?;
+        let end_glyph = location.read_u16(record + 42)?;
+        let ppem = u16::from(location.read_u8(record + 44)?);
+        if (start_glyph..=end_glyph).contains(&id.0)
+            && is_better_strike(best.map(|(ppem, _)| ppem), ppem, pixel_size)
+        {
+            best = Some((ppem, record));
+        }
+    }
+    let (ppem, record) = best?;
+    let bit_depth = location.read_u8(record + 46)?;
+
+    // find the index subtable covering the glyph
+    let array = location.read_u32(record)? as usize;
+    let num_subtables = location.read_u32(record + 8)? as usize;
+    let (first_glyph, subtable) = (0..num_subtables).find_map(|idx| {
+        let entry = array + 8 * idx;
+        let first = location.read_u16(entry)?;
+        let last = location.read_u16(entry + 2)?;
+        let offset = array + location.read_u32(entry + 4)? as usize;
+        Some((first, offset)).filter(|_| (first..=last).contains(&id.0))
+    })?;
+
+    let index_format = location.read_u16(subtable)?;
+    let image_format = location.read_u16(subtable + 2)?;
+    let image_data = location.read_u32(subtable + 4)? as usize;
+    let glyph_idx = usize::from(id.0.checked_sub(first_glyph)?);
+
+    let mut shared_metrics = None;
+    let offset = match index_format {
+        1 => image_data + location.read_u32(subtable + 8 + 4 * glyph_idx)? as usize,
+        3 => image_data + usize::from(location.read_u16(subtable + 8 + 2 * glyph_idx)?),
+        2 => {
+            let image_size = location.read_u32(subtable + 8)? as usize;
+            shared_metrics = Some(Metrics::read(location, subtable + 12)?);
+            image_data + glyph_idx.checked_mul(image_size)?
+        }
+        4 => {
+            let num_glyphs = location.read_u32(subtable + 8)? as usize;
+            let pair = (0..num_glyphs)
+                .map(|idx| subtable + 12 + 4 * idx)
+                .find(|&pair| location.read_u16(pair) == Some(id.0))?;
+            image_data + usize::from(location.read_u16(pair + 2)?)
+        }
+        5 => {
+            let image_size = location.read_u32(subtable + 8)? as usize;
+            shared_metrics = Some(Metrics::read(location, subtable + 12)?);
+            let num_glyphs = location.read_u32(subtable + 12 + BIG_METRICS)? as usize;
+            let ids = subtable + 16 + BIG_METRICS;
+            let sparse_idx =
+                (0..num_glyphs).find(|idx| location.read_u16(ids + 2 * idx) == Some(id.0))?;
+            image_data + sparse_idx.checked_mul(image_size)?
+        }
+        _ => return None,
+    };
+
+    let (metrics, offset) = match image_format {
+        1 | 2 | 17 => (Metrics::read(data, offset)?, offset + SMALL_METRICS),
+        6 | 7 | 18 => (Metrics::read(data, offset)?, offset + BIG_METRICS),
+        5 | 19 => (shared_metrics?, offset),
+        // 8 & 9 are composites of other bitmaps, unsupported
+        _ => return None,
+    };
+
+    let (width, height) = (u16::from(metrics.width), u16::from(metrics.height));
+    let (format, data) = match image_format {
+        17..=19 => {
+            let len = data.read_u32(offset)? as usize;
+            let start = offset + 4;
+            (
+                GlyphImageFormat::Png,
+                data.get(start..start.checked_add(len)?)?,
+            )
+        }
+        _ => {
+            let bit_aligned = matches!(image_format, 2 | 5 | 7);
+            let (w, h, d) = (
+                usize::from(width),
+                usize::from(height),
+                usize::from(bit_depth),
+            );
+            let len = match bit_aligned {
+                true => (w * h * d + 7) / 8,
+                false => (w * d + 7) / 8 * h,
+            };
+            let format = match (bit_depth, bit_aligned) {
+                (1, false) => GlyphImageFormat::BitmapMono,
+                (1, true) => GlyphImageFormat::BitmapMonoPacked,
+                (2, false) => GlyphImageFormat::BitmapGray2,
+                (2, true) => GlyphImageFormat::BitmapGray2Packed,
+                (4, false) => GlyphImageFormat::BitmapGray4,
+                (4, true) => GlyphImageFormat::BitmapGray4Packed,
+                (8, _) => GlyphImageFormat::BitmapGray8,
+                (32, _) => GlyphImageFormat::BitmapPremulBgra32,
+                _ => return None,
+            };
+            (format, data.get(offset..offset.checked_add(len)?)?)
+        }
+    };
+
+    Some(GlyphImage {
+        // bearing y is the top edge, origin is the bottom edge
+        origin: point(
+            metrics.bearing_x.into(),
+            f32::from(metrics.bearing_y) - f32::from(height),
+        ),
+        scale: ppem.into(),
+        width,
+        height,
+        data,
+        format,
+    })
+}
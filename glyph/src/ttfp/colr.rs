@@ -0,0 +1,442 @@
+//! `COLR` & `CPAL` table parsing.
+//!
+//! See <https://docs.microsoft.com/en-us/typography/opentype/spec/colr>.
+use super::read::ReadBe;
+#[cfg(all(feature = "libm", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+use crate::{
+    float_cmp::total_cmp, point, Color, ColorGlyph, ColorLayer, ColorLine, ColorStop,
+    CompositeMode, Extend, GlyphId, Paint, Rect,
+};
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::cell::Cell;
+use owned_ttf_parser::{self as ttfp, Tag};
+
+/// Palette index indicating the text foreground color.
+const FOREGROUND: u16 = 0xFFFF;
+/// Maximum paint graph depth, protects against cyclic paint graphs.
+const MAX_DEPTH: u8 = 64;
+/// Maximum number of paint tables parsed per glyph. Paint tables may be shared by multiple
+/// parents, so without a total limit small graphs can expand exponentially.
+const MAX_PAINTS: u32 = 10_000;
+
+/// Returns the color glyph description for `id` using colors from `palette`.
+pub(crate) fn color_glyph(face: &ttfp::Face<'_>, id: GlyphId, palette: u16) -> Option<ColorGlyph> {
+    let colr = Colr::parse(face.table_data(Tag::from_bytes(b"COLR"))?)?;
+    let cpal = face
+        .table_data(Tag::from_bytes(b"CPAL"))
+        .and_then(|data| Cpal::parse(data, palette));
+
+    if let Some(paint_offset) = colr.base_glyph_paint(id) {
+        let cx = PaintContext {
+            colr: &colr,
+            cpal: cpal.as_ref(),
+            budget: Cell::new(MAX_PAINTS),
+        };
+        let paint = cx.paint(paint_offset, 0)?;
+        return Some(ColorGlyph::Paint {
+            paint,
+            clip_box: colr.clip_box(id),
+        });
+    }
+
+    let (first, count) = colr.base_glyph_layers(id)?;
+    let layers = (first..first + count)
+        .map(|idx| {
+            let record = colr.layer_records + 4 * usize::from(idx);
+            let id = GlyphId(colr.data.read_u16(record)?);
+            let color = resolve_color(cpal.as_ref(), colr.data.read_u16(record + 2)?);
+            Some(ColorLayer { id, color })
+        })
+        .collect::<Option<_>>()?;
+    Some(ColorGlyph::Layers(layers))
+}
+
+/// Resolves a palette index into a color, `None` indicates the foreground color.
+fn resolve_color(cpal: Option<&Cpal<'_>>, index: u16) -> Option<Color> {
+    if index == FOREGROUND {
+        return None;
+    }
+    // missing colors are not fatal, render them in the foreground color
+    cpal.and_then(|cpal| cpal.color(index))
+}
+
+/// `CPAL` table with a selected palette.
+struct Cpal<'a> {
+    data: &'a [u8],
+    num_entries: u16,
+    first_color_record: usize,
+}
+
+impl<'a> Cpal<'a> {
+    /// Parses the table selecting `palette`, falling back to the first palette.
+    fn parse(data: &'a [u8], palette: u16) -> Option<Self> {
+        let num_entries = data.read_u16(2)?;
+        let num_palettes = data.read_u16(4)?;
+        let color_records = data.read_u32(8)? as usize;
+        let palette = if palette < num_palettes { palette } else { 0 };
+        let first_index = data.read_u16(12 + 2 * usize::from(palette))?;
+        Some(Self {
+            data,
+            num_entries,
+            first_color_record: color_records + 4 * usize::from(first_index),
+        })
+    }
+
+    fn color(&self, index: u16) -> Option<Color> {
+        if index >= self.num_entries {
+            return None;
+        }
+        let offset = self.first_color_record + 4 * usize::from(index);
+        let bgra = self.data.get(offset..offset + 4)?;
+        Some(Color::new(bgra[2], bgra[1], bgra[0], bgra[3]))
+    }
+}
+
+struct Colr<'a> {
+    data: &'a [u8],
+    // v0
+    num_base_glyphs: u16,
+    base_glyph_records: usize,
+    layer_records: usize,
+    num_layers: u16,
+    // v1
+    base_glyph_list: Option<usize>,
+    layer_list: Option<usize>,
+    clip_list: Option<usize>,
+}
+
+impl<'a> Colr<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let version = data.read_u16(0)?;
+        let mut colr = Self {
+            data,
+            num_base_glyphs: data.read_u16(2)?,
+            base_glyph_records: data.read_u32(4)? as usize,
+            layer_records: data.read_u32(8)? as usize,
+            num_layers: data.read_u16(12)?,
+            base_glyph_list: None,
+            layer_list: None,
+            clip_list: None,
+        };
+        if version >= 1 {
+            let non_zero = |offset: u32| Some(offset as usize).filter(|o| *o > 0);
+            colr.base_glyph_list = non_zero(data.read_u32(14)?);
+            colr.layer_list = non_zero(data.read_u32(18)?);
+            colr.clip_list = non_zero(data.read_u32(22)?);
+        }
+        Some(colr)
+    }
+
+    /// Returns `(first_layer_index, num_layers)` of a v0 base glyph.
+    fn base_glyph_layers(&self, id: GlyphId) -> Option<(u16, u16)> {
+        let record = binary_search(
+            self.data,
+            self.base_glyph_records,
+            6,
+            u32::from(self.num_base_glyphs),
+            id.0,
+        )?;
+        let first = self.data.read_u16(record + 2)?;
+        let count = self.data.read_u16(record + 4)?;
+        if u32::from(first) + u32::from(count) > u32::from(self.num_layers) {
+            return None;
+        }
+        Some((first, count))
+    }
+
+    /// Returns the absolute offset of a v1 base glyph's paint table.
+    fn base_glyph_paint(&self, id: GlyphId) -> Option<usize> {
+        let list = self.base_glyph_list?;
+        let count = self.data.read_u32(list)?;
+        let record = binary_search(self.data, list + 4, 6, count, id.0)?;
+        Some(list + self.data.read_u32(record + 2)? as usize)
+    }
+
+    /// Returns the absolute offset of a layer list paint table.
+    fn layer_paint(&self, index: u32) -> Option<usize> {
+        let list = self.layer_list?;
+        if index >= self.data.read_u32(list)? {
+            return None;
+        }
+        let offset = list + 4 + 4 * index as usize;
+        Some(list + self.data.read_u32(offset)? as usize)
+    }
+
+    /// Returns the unscaled clip box of a v1 base glyph.
+    fn clip_box(&self, id: GlyphId) -> Option<Rect> {
+        let list = self.clip_list?;
+        let count = self.data.read_u32(list + 1)?;
+        // clip records are ordered by, non-overlapping, glyph id ranges
+        let (mut lo, mut hi) = (0, count);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let record = list + 5 + 7 * mid as usize;
+            let start = self.data.read_u16(record)?;
+            let end = self.data.read_u16(record + 2)?;
+            if id.0 < start {
+                hi = mid;
+            } else if id.0 > end {
+                lo = mid + 1;
+            } else {
+                let clip = list + self.data.read_u24(record + 4)? as usize;
+                let d = self.data;
+                return Some(Rect {
+                    min: point(d.read_i16(clip + 1)?.into(), d.read_i16(clip + 7)?.into()),
+                    max: point(d.read_i16(clip + 5)?.into(), d.read_i16(clip + 3)?.into()),
+                });
+            }
+        }
+        None
+    }
+}
+
+/// Binary search of records starting with a `u16` glyph id, returns the
+/// matching record offset.
+fn binary_search(data: &[u8], start: usize, size: usize, count: u32, id: u16) -> Option<usize> {
+    let (mut lo, mut hi) = (0, count as usize);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let record = start + mid * size;
+        let record_id = data.read_u16(record)?;
+        match record_id.cmp(&id) {
+            core::cmp::Ordering::Less => lo = mid + 1,
+            core::cmp::Ordering::Greater => hi = mid,
+            core::cmp::Ordering::Equal => return Some(record),
+        }
+    }
+    None
+}
+
+struct PaintContext<'a, 'b> {
+    colr: &'b Colr<'a>,
+    cpal: Option<&'b Cpal<'a>>,
+    /// Remaining number of paint tables that may be parsed.
+    budget: Cell<u32>,
+}
+
+impl PaintContext<'_, '_> {
+    /// Parses the paint table at absolute `offset`.
+    fn paint(&self, offset: usize, depth: u8) -> Option<Paint> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        self.budget.set(self.budget.get().checked_sub(1)?);
+        let d = self.colr.data;
+        let format = d.read_u8(offset)?;
+        // Variable formats share the layout of the preceding format, with
+        // additional trailing variation indices that are ignored here.
+        let (format, var) = match format {
+            3 | 5 | 7 | 9 | 13 | 15 | 17 | 19 | 21 | 23 | 25 | 27 | 29 | 31 => (format - 1, true),
+            f => (f, false),
+        };
+        let child = |at: usize| -> Option<Box<Paint>> {
+            let child_offset = offset + d.read_u24(offset + at)? as usize;
+            self.paint(child_offset, depth + 1).map(Box::new)
+        };
+        let fword = |at: usize| d.read_i16(offset + at).map(f32::from);
+        let f2dot14 = |at: usize| d.read_f2dot14(offset + at);
+        let transformed = |matrix: [f32; 6]| -> Option<Paint> {
+            Some(Paint::Transform {
+                matrix,
+                paint: child(1)?,
+            })
+        };
+        let around_center = |matrix: [f32; 6], center: (f32, f32)| -> Option<Paint> {
+            let (cx, cy) = center;
+            let [xx, yx, xy, yy, dx, dy] = matrix;
+            // translate(center) * matrix * translate(-center)
+            Some(Paint::Transform {
+                matrix: [
+                    xx,
+                    yx,
+                    xy,
+                    yy,
+                    dx + cx - (xx * cx + xy * cy),
+                    dy + cy - (yx * cx + yy * cy),
+                ],
+                paint: child(1)?,
+            })
+        };
+
+        match format {
+            // PaintColrLayers
+            1 => {
+                let count = d.read_u8(offset + 1)?;
+                let first = d.read_u32(offset + 2)?;
+                let layers = (0..u32::from(count))
+                    .map(|i| {
+                        let layer = self.colr.layer_paint(first.checked_add(i)?)?;
+                        self.paint(layer, depth + 1)
+                    })
+                    .collect::<Option<_>>()?;
+                Some(Paint::Layers(layers))
+            }
+            // PaintSolid
+            2 => Some(Paint::Solid {
+                color: resolve_color(self.cpal, d.read_u16(offset + 1)?),
+                alpha: f2dot14(3)?,
+            }),
+            // PaintLinearGradient
+            4 => Some(Paint::LinearGradient {
+                color_line: self.color_line(offset + d.read_u24(offset + 1)? as usize, var)?,
+                p0: point(fword(4)?, fword(6)?),
+                p1: point(fword(8)?, fword(10)?),
+                p2: point(fword(12)?, fword(14)?),
+            }),
+            // PaintRadialGradient
+            6 => Some(Paint::RadialGradient {
+                color_line: self.color_line(offset + d.read_u24(offset + 1)? as usize, var)?,
+                c0: point(fword(4)?, fword(6)?),
+                r0: d.read_u16(offset + 8)?.into(),
+                c1: point(fword(10)?, fword(12)?),
+                r1: d.read_u16(offset + 14)?.into(),
+            }),
+            // PaintSweepGradient
+            8 => Some(Paint::SweepGradient {
+                color_line: self.color_line(offset + d.read_u24(offset + 1)? as usize, var)?,
+                center: point(fword(4)?, fword(6)?),
+                start_angle: f2dot14(8)? * 180.0,
+                end_angle: f2dot14(10)? * 180.0,
+            }),
+            // PaintGlyph
+            10 => Some(Paint::Glyph {
+                paint: child(1)?,
+                id: GlyphId(d.read_u16(offset + 4)?),
+            }),
+            // PaintColrGlyph
+            11 => {
+                let id = GlyphId(d.read_u16(offset + 1)?);
+                let base = self.colr.base_glyph_paint(id)?;
+                self.paint(base, depth + 1)
+            }
+            // PaintTransform
+            12 => {
+                let affine = offset + d.read_u24(offset + 4)? as usize;
+                transformed([
+                    d.read_fixed(affine)?,
+                    d.read_fixed(affine + 4)?,
+                    d.read_fixed(affine + 8)?,
+                    d.read_fixed(affine + 12)?,
+                    d.read_fixed(affine + 16)?,
+                    d.read_fixed(affine + 20)?,
+                ])
+            }
+            // PaintTranslate
+            14 => transformed([1.0, 0.0, 0.0, 1.0, fword(4)?, fword(6)?]),
+            // PaintScale
+            16 => transformed([f2dot14(4)?, 0.0, 0.0, f2dot14(6)?, 0.0, 0.0]),
+            // PaintScaleAroundCenter
+            18 => around_center(
+                [f2dot14(4)?, 0.0, 0.0, f2dot14(6)?, 0.0, 0.0],
+                (fword(8)?, fword(10)?),
+            ),
+            // PaintScaleUniform
+            20 => {
+                let s = f2dot14(4)?;
+                transformed([s, 0.0, 0.0, s, 0.0, 0.0])
+            }
+            // PaintScaleUniformAroundCenter
+            22 => {
+                let s = f2dot14(4)?;
+                around_center([s, 0.0, 0.0, s, 0.0, 0.0], (fword(6)?, fword(8)?))
+            }
+            // PaintRotate
+            24 => transformed(rotation(f2dot14(4)?)),
+            // PaintRotateAroundCenter
+            26 => around_center(rotation(f2dot14(4)?), (fword(6)?, fword(8)?)),
+            // PaintSkew
+            28 => transformed(skew(f2dot14(4)?, f2dot14(6)?)),
+            // PaintSkewAroundCenter
+            30 => around_center(skew(f2dot14(4)?, f2dot14(6)?), (fword(8)?, fword(10)?)),
+            // PaintComposite
+            32 => {
+                let mode = composite_mode(d.read_u8(offset + 4)?)?;
+                Some(Paint::Composite {
+                    source: child(1)?,
+                    mode,
+                    backdrop: child(5)?,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses a `ColorLine`, or `VarColorLine` if `var`, at absolute `offset`.
+    fn color_line(&self, offset: usize, var: bool) -> Option<ColorLine> {
+        let d = self.colr.data;
+        let extend = match d.read_u8(offset)? {
+            1 => Extend::Repeat,
+            2 => Extend::Reflect,
+            // unknown values should be treated as pad
+            _ => Extend::Pad,
+        };
+        let count = d.read_u16(offset + 1)?;
+        let stop_size = if var { 10 } else { 6 };
+        let mut stops = (0..usize::from(count))
+            .map(|i| {
+                let stop = offset + 3 + i * stop_size;
+                Some(ColorStop {
+                    offset: d.read_f2dot14(stop)?,
+                    color: resolve_color(self.cpal, d.read_u16(stop + 2)?),
+                    alpha: d.read_f2dot14(stop + 4)?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        // stable sort, so equal offsets keep their order producing hard stops
+        stops.sort_by(|a, b| total_cmp(a.offset, b.offset));
+        Some(ColorLine { extend, stops })
+    }
+}
+
+/// Counter-clockwise rotation matrix, `angle` in half turns.
+fn rotation(angle: f32) -> [f32; 6] {
+    let (sin, cos) = (
+        (angle * core::f32::consts::PI).sin(),
+        (angle * core::f32::consts::PI).cos(),
+    );
+    [cos, sin, -sin, cos, 0.0, 0.0]
+}
+
+/// Skew matrix, angles in half turns.
+fn skew(x_angle: f32, y_angle: f32) -> [f32; 6] {
+    let x = (x_angle * core::f32::consts::PI).tan();
+    let y = (y_angle * core::f32::consts::PI).tan();
+    [1.0, y, -x, 1.0, 0.0, 0.0]
+}
+
+fn composite_mode(mode: u8) -> Option<CompositeMode> {
+    use CompositeMode::*;
+    Some(match mode {
+        0 => Clear,
+        1 => Source,
+        2 => Destination,
+        3 => SourceOver,
+        4 => DestinationOver,
+        5 => SourceIn,
+        6 => DestinationIn,
+        7 => SourceOut,
+        8 => DestinationOut,
+        9 => SourceAtop,
+        10 => DestinationAtop,
+        11 => Xor,
+        12 => Plus,
+        13 => Screen,
+        14 => Overlay,
+        15 => Darken,
+        16 => Lighten,
+        17 => ColorDodge,
+        18 => ColorBurn,
+        19 => HardLight,
+        20 => SoftLight,
+        21 => Difference,
+        22 => Exclusion,
+        23 => Multiply,
+        24 => Hue,
+        25 => Saturation,
+        26 => Color,
+        27 => Luminosity,
+        _ => return None,
+    })
+}
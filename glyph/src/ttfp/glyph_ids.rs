@@ -0,0 +1,164 @@
+//! Configurable caching of char to glyph id lookups.
+use super::glyph_index;
+use crate::GlyphId;
+use alloc::{borrow::Cow, boxed::Box};
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap as CharMap, vec};
+use core::convert::TryFrom;
+use owned_ttf_parser::{AsFaceRef, PreParsedSubtables};
+#[cfg(feature = "std")]
+use std::{collections::HashMap as CharMap, sync::RwLock};
+
+/// Common chars cached by default.
+const DEFAULT_CHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789#+*ç%&/()=?`~'-.,;:_<>!¨$€\\/\"|§°[]{}üèöé \n\t\r";
+
+/// Number of chars in the Basic Multilingual Plane.
+const BMP_LEN: usize = 0x10000;
+
+/// How a [`FontRef`](crate::FontRef) or [`FontVec`](crate::FontVec) caches char to
+/// glyph id lookups, chosen at construction.
+///
+/// The default caches common latin chars on load. Variants may be added in future,
+/// so matches need a wildcard arm.
+///
+/// # Example
+/// ```
+/// use ab_glyph::{Font, FontRef, GlyphIdCache};
+///
+/// # fn main() -> Result<(), ab_glyph::InvalidFont> {
+/// let data = include_bytes!("../../../dev/fonts/Exo2-Light.otf");
+///
+/// // only cache chars that are actually looked up
+/// let font = FontRef::try_from_slice_and_index_with_cache(data, 0, GlyphIdCache::Lazy)?;
+/// assert_eq!(font.glyph_id('s'), ab_glyph::GlyphId(56));
+///
+/// // cache a custom set of chars
+/// let cache = GlyphIdCache::Chars("0123456789.,".into());
+/// let font = FontRef::try_from_slice_and_index_with_cache(data, 0, cache)?;
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum GlyphIdCache {
+    /// No caching, each lookup reads the `cmap` table.
+    Disabled,
+    /// Looks up these chars on load. Other chars read the `cmap` table.
+    Chars(Cow<'static, str>),
+    /// Dense table of all Basic Multilingual Plane, `U+0000..=U+FFFF`, chars filled from
+    /// the `cmap` table on load, using 128KiB. Other chars read the `cmap` table.
+    Bmp,
+    /// Caches each char on its first lookup, thread-safe.
+    ///
+    /// Requires the `std` feature.
+    #[cfg(feature = "std")]
+    Lazy,
+}
+
+impl Default for GlyphIdCache {
+    #[inline]
+    fn default() -> Self {
+        Self::Chars(Cow::Borrowed(DEFAULT_CHARS))
+    }
+}
+
+/// Glyph id cache of a loaded font.
+pub(super) enum GlyphIds {
+    Disabled,
+    /// Cached chars, `None` if not in the font.
+    Map(CharMap<char, Option<GlyphId>>),
+    /// Glyph ids indexed by char, `0` if not in the font.
+    Bmp(Box<[u16]>),
+    #[cfg(feature = "std")]
+    Lazy(RwLock<CharMap<char, Option<GlyphId>>>),
+}
+
+impl GlyphIds {
+    pub(super) fn new<F: AsFaceRef>(
+        cache: &GlyphIdCache,
+        pre_parsed_subtables: &PreParsedSubtables<'_, F>,
+    ) -> Self {
+        match cache {
+            GlyphIdCache::Disabled => Self::Disabled,
+            GlyphIdCache::Chars(chars) => Self::Map(
+                chars
+                    .chars()
+                    // record missing chars too, so lookups of them are also cached
+                    .map(|c| (c, glyph_index(pre_parsed_subtables, c)))
+                    .collect(),
+            ),
+            GlyphIdCache::Bmp => {
+                let mut table = vec![0; BMP_LEN].into_boxed_slice();
+                // the first subtable mapping a char decides its glyph, as in uncached lookups
+                let mut mapped = vec![false; BMP_LEN];
+                let subtables = pre_parsed_subtables
+                    .as_face_ref()
+                    .tables()
+                    .cmap
+                    .iter()
+                    .flat_map(|cmap| cmap.subtables)
+                    .filter(|subtable| subtable.is_unicode());
+                for subtable in subtables {
+                    subtable.codepoints(|codepoint| {
+                        let idx = codepoint as usize;
+                        if idx >= BMP_LEN || mapped[idx] || char::try_from(codepoint).is_err() {
+                            return;
+                        }
+                        if let Some(id) = subtable.glyph_index(codepoint) {
+                            // `.notdef` mappings stay missing
+                            table[idx] = id.0;
+                            mapped[idx] = true;
+                        }
+                    });
+                }
+                Self::Bmp(table)
+            }
+            #[cfg(feature = "std")]
+            GlyphIdCache::Lazy => Self::Lazy(RwLock::default()),
+        }
+    }
+
+    /// Returns the glyph id of `c`, using `lookup` for chars not in the cache.
+    #[inline]
+    pub(super) fn get(
+        &self,
+        c: char,
+        lookup: impl FnOnce(char) -> Option<GlyphId>,
+    ) -> Option<GlyphId> {
+        match self {
+            Self::Disabled => lookup(c),
+            Self::Map(map) => match map.get(&c) {
+                Some(id) => *id,
+                None => lookup(c),
+            },
+            Self::Bmp(table) => match table.get(c as usize) {
+                Some(&id) => Some(GlyphId(id)).filter(|id| id.0 != 0),
+                None => lookup(c),
+            },
+            #[cfg(feature = "std")]
+            Self::Lazy(lock) => {
+                if let Some(id) = lock.read().unwrap_or_else(|e| e.into_inner()).get(&c) {
+                    return *id;
+                }
+                let id = lookup(c);
+                lock.write()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(c, id);
+                id
+            }
+        }
+    }
+}
+
+impl Clone for GlyphIds {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Disabled => Self::Disabled,
+            Self::Map(map) => Self::Map(map.clone()),
+            Self::Bmp(table) => Self::Bmp(table.clone()),
+            #[cfg(feature = "std")]
+            Self::Lazy(lock) => Self::Lazy(RwLock::new(
+                lock.read().unwrap_or_else(|e| e.into_inner()).clone(),
+            )),
+        }
+    }
+}
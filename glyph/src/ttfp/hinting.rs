@@ -0,0 +1,566 @@
+//! TrueType glyph loading & grid-fitting using glyph instructions.
+use super::{
+    interpreter::{div_fix, mul_fix, pix_round, Interpreter, Limits, Vector, Zone, ON_CURVE},
+    read::ReadBe,
+};
+#[cfg(all(feature = "libm", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+use crate::{point, GlyphId, Outline, OutlineCurve, Point, Rect};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use owned_ttf_parser as ttfp;
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+const MAX_COMPONENT_DEPTH: u8 = 16;
+/// Number of ppems with cached interpreter state, the first cached is evicted first.
+#[cfg(feature = "std")]
+const MAX_CACHED_PPEMS: usize = 8;
+
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+const ROUND_XY_TO_GRID: u16 = 0x0004;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+const USE_MY_METRICS: u16 = 0x0200;
+
+/// Interpreter state after running `fpgm` & `prep`, by 26.6 ppem.
+///
+/// Without `std` nothing is cached & each glyph runs the font programs again.
+#[derive(Debug, Default)]
+pub(super) struct HintingCache {
+    /// `None` if the font programs failed at the ppem.
+    #[cfg(feature = "std")]
+    instances: RwLock<Vec<(i32, Option<Interpreter<'static>>)>>,
+}
+
+impl HintingCache {
+    /// Returns the interpreter set up at `ppem_26`, calling `new` for uncached ppems.
+    fn get<'a>(
+        &self,
+        ppem_26: i32,
+        fpgm: &'a [u8],
+        prep: &'a [u8],
+        new: impl FnOnce() -> Option<Interpreter<'a>>,
+    ) -> Option<Interpreter<'a>> {
+        #[cfg(feature = "std")]
+        {
+            let find = |instances: &[(i32, Option<Interpreter<'static>>)]| {
+                let (_, cached) = instances.iter().find(|(p, _)| *p == ppem_26)?;
+                Some(cached.as_ref().map(|i| i.with_programs(fpgm, prep)))
+            };
+            if let Some(cached) = find(&self.instances.read().unwrap_or_else(|e| e.into_inner())) {
+                return cached;
+            }
+
+            let interpreter = new();
+            let mut instances = self.instances.write().unwrap_or_else(|e| e.into_inner());
+            // another thread may have cached the ppem meanwhile
+            if find(&instances).is_none() {
+                if instances.len() >= MAX_CACHED_PPEMS {
+                    instances.remove(0);
+                }
+                let stored = interpreter.as_ref().map(|i| i.with_programs(&[], &[]));
+                instances.push((ppem_26, stored));
+            }
+            interpreter
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            let _ = (ppem_26, fpgm, prep);
+            new()
+        }
+    }
+}
+
+impl Clone for HintingCache {
+    fn clone(&self) -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            instances: RwLock::new(
+                self.instances
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .iter()
+                    .map(|(p, i)| (*p, i.as_ref().map(|i| i.with_programs(&[], &[]))))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Returns the outline of glyph `id` grid-fitted at `ppem` in font units.
+///
+/// Returns `None` for fonts without `glyf` outlines & variable fonts with
+/// non-default coordinates, which would need `gvar` deltas applied before hinting.
+pub(super) fn hinted_outline(
+    face: &ttfp::Face<'_>,
+    cache: &HintingCache,
+    id: GlyphId,
+    ppem: f32,
+) -> Option<Outline> {
+    #[cfg(feature = "variable-fonts")]
+    if face.has_non_default_variation_coordinates() {
+        return None;
+    }
+
+    let table = |tag: &[u8; 4]| face.table_data(ttfp::Tag::from_bytes(tag));
+    let head = table(b"head")?;
+    let maxp = table(b"maxp")?;
+    let units_per_em = i32::from(head.read_u16(18)?);
+    if !(ppem > 0.0 && ppem < 16384.0) || units_per_em == 0 {
+        return None;
+    }
+
+    // Note: fractional ppem is used even if the font requests integer scaling,
+    // so the result stays aligned to the pixel grid at the requested scale.
+    let ppem_26 = (ppem * 64.0).round() as i32;
+    if ppem_26 == 0 {
+        return None;
+    }
+    let scale = div_fix(ppem_26, units_per_em);
+
+    let limits = Limits {
+        twilight_points: maxp.read_u16(16).unwrap_or(0).into(),
+        storage: maxp.read_u16(18).unwrap_or(0).into(),
+        function_defs: maxp.read_u16(20).unwrap_or(0).into(),
+        stack: maxp.read_u16(24).unwrap_or(0).into(),
+    };
+    let cvt = table(b"cvt ").unwrap_or_default();
+    let fpgm = table(b"fpgm").unwrap_or_default();
+    let prep = table(b"prep").unwrap_or_default();
+    let interpreter = cache.get(ppem_26, fpgm, prep, || {
+        Interpreter::new(
+            fpgm,
+            prep,
+            cvt.chunks_exact(2)
+                .map(|b| i16::from_be_bytes([b[0], b[1]])),
+            limits,
+            pix_round(ppem_26) >> 6,
+            scale,
+        )
+        .ok()
+    })?;
+
+    let mut loader = Loader {
+        face,
+        glyf: table(b"glyf")?,
+        loca: table(b"loca")?,
+        long_loca: head.read_i16(50)? != 0,
+        interpreter,
+        scale,
+    };
+    let glyph = loader.load(id.0, 0)?;
+
+    // points are relative to the hinted origin phantom point
+    let origin_x = glyph.phantom[0].x;
+    let to_units = units_per_em as f32 / ppem_26 as f32;
+    let points: Vec<(Point, bool)> = glyph
+        .points
+        .iter()
+        .zip(&glyph.flags)
+        .map(|(v, flags)| {
+            let p = point((v.x - origin_x) as f32 * to_units, v.y as f32 * to_units);
+            (p, flags & ON_CURVE != 0)
+        })
+        .collect();
+
+    let mut curves = Vec::new();
+    let mut start = 0;
+    for &end in &glyph.contours {
+        contour_curves(points.get(start..=end)?, &mut curves);
+        start = end + 1;
+    }
+
+    let (mut min, mut max) = (points.first()?.0, points.first()?.0);
+    for (p, _) in &points {
+        min = point(min.x.min(p.x), min.y.min(p.y));
+        max = point(max.x.max(p.x), max.y.max(p.y));
+    }
+    if curves.is_empty() || min.x >= max.x || min.y >= max.y {
+        return None;
+    }
+
+    Some(Outline {
+        bounds: Rect {
+            min: point(min.x, max.y),
+            max: point(max.x, min.y),
+        },
+        curves,
+    })
+}
+
+/// Hinted points in 26.6 pixels.
+#[derive(Debug, Default)]
+struct HintedGlyph {
+    points: Vec<Vector>,
+    flags: Vec<u8>,
+    contours: Vec<usize>,
+    /// Horizontal origin & advance, vertical origin & advance points.
+    phantom: [Vector; 4],
+}
+
+struct Loader<'a, 'f> {
+    face: &'f ttfp::Face<'a>,
+    glyf: &'a [u8],
+    loca: &'a [u8],
+    long_loca: bool,
+    interpreter: Interpreter<'a>,
+    scale: i32,
+}
+
+impl<'a> Loader<'a, '_> {
+    fn load(&mut self, id: u16, depth: u8) -> Option<HintedGlyph> {
+        if depth > MAX_COMPONENT_DEPTH {
+            return None;
+        }
+        let data = self.glyph_data(id)?;
+        if data.is_empty() {
+            let phantom = self.phantom_points(id, 0, 0).map(|p| self.scaled(p));
+            return Some(HintedGlyph {
+                phantom: round_phantom(phantom),
+                ..<_>::default()
+            });
+        }
+
+        let contours = data.read_i16(0)?;
+        let phantom = self.phantom_points(id, data.read_i16(2)?, data.read_i16(8)?);
+        match usize::try_from(contours) {
+            Ok(contours) => self.load_simple(data, contours, phantom),
+            Err(_) => self.load_composite(data, phantom, depth),
+        }
+    }
+
+    fn glyph_data(&self, id: u16) -> Option<&'a [u8]> {
+        let index = usize::from(id);
+        let (start, end) = match self.long_loca {
+            true => (
+                self.loca.read_u32(index * 4)? as usize,
+                self.loca.read_u32(index * 4 + 4)? as usize,
+            ),
+            false => (
+                usize::from(self.loca.read_u16(index * 2)?) * 2,
+                usize::from(self.loca.read_u16(index * 2 + 2)?) * 2,
+            ),
+        };
+        match start.cmp(&end) {
+            core::cmp::Ordering::Less => self.glyf.get(start..end),
+            core::cmp::Ordering::Equal => Some(&[]),
+            core::cmp::Ordering::Greater => None,
+        }
+    }
+
+    /// Returns unscaled phantom points for a glyph with bounding box `x_min` & `y_max`.
+    fn phantom_points(&self, id: u16, x_min: i16, y_max: i16) -> [Vector; 4] {
+        let face = self.face;
+        let id = ttfp::GlyphId(id);
+        let advance = i32::from(face.glyph_hor_advance(id).unwrap_or(0));
+        let lsb = i32::from(face.glyph_hor_side_bearing(id).unwrap_or(0));
+        let (tsb, v_advance) = match (face.glyph_ver_side_bearing(id), face.glyph_ver_advance(id)) {
+            (Some(tsb), Some(advance)) => (i32::from(tsb), i32::from(advance)),
+            _ => super::derived_ver_metrics(face, Some(y_max)),
+        };
+        let origin_x = i32::from(x_min) - lsb;
+        let origin_y = i32::from(y_max) + tsb;
+        [
+            Vector::new(origin_x, 0),
+            Vector::new(origin_x + advance, 0),
+            Vector::new(0, origin_y),
+            Vector::new(0, origin_y - v_advance),
+        ]
+    }
+
+    #[inline]
+    fn scaled(&self, v: Vector) -> Vector {
+        Vector::new(mul_fix(v.x, self.scale), mul_fix(v.y, self.scale))
+    }
+
+    fn load_simple(
+        &mut self,
+        data: &'a [u8],
+        contour_count: usize,
+        phantom: [Vector; 4],
+    ) -> Option<HintedGlyph> {
+        let mut offset = 10;
+        let mut contours = Vec::with_capacity(contour_count);
+        for _ in 0..contour_count {
+            let end = usize::from(data.read_u16(offset)?);
+            if contours.last().map_or(false, |last| end <= *last) {
+                return None;
+            }
+            contours.push(end);
+            offset += 2;
+        }
+        let point_count = contours.last().map_or(0, |last| last + 1);
+
+        let instructions_len = usize::from(data.read_u16(offset)?);
+        let instructions = data.get(offset + 2..offset + 2 + instructions_len)?;
+        offset += 2 + instructions_len;
+
+        let mut flags = Vec::with_capacity(point_count + 4);
+        while flags.len() < point_count {
+            let f = data.read_u8(offset)?;
+            offset += 1;
+            let mut repeat = 0;
+            if f & 8 != 0 {
+                repeat = data.read_u8(offset)?;
+                offset += 1;
+            }
+            for _ in 0..=repeat {
+                flags.push(f);
+            }
+        }
+        flags.truncate(point_count);
+
+        let mut orus = Vec::with_capacity(point_count + 4);
+        let mut x = 0_i32;
+        for f in &flags {
+            if f & 2 != 0 {
+                let dx = i32::from(data.read_u8(offset)?);
+                offset += 1;
+                x += if f & 16 != 0 { dx } else { -dx };
+            } else if f & 16 == 0 {
+                x += i32::from(data.read_i16(offset)?);
+                offset += 2;
+            }
+            orus.push(Vector::new(x, 0));
+        }
+        let mut y = 0_i32;
+        for (f, p) in flags.iter().zip(&mut orus) {
+            if f & 4 != 0 {
+                let dy = i32::from(data.read_u8(offset)?);
+                offset += 1;
+                y += if f & 32 != 0 { dy } else { -dy };
+            } else if f & 32 == 0 {
+                y += i32::from(data.read_i16(offset)?);
+                offset += 2;
+            }
+            p.y = y;
+        }
+        orus.extend_from_slice(&phantom);
+
+        let org: Vec<_> = orus.iter().map(|p| self.scaled(*p)).collect();
+        let zone = Zone {
+            orus,
+            cur: org.clone(),
+            org,
+            flags: flags
+                .iter()
+                .map(|f| if f & 1 != 0 { ON_CURVE } else { 0 })
+                .chain([0; 4])
+                .collect(),
+            contours,
+        };
+        Some(self.hint(zone, instructions, false))
+    }
+
+    fn load_composite(
+        &mut self,
+        data: &'a [u8],
+        phantom: [Vector; 4],
+        depth: u8,
+    ) -> Option<HintedGlyph> {
+        let mut glyph = HintedGlyph {
+            phantom: phantom.map(|p| self.scaled(p)),
+            ..<_>::default()
+        };
+
+        let mut offset = 10;
+        let mut flags = MORE_COMPONENTS;
+        while flags & MORE_COMPONENTS != 0 {
+            flags = data.read_u16(offset)?;
+            let id = data.read_u16(offset + 2)?;
+            offset += 4;
+
+            let xy_values = flags & ARGS_ARE_XY_VALUES != 0;
+            let (arg1, arg2) = if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+                let args = (data.read_u16(offset)?, data.read_u16(offset + 2)?);
+                offset += 4;
+                match xy_values {
+                    true => (i32::from(args.0 as i16), i32::from(args.1 as i16)),
+                    false => (i32::from(args.0), i32::from(args.1)),
+                }
+            } else {
+                let args = (data.read_u8(offset)?, data.read_u8(offset + 1)?);
+                offset += 2;
+                match xy_values {
+                    true => (i32::from(args.0 as i8), i32::from(args.1 as i8)),
+                    false => (i32::from(args.0), i32::from(args.1)),
+                }
+            };
+
+            // 2.14 -> 16.16 [xx, yx, xy, yy]
+            let f2dot14 = |offset: usize| data.read_i16(offset).map(|v| i32::from(v) << 2);
+            let matrix = if flags & WE_HAVE_A_SCALE != 0 {
+                let s = f2dot14(offset)?;
+                offset += 2;
+                Some([s, 0, 0, s])
+            } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+                let m = [f2dot14(offset)?, 0, 0, f2dot14(offset + 2)?];
+                offset += 4;
+                Some(m)
+            } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+                let m = [
+                    f2dot14(offset)?,
+                    f2dot14(offset + 2)?,
+                    f2dot14(offset + 4)?,
+                    f2dot14(offset + 6)?,
+                ];
+                offset += 8;
+                Some(m)
+            } else {
+                None
+            };
+
+            let mut component = self.load(id, depth + 1)?;
+            if let Some([xx, yx, xy, yy]) = matrix {
+                for p in &mut component.points {
+                    *p = Vector::new(
+                        mul_fix(p.x, xx) + mul_fix(p.y, xy),
+                        mul_fix(p.x, yx) + mul_fix(p.y, yy),
+                    );
+                }
+            }
+
+            let offset_by = if xy_values {
+                let mut v = self.scaled(Vector::new(arg1, arg2));
+                if flags & ROUND_XY_TO_GRID != 0 {
+                    v = Vector::new(pix_round(v.x), pix_round(v.y));
+                }
+                v
+            } else {
+                // align a point of the component with a point of the glyph so far
+                let base = glyph.points.get(arg1 as usize)?;
+                let matched = component.points.get(arg2 as usize)?;
+                Vector::new(base.x - matched.x, base.y - matched.y)
+            };
+
+            let base = glyph.points.len();
+            glyph.points.extend(
+                component
+                    .points
+                    .iter()
+                    .map(|p| Vector::new(p.x + offset_by.x, p.y + offset_by.y)),
+            );
+            glyph.flags.extend(component.flags);
+            glyph
+                .contours
+                .extend(component.contours.iter().map(|c| c + base));
+            if flags & USE_MY_METRICS != 0 {
+                glyph.phantom = component.phantom;
+            }
+        }
+
+        let instructions = match flags & WE_HAVE_INSTRUCTIONS {
+            0 => &[],
+            _ => {
+                let len = usize::from(data.read_u16(offset)?);
+                data.get(offset + 2..offset + 2 + len)?
+            }
+        };
+        if instructions.is_empty() {
+            glyph.phantom = round_phantom(glyph.phantom);
+            return Some(glyph);
+        }
+
+        // composite instructions refer to the already hinted component points
+        let mut points = glyph.points;
+        points.extend_from_slice(&glyph.phantom);
+        let zone = Zone {
+            orus: points.clone(),
+            org: points.clone(),
+            cur: points,
+            flags: glyph.flags.into_iter().chain([0; 4]).collect(),
+            contours: glyph.contours,
+        };
+        Some(self.hint(zone, instructions, true))
+    }
+
+    /// Rounds phantom points then runs glyph `instructions` on the zone.
+    fn hint(&mut self, mut zone: Zone, instructions: &'a [u8], composite: bool) -> HintedGlyph {
+        let n = zone.cur.len() - 4;
+        let phantom = round_phantom([
+            zone.cur[n],
+            zone.cur[n + 1],
+            zone.cur[n + 2],
+            zone.cur[n + 3],
+        ]);
+        zone.cur[n..].copy_from_slice(&phantom);
+
+        if !instructions.is_empty() && !self.interpreter.glyph_instructions_disabled() {
+            zone = self.interpreter.run_glyph(zone, instructions, composite);
+        }
+
+        HintedGlyph {
+            phantom: [
+                zone.cur[n],
+                zone.cur[n + 1],
+                zone.cur[n + 2],
+                zone.cur[n + 3],
+            ],
+            points: zone.cur[..n].to_vec(),
+            flags: zone.flags[..n].iter().map(|f| f & ON_CURVE).collect(),
+            contours: zone.contours,
+        }
+    }
+}
+
+/// Rounds horizontal phantom points to whole pixels horizontally & vertical ones vertically.
+fn round_phantom([p1, p2, p3, p4]: [Vector; 4]) -> [Vector; 4] {
+    [
+        Vector::new(pix_round(p1.x), p1.y),
+        Vector::new(pix_round(p2.x), p2.y),
+        Vector::new(p3.x, pix_round(p3.y)),
+        Vector::new(p4.x, pix_round(p4.y)),
+    ]
+}
+
+/// Converts a contour of quadratic on & off curve points into curves.
+fn contour_curves(points: &[(Point, bool)], curves: &mut Vec<OutlineCurve>) {
+    let n = points.len();
+    let first_on = points.iter().position(|(_, on)| *on);
+    let (start, skip) = match first_on {
+        Some(i) => (points[i].0, i + 1),
+        // no on curve points, start between the last & first control points
+        None if n > 0 => (midpoint(points[n - 1].0, points[0].0), 0),
+        None => return,
+    };
+
+    let mut last = start;
+    let mut control: Option<Point> = None;
+    let remaining = n - usize::from(first_on.is_some());
+    for (p, on) in points.iter().cycle().skip(skip).take(remaining) {
+        match (on, control.take()) {
+            (true, Some(c)) => {
+                curves.push(OutlineCurve::Quad(last, c, *p));
+                last = *p;
+            }
+            (true, None) => {
+                curves.push(OutlineCurve::Line(last, *p));
+                last = *p;
+            }
+            (false, Some(c)) => {
+                let mid = midpoint(c, *p);
+                curves.push(OutlineCurve::Quad(last, c, mid));
+                last = mid;
+                control = Some(*p);
+            }
+            (false, None) => control = Some(*p),
+        }
+    }
+
+    match control {
+        Some(c) => curves.push(OutlineCurve::Quad(last, c, start)),
+        None if last != start => curves.push(OutlineCurve::Line(last, start)),
+        None => {}
+    }
+}
+
+#[inline]
+fn midpoint(a: Point, b: Point) -> Point {
+    point((a.x + b.x) / 2.0, (a.y + b.y) / 2.0)
+}
@@ -0,0 +1,1855 @@
+//! TrueType bytecode interpreter.
+//!
+//! Follows the classic (FreeType "v35") grid-fitting behaviour, including the undocumented
+//! twilight zone & cut-in quirks of the Windows rasterizer that fonts rely on.
+//! Fixed-point rounding matches FreeType so hinted points are reproducible.
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::convert::TryFrom;
+
+/// Point has been moved along the x-axis.
+pub(super) const TOUCH_X: u8 = 1;
+/// Point has been moved along the y-axis.
+pub(super) const TOUCH_Y: u8 = 2;
+/// Point is on the curve, rather than a quadratic control point.
+pub(super) const ON_CURVE: u8 = 4;
+
+/// Guards against fonts looping forever.
+const MAX_INSTRUCTIONS: u32 = 1_000_000;
+const MAX_CALL_DEPTH: usize = 64;
+
+const X_AXIS: Vector = Vector { x: 0x4000, y: 0 };
+const Y_AXIS: Vector = Vector { x: 0, y: 0x4000 };
+
+/// Invalid bytecode, or a font doing something the interpreter can't.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct HintError;
+
+type HintResult<T = ()> = Result<T, HintError>;
+
+/// A 26.6 fixed-point position, or a 2.14 unit vector.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct Vector {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Vector {
+    #[inline]
+    pub(super) fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x.wrapping_sub(other.x), self.y.wrapping_sub(other.y))
+    }
+}
+
+/// Points an instruction stream can address.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Zone {
+    /// Original positions in font units, or 26.6 for composite glyphs.
+    pub orus: Vec<Vector>,
+    /// Original scaled positions in 26.6.
+    pub org: Vec<Vector>,
+    /// Current (hinted) positions in 26.6.
+    pub cur: Vec<Vector>,
+    pub flags: Vec<u8>,
+    /// Index of the last point of each contour.
+    pub contours: Vec<usize>,
+}
+
+impl Zone {
+    fn with_len(len: usize) -> Self {
+        Self {
+            orus: vec![Vector::default(); len],
+            org: vec![Vector::default(); len],
+            cur: vec![Vector::default(); len],
+            flags: vec![0; len],
+            contours: Vec::new(),
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.cur.len()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RoundState {
+    HalfGrid,
+    Grid,
+    DoubleGrid,
+    DownToGrid,
+    UpToGrid,
+    Off,
+    Super,
+    Super45,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) struct GraphicsState {
+    rp: [usize; 3],
+    gep: [usize; 3],
+    proj_vector: Vector,
+    dual_vector: Vector,
+    free_vector: Vector,
+    loop_count: u32,
+    min_distance: i32,
+    round_state: RoundState,
+    auto_flip: bool,
+    control_value_cutin: i32,
+    single_width_cutin: i32,
+    single_width_value: i32,
+    delta_base: i32,
+    delta_shift: i32,
+    instruct_control: u8,
+    scan_control: i32,
+    scan_type: i32,
+    /// Super rounding period, phase & threshold.
+    period: i32,
+    phase: i32,
+    threshold: i32,
+}
+
+impl Default for GraphicsState {
+    fn default() -> Self {
+        Self {
+            rp: [0; 3],
+            gep: [1; 3],
+            proj_vector: X_AXIS,
+            dual_vector: X_AXIS,
+            free_vector: X_AXIS,
+            loop_count: 1,
+            min_distance: 64,
+            round_state: RoundState::Grid,
+            auto_flip: true,
+            control_value_cutin: 68,
+            single_width_cutin: 0,
+            single_width_value: 0,
+            delta_base: 9,
+            delta_shift: 3,
+            instruct_control: 0,
+            scan_control: 0,
+            scan_type: 0,
+            period: 64,
+            phase: 0,
+            threshold: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CodeRange {
+    Fpgm,
+    Prep,
+    Glyph,
+}
+
+/// A function or instruction definition.
+#[derive(Debug, Clone, Copy)]
+struct Definition {
+    range: CodeRange,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CallFrame {
+    range: CodeRange,
+    return_ip: usize,
+    definition: Definition,
+    count: u32,
+}
+
+/// Sizes declared in the `maxp` table.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct Limits {
+    pub twilight_points: usize,
+    pub storage: usize,
+    pub function_defs: usize,
+    pub stack: usize,
+}
+
+/// Executes font programs for a single ppem.
+///
+/// Construction runs `fpgm` & `prep`, glyph programs then run with the resulting state.
+#[derive(Debug)]
+pub(super) struct Interpreter<'a> {
+    fpgm: &'a [u8],
+    prep: &'a [u8],
+    glyph: &'a [u8],
+    cvt: Vec<i32>,
+    storage: Vec<i32>,
+    functions: Vec<Option<Definition>>,
+    instruction_defs: Vec<(u8, Definition)>,
+    stack: Vec<i32>,
+    max_stack: usize,
+    /// Twilight zone & glyph zone.
+    zones: [Zone; 2],
+    gs: GraphicsState,
+    /// Graphics state set up by `prep`, used by each glyph program.
+    default_gs: GraphicsState,
+    ppem: i32,
+    /// 16.16 font units to 26.6 scale.
+    scale: i32,
+    /// Scale applied to measurements of `orus`, 1.0 for composite glyphs.
+    orus_scale: i32,
+    f_dot_p: i64,
+    range: CodeRange,
+}
+
+impl<'a> Interpreter<'a> {
+    /// Sets up the interpreter at `ppem` & `scale`, running `fpgm` then `prep`.
+    pub(super) fn new(
+        fpgm: &'a [u8],
+        prep: &'a [u8],
+        cvt: impl Iterator<Item = i16>,
+        limits: Limits,
+        ppem: i32,
+        scale: i32,
+    ) -> HintResult<Self> {
+        let mut interpreter = Self {
+            fpgm,
+            prep,
+            glyph: &[],
+            cvt: cvt.map(|v| mul_fix(v.into(), scale)).collect(),
+            storage: vec![0; limits.storage],
+            functions: vec![None; limits.function_defs],
+            instruction_defs: Vec::new(),
+            stack: Vec::with_capacity(limits.stack + 32),
+            max_stack: limits.stack + 32,
+            zones: [Zone::with_len(limits.twilight_points + 4), Zone::default()],
+            gs: GraphicsState::default(),
+            default_gs: GraphicsState::default(),
+            ppem,
+            scale,
+            orus_scale: scale,
+            f_dot_p: 0x4000,
+            range: CodeRange::Fpgm,
+        };
+
+        interpreter.execute(CodeRange::Fpgm)?;
+
+        interpreter.reset_state(GraphicsState::default());
+        interpreter.execute(CodeRange::Prep)?;
+
+        // The Windows rasterizer doesn't let `prep` modify these.
+        let gs = &mut interpreter.gs;
+        gs.proj_vector = X_AXIS;
+        gs.dual_vector = X_AXIS;
+        gs.free_vector = X_AXIS;
+        gs.rp = [0; 3];
+        gs.gep = [1; 3];
+        gs.loop_count = 1;
+        interpreter.default_gs = interpreter.gs;
+
+        Ok(interpreter)
+    }
+
+    /// Returns a copy of the current state that runs functions from `fpgm` & `prep`.
+    ///
+    /// Used to store the state after `prep` without the font data it was read from,
+    /// by passing empty programs, then to restore it with the same programs.
+    #[cfg(feature = "std")]
+    pub(super) fn with_programs<'b>(&self, fpgm: &'b [u8], prep: &'b [u8]) -> Interpreter<'b> {
+        Interpreter {
+            fpgm,
+            prep,
+            glyph: &[],
+            cvt: self.cvt.clone(),
+            storage: self.storage.clone(),
+            functions: self.functions.clone(),
+            instruction_defs: self.instruction_defs.clone(),
+            stack: self.stack.clone(),
+            max_stack: self.max_stack,
+            zones: self.zones.clone(),
+            gs: self.gs,
+            default_gs: self.default_gs,
+            ppem: self.ppem,
+            scale: self.scale,
+            orus_scale: self.orus_scale,
+            f_dot_p: self.f_dot_p,
+            range: self.range,
+        }
+    }
+
+    /// Returns `true` if `prep` has disabled glyph instructions.
+    #[inline]
+    pub(super) fn glyph_instructions_disabled(&self) -> bool {
+        self.default_gs.instruct_control & 1 != 0
+    }
+
+    /// Runs glyph `instructions` on `zone`, returning the hinted zone.
+    ///
+    /// Invalid instructions stop execution, leaving points where they were moved to.
+    /// Composite glyph instructions measure original distances in 26.6 rather than font units.
+    pub(super) fn run_glyph(
+        &mut self,
+        zone: Zone,
+        instructions: &'a [u8],
+        composite: bool,
+    ) -> Zone {
+        self.zones[1] = zone;
+        self.glyph = instructions;
+        self.orus_scale = if composite { 0x10000 } else { self.scale };
+
+        let gs = if self.default_gs.instruct_control & 2 != 0 {
+            GraphicsState::default()
+        } else {
+            self.default_gs
+        };
+        self.reset_state(gs);
+        let _ = self.execute(CodeRange::Glyph);
+
+        core::mem::take(&mut self.zones[1])
+    }
+
+    fn reset_state(&mut self, gs: GraphicsState) {
+        self.gs = gs;
+        self.stack.clear();
+        self.compute_f_dot_p();
+    }
+
+    #[inline]
+    fn code(&self, range: CodeRange) -> &'a [u8] {
+        match range {
+            CodeRange::Fpgm => self.fpgm,
+            CodeRange::Prep => self.prep,
+            CodeRange::Glyph => self.glyph,
+        }
+    }
+
+    fn execute(&mut self, range: CodeRange) -> HintResult {
+        self.range = range;
+        let mut current = range;
+        let mut code = self.code(range);
+        let mut ip = 0;
+        let mut calls: Vec<CallFrame> = Vec::new();
+        let mut executed = 0;
+
+        loop {
+            if ip >= code.len() {
+                return match calls.is_empty() {
+                    true => Ok(()),
+                    // functions must end with ENDF
+                    false => Err(HintError),
+                };
+            }
+            executed += 1;
+            if executed > MAX_INSTRUCTIONS {
+                return Err(HintError);
+            }
+
+            let opcode = code[ip];
+            let mut next_ip = ip + instruction_len(code, ip).ok_or(HintError)?;
+
+            match opcode {
+                // NPUSHB, NPUSHW, PUSHB, PUSHW
+                0x40 | 0x41 | 0xB0..=0xBF => {
+                    let (words, data) = match opcode {
+                        0x40 => (false, &code[ip + 2..next_ip]),
+                        0x41 => (true, &code[ip + 2..next_ip]),
+                        0xB0..=0xB7 => (false, &code[ip + 1..next_ip]),
+                        _ => (true, &code[ip + 1..next_ip]),
+                    };
+                    if words {
+                        for w in data.chunks_exact(2) {
+                            self.push(i16::from_be_bytes([w[0], w[1]]).into())?;
+                        }
+                    } else {
+                        for b in data {
+                            self.push((*b).into())?;
+                        }
+                    }
+                }
+                // IF
+                0x58 => {
+                    if self.pop()? == 0 {
+                        next_ip = skip_conditional(code, ip, true)?;
+                    }
+                }
+                // ELSE
+                0x1B => next_ip = skip_conditional(code, ip, false)?,
+                // EIF
+                0x59 => {}
+                // JMPR, JROT, JROF
+                0x1C | 0x78 | 0x79 => {
+                    let jump = match opcode {
+                        0x1C => true,
+                        0x78 => self.pop()? != 0,
+                        _ => self.pop()? == 0,
+                    };
+                    let offset = self.pop()?;
+                    if jump {
+                        if offset == 0 {
+                            return Err(HintError);
+                        }
+                        next_ip = usize::try_from(ip as i64 + i64::from(offset))
+                            .map_err(|_| HintError)?;
+                        if let Some(frame) = calls.last() {
+                            if next_ip > frame.definition.end {
+                                return Err(HintError);
+                            }
+                        }
+                    }
+                }
+                // FDEF, IDEF
+                0x2C | 0x89 => {
+                    if range == CodeRange::Glyph {
+                        return Err(HintError);
+                    }
+                    let n = self.pop()?;
+                    let end = find_endf(code, next_ip)?;
+                    let definition = Definition {
+                        range: current,
+                        start: next_ip,
+                        end,
+                    };
+                    if opcode == 0x2C {
+                        let n = usize::try_from(n).map_err(|_| HintError)?;
+                        if n >= self.functions.len() {
+                            self.functions.resize(n + 1, None);
+                        }
+                        self.functions[n] = Some(definition);
+                    } else {
+                        let opcode = u8::try_from(n).map_err(|_| HintError)?;
+                        self.instruction_defs.retain(|(op, _)| *op != opcode);
+                        self.instruction_defs.push((opcode, definition));
+                    }
+                    next_ip = end + 1;
+                }
+                // ENDF
+                0x2D => {
+                    let frame = calls.last_mut().ok_or(HintError)?;
+                    frame.count -= 1;
+                    if frame.count > 0 {
+                        next_ip = frame.definition.start;
+                    } else {
+                        let frame = calls.pop().unwrap();
+                        current = frame.range;
+                        code = self.code(current);
+                        next_ip = frame.return_ip;
+                    }
+                }
+                // CALL, LOOPCALL
+                0x2B | 0x2A => {
+                    let n = self.pop()?;
+                    let count = match opcode {
+                        0x2A => self.pop()?,
+                        _ => 1,
+                    };
+                    let definition = usize::try_from(n)
+                        .ok()
+                        .and_then(|n| *self.functions.get(n)?)
+                        .ok_or(HintError)?;
+                    if count > 0 {
+                        if calls.len() >= MAX_CALL_DEPTH {
+                            return Err(HintError);
+                        }
+                        calls.push(CallFrame {
+                            range: current,
+                            return_ip: next_ip,
+                            definition,
+                            count: count as u32,
+                        });
+                        current = definition.range;
+                        code = self.code(current);
+                        next_ip = definition.start;
+                    }
+                }
+                _ => {
+                    if let Some((_, definition)) =
+                        self.instruction_defs.iter().find(|(op, _)| *op == opcode)
+                    {
+                        if calls.len() >= MAX_CALL_DEPTH {
+                            return Err(HintError);
+                        }
+                        let definition = *definition;
+                        calls.push(CallFrame {
+                            range: current,
+                            return_ip: next_ip,
+                            definition,
+                            count: 1,
+                        });
+                        current = definition.range;
+                        code = self.code(current);
+                        next_ip = definition.start;
+                    } else {
+                        self.instruction(opcode)?;
+                    }
+                }
+            }
+            ip = next_ip;
+        }
+    }
+
+    /// Executes instructions not affecting control flow.
+    fn instruction(&mut self, opcode: u8) -> HintResult {
+        match opcode {
+            // SVTCA, SPVTCA, SFVTCA
+            0x00..=0x05 => {
+                let axis = if opcode & 1 == 0 { Y_AXIS } else { X_AXIS };
+                if opcode < 0x04 {
+                    self.gs.proj_vector = axis;
+                    self.gs.dual_vector = axis;
+                }
+                if opcode & !1 != 0x02 {
+                    self.gs.free_vector = axis;
+                }
+                self.compute_f_dot_p();
+            }
+            // SPVTL, SFVTL
+            0x06..=0x09 => {
+                let p1 = self.pop_point()?;
+                let p2 = self.pop_point()?;
+                let a = self.point(self.zp(2), p1)?;
+                let b = self.point(self.zp(1), p2)?;
+                let v = line_vector(b.sub(a), opcode & 1 != 0);
+                if opcode < 0x08 {
+                    self.gs.proj_vector = v;
+                    self.gs.dual_vector = v;
+                } else {
+                    self.gs.free_vector = v;
+                }
+                self.compute_f_dot_p();
+            }
+            // SPVFS, SFVFS
+            0x0A | 0x0B => {
+                let y = self.pop()? as i16;
+                let x = self.pop()? as i16;
+                if let Some(v) = normalize(x.into(), y.into()) {
+                    if opcode == 0x0A {
+                        self.gs.proj_vector = v;
+                        self.gs.dual_vector = v;
+                    } else {
+                        self.gs.free_vector = v;
+                    }
+                }
+                self.compute_f_dot_p();
+            }
+            // GPV, GFV
+            0x0C | 0x0D => {
+                let v = match opcode {
+                    0x0C => self.gs.proj_vector,
+                    _ => self.gs.free_vector,
+                };
+                self.push(v.x)?;
+                self.push(v.y)?;
+            }
+            // SFVTPV
+            0x0E => {
+                self.gs.free_vector = self.gs.proj_vector;
+                self.compute_f_dot_p();
+            }
+            // ISECT
+            0x0F => self.isect()?,
+            // SRP0, SRP1, SRP2
+            0x10..=0x12 => self.gs.rp[usize::from(opcode - 0x10)] = self.pop_point()?,
+            // SZP0, SZP1, SZP2
+            0x13..=0x15 => self.gs.gep[usize::from(opcode - 0x13)] = self.pop_zone()?,
+            // SZPS
+            0x16 => self.gs.gep = [self.pop_zone()?; 3],
+            // SLOOP
+            0x17 => {
+                let n = self.pop()?;
+                if n < 0 {
+                    return Err(HintError);
+                }
+                self.gs.loop_count = n.min(0xFFFF) as u32;
+            }
+            // RTG, RTHG
+            0x18 => self.gs.round_state = RoundState::Grid,
+            0x19 => self.gs.round_state = RoundState::HalfGrid,
+            // SMD
+            0x1A => self.gs.min_distance = self.pop()?,
+            // SCVTCI, SSWCI
+            0x1D => self.gs.control_value_cutin = self.pop()?,
+            0x1E => self.gs.single_width_cutin = self.pop()?,
+            // SSW
+            0x1F => self.gs.single_width_value = mul_fix(self.pop()?, self.scale),
+            // DUP
+            0x20 => {
+                let v = *self.stack.last().ok_or(HintError)?;
+                self.push(v)?;
+            }
+            // POP
+            0x21 => {
+                self.pop()?;
+            }
+            // CLEAR
+            0x22 => self.stack.clear(),
+            // SWAP
+            0x23 => {
+                let a = self.pop()?;
+                let b = self.pop()?;
+                self.push(a)?;
+                self.push(b)?;
+            }
+            // DEPTH
+            0x24 => self.push(self.stack.len() as i32)?,
+            // CINDEX, MINDEX
+            0x25 | 0x26 => {
+                let n = self.pop()?;
+                let len = self.stack.len();
+                if n <= 0 || n as usize > len {
+                    return Err(HintError);
+                }
+                let index = len - n as usize;
+                let v = match opcode {
+                    0x25 => self.stack[index],
+                    _ => self.stack.remove(index),
+                };
+                self.push(v)?;
+            }
+            // ALIGNPTS
+            0x27 => {
+                let p2 = self.pop_point()?;
+                let p1 = self.pop_point()?;
+                let (zp0, zp1) = (self.zp(0), self.zp(1));
+                let distance = self.project(self.point(zp0, p2)?.sub(self.point(zp1, p1)?)) / 2;
+                self.move_point(zp1, p1, distance);
+                self.move_point(zp0, p2, -distance);
+            }
+            // UTP
+            0x29 => {
+                let p = self.pop_point()?;
+                let zp0 = self.zp(0);
+                self.point(zp0, p)?;
+                let mut mask = 0xFF;
+                if self.gs.free_vector.x != 0 {
+                    mask &= !TOUCH_X;
+                }
+                if self.gs.free_vector.y != 0 {
+                    mask &= !TOUCH_Y;
+                }
+                self.zones[zp0].flags[p] &= mask;
+            }
+            // MDAP
+            0x2E | 0x2F => {
+                let p = self.pop_point()?;
+                let zp0 = self.zp(0);
+                let cur = self.point(zp0, p)?;
+                let distance = match opcode & 1 {
+                    1 => {
+                        let d = self.project(cur);
+                        self.round(d) - d
+                    }
+                    _ => 0,
+                };
+                self.move_point(zp0, p, distance);
+                self.gs.rp[0] = p;
+                self.gs.rp[1] = p;
+            }
+            // IUP
+            0x30 | 0x31 => self.iup(opcode & 1 == 1),
+            // SHP
+            0x32 | 0x33 => {
+                let (dx, dy, _, _) = self.point_displacement(opcode)?;
+                let zp2 = self.zp(2);
+                for _ in 0..self.take_loop() {
+                    let p = self.pop_point()?;
+                    if p < self.zones[zp2].len() {
+                        self.shift_point(zp2, p, dx, dy, true);
+                    }
+                }
+            }
+            // SHC
+            0x34 | 0x35 => {
+                let contour = self.pop_point()?;
+                let (dx, dy, zone, refp) = self.point_displacement(opcode)?;
+                let zp2 = self.zp(2);
+                let contours = &self.zones[zp2].contours;
+                if contour >= contours.len() && zp2 == 1 {
+                    return Err(HintError);
+                }
+                let start = match contour {
+                    0 => 0,
+                    _ => contours.get(contour - 1).map_or(0, |c| c + 1),
+                };
+                let limit = match zp2 {
+                    0 => self.zones[0].len(),
+                    _ => contours[contour] + 1,
+                };
+                for p in start..limit {
+                    if zone != zp2 || refp != p {
+                        self.shift_point(zp2, p, dx, dy, true);
+                    }
+                }
+            }
+            // SHZ
+            0x36 | 0x37 => {
+                let _ = self.pop_zone()?;
+                let (dx, dy, zone, refp) = self.point_displacement(opcode)?;
+                let zp2 = self.zp(2);
+                // phantom points are not moved
+                let limit = match zp2 {
+                    0 => self.zones[0].len(),
+                    _ => self.zones[1].contours.last().map_or(0, |c| c + 1),
+                };
+                for p in 0..limit {
+                    if zone != zp2 || refp != p {
+                        self.shift_point(zp2, p, dx, dy, false);
+                    }
+                }
+            }
+            // SHPIX
+            0x38 => {
+                let amount = self.pop()?;
+                let dx = mul_fix14(amount, self.gs.free_vector.x);
+                let dy = mul_fix14(amount, self.gs.free_vector.y);
+                let zp2 = self.zp(2);
+                for _ in 0..self.take_loop() {
+                    let p = self.pop_point()?;
+                    if p < self.zones[zp2].len() {
+                        self.shift_point(zp2, p, dx, dy, true);
+                    }
+                }
+            }
+            // IP
+            0x39 => self.interpolate_points()?,
+            // MSIRP
+            0x3A | 0x3B => {
+                let distance = self.pop()?;
+                let p = self.pop_point()?;
+                let (zp0, zp1) = (self.zp(0), self.zp(1));
+                let rp0 = self.gs.rp[0];
+                let rp0_cur = self.point(zp0, rp0)?;
+                self.point(zp1, p)?;
+                if self.gs.gep[1] == 0 {
+                    let org = self.zones[zp0].org[rp0];
+                    self.zones[zp1].org[p] = org;
+                    self.move_original(zp1, p, distance);
+                    self.zones[zp1].cur[p] = self.zones[zp1].org[p];
+                }
+                let current = self.project(self.zones[zp1].cur[p].sub(rp0_cur));
+                self.move_point(zp1, p, distance.wrapping_sub(current));
+                self.gs.rp[1] = rp0;
+                self.gs.rp[2] = p;
+                if opcode & 1 == 1 {
+                    self.gs.rp[0] = p;
+                }
+            }
+            // ALIGNRP
+            0x3C => {
+                let (zp0, zp1) = (self.zp(0), self.zp(1));
+                let rp0 = self.point(zp0, self.gs.rp[0])?;
+                for _ in 0..self.take_loop() {
+                    let p = self.pop_point()?;
+                    if let Ok(cur) = self.point(zp1, p) {
+                        let distance = self.project(cur.sub(rp0));
+                        self.move_point(zp1, p, -distance);
+                    }
+                }
+            }
+            // RTDG
+            0x3D => self.gs.round_state = RoundState::DoubleGrid,
+            // MIAP
+            0x3E | 0x3F => {
+                let cvt = self.pop()?;
+                let p = self.pop_point()?;
+                let zp0 = self.zp(0);
+                self.point(zp0, p)?;
+                let mut distance = self.read_cvt(cvt)?;
+                if self.gs.gep[0] == 0 {
+                    let fv = self.gs.free_vector;
+                    let org = Vector::new(mul_fix14(distance, fv.x), mul_fix14(distance, fv.y));
+                    self.zones[zp0].org[p] = org;
+                    self.zones[zp0].cur[p] = org;
+                }
+                let original = self.project(self.zones[zp0].cur[p]);
+                if opcode & 1 == 1 {
+                    if (distance - original).abs() > self.gs.control_value_cutin {
+                        distance = original;
+                    }
+                    distance = self.round(distance);
+                }
+                self.move_point(zp0, p, distance.wrapping_sub(original));
+                self.gs.rp[0] = p;
+                self.gs.rp[1] = p;
+            }
+            // WS
+            0x42 => {
+                let v = self.pop()?;
+                let i = self.pop()?;
+                if let Some(s) = usize::try_from(i)
+                    .ok()
+                    .and_then(|i| self.storage.get_mut(i))
+                {
+                    *s = v;
+                }
+            }
+            // RS
+            0x43 => {
+                let i = self.pop()?;
+                let v = usize::try_from(i)
+                    .ok()
+                    .and_then(|i| self.storage.get(i))
+                    .copied()
+                    .unwrap_or(0);
+                self.push(v)?;
+            }
+            // WCVTP, WCVTF
+            0x44 | 0x70 => {
+                let mut v = self.pop()?;
+                let i = self.pop()?;
+                if opcode == 0x70 {
+                    v = mul_fix(v, self.scale);
+                }
+                if let Some(c) = usize::try_from(i).ok().and_then(|i| self.cvt.get_mut(i)) {
+                    *c = v;
+                }
+            }
+            // RCVT
+            0x45 => {
+                let i = self.pop()?;
+                let v = self.read_cvt(i).unwrap_or(0);
+                self.push(v)?;
+            }
+            // GC
+            0x46 | 0x47 => {
+                let p = self.pop_point()?;
+                let zp2 = self.zp(2);
+                let v = match self.point(zp2, p) {
+                    Ok(cur) if opcode & 1 == 0 => self.project(cur),
+                    Ok(_) => self.dual_project(self.zones[zp2].org[p]),
+                    Err(_) => 0,
+                };
+                self.push(v)?;
+            }
+            // SCFS
+            0x48 => {
+                let target = self.pop()?;
+                let p = self.pop_point()?;
+                let zp2 = self.zp(2);
+                let current = self.project(self.point(zp2, p)?);
+                self.move_point(zp2, p, target.wrapping_sub(current));
+                if self.gs.gep[2] == 0 {
+                    self.zones[zp2].org[p] = self.zones[zp2].cur[p];
+                }
+            }
+            // MD
+            0x49 | 0x4A => {
+                let k = self.pop_point()?;
+                let l = self.pop_point()?;
+                let (zp0, zp1) = (self.zp(0), self.zp(1));
+                let a = self.point(zp0, l);
+                let b = self.point(zp1, k);
+                let d = match (a, b) {
+                    (Ok(a), Ok(b)) if opcode & 1 == 1 => self.project(a.sub(b)),
+                    (Ok(_), Ok(_)) if self.gs.gep[0] == 0 || self.gs.gep[1] == 0 => {
+                        self.dual_project(self.zones[zp0].org[l].sub(self.zones[zp1].org[k]))
+                    }
+                    (Ok(_), Ok(_)) => {
+                        let d =
+                            self.dual_project(self.zones[zp0].orus[l].sub(self.zones[zp1].orus[k]));
+                        mul_fix(d, self.orus_scale)
+                    }
+                    _ => 0,
+                };
+                self.push(d)?;
+            }
+            // MPPEM, MPS
+            0x4B | 0x4C => self.push(self.ppem)?,
+            // FLIPON, FLIPOFF
+            0x4D => self.gs.auto_flip = true,
+            0x4E => self.gs.auto_flip = false,
+            // DEBUG
+            0x4F => {
+                self.pop()?;
+            }
+            // LT, LTEQ, GT, GTEQ, EQ, NEQ
+            0x50..=0x55 => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                let result = match opcode {
+                    0x50 => a < b,
+                    0x51 => a <= b,
+                    0x52 => a > b,
+                    0x53 => a >= b,
+                    0x54 => a == b,
+                    _ => a != b,
+                };
+                self.push(result.into())?;
+            }
+            // ODD, EVEN
+            0x56 | 0x57 => {
+                let v = self.pop()?;
+                let odd = (self.round(v) & 127) == 64;
+                self.push((odd == (opcode == 0x56)).into())?;
+            }
+            // AND, OR
+            0x5A | 0x5B => {
+                let b = self.pop()? != 0;
+                let a = self.pop()? != 0;
+                let result = match opcode {
+                    0x5A => a && b,
+                    _ => a || b,
+                };
+                self.push(result.into())?;
+            }
+            // NOT
+            0x5C => {
+                let v = self.pop()?;
+                self.push((v == 0).into())?;
+            }
+            // DELTAP1, DELTAP2, DELTAP3, DELTAC1, DELTAC2, DELTAC3
+            0x5D | 0x71..=0x75 => self.delta(opcode)?,
+            // SDB, SDS
+            0x5E => self.gs.delta_base = self.pop()?,
+            0x5F => {
+                let v = self.pop()?;
+                if !(0..=6).contains(&v) {
+                    return Err(HintError);
+                }
+                self.gs.delta_shift = v;
+            }
+            // ADD, SUB, DIV, MUL
+            0x60..=0x63 => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                let result = match opcode {
+                    0x60 => a.wrapping_add(b),
+                    0x61 => a.wrapping_sub(b),
+                    0x62 => {
+                        if b == 0 {
+                            return Err(HintError);
+                        }
+                        mul_div_no_round(a, 64, b)
+                    }
+                    _ => mul_div(a, b, 64),
+                };
+                self.push(result)?;
+            }
+            // ABS, NEG, FLOOR, CEILING
+            0x64..=0x67 => {
+                let v = self.pop()?;
+                let result = match opcode {
+                    0x64 => v.wrapping_abs(),
+                    0x65 => v.wrapping_neg(),
+                    0x66 => pix_floor(v),
+                    _ => pix_ceil(v),
+                };
+                self.push(result)?;
+            }
+            // ROUND, NROUND
+            0x68..=0x6F => {
+                let v = self.pop()?;
+                let result = match opcode {
+                    0x68..=0x6B => self.round(v),
+                    _ => round_none(v),
+                };
+                self.push(result)?;
+            }
+            // SROUND, S45ROUND
+            0x76 | 0x77 => {
+                let selector = self.pop()?;
+                self.super_round(if opcode == 0x76 { 0x4000 } else { 0x2D41 }, selector);
+                self.gs.round_state = match opcode {
+                    0x76 => RoundState::Super,
+                    _ => RoundState::Super45,
+                };
+            }
+            // ROFF, RUTG, RDTG
+            0x7A => self.gs.round_state = RoundState::Off,
+            0x7C => self.gs.round_state = RoundState::UpToGrid,
+            0x7D => self.gs.round_state = RoundState::DownToGrid,
+            // SANGW, AA
+            0x7E | 0x7F => {
+                self.pop()?;
+            }
+            // FLIPPT
+            0x80 => {
+                for _ in 0..self.take_loop() {
+                    let p = self.pop_point()?;
+                    if let Some(flags) = self.zones[1].flags.get_mut(p) {
+                        *flags ^= ON_CURVE;
+                    }
+                }
+            }
+            // FLIPRGON, FLIPRGOFF
+            0x81 | 0x82 => {
+                let end = self.pop_point()?;
+                let start = self.pop_point()?;
+                let flags = &mut self.zones[1].flags;
+                if start > end || end >= flags.len() {
+                    return Ok(());
+                }
+                for f in &mut flags[start..=end] {
+                    match opcode {
+                        0x81 => *f |= ON_CURVE,
+                        _ => *f &= !ON_CURVE,
+                    }
+                }
+            }
+            // SCANCTRL, SCANTYPE
+            0x85 => self.gs.scan_control = self.pop()?,
+            0x8D => self.gs.scan_type = self.pop()?,
+            // SDPVTL
+            0x86 | 0x87 => {
+                let p1 = self.pop_point()?;
+                let p2 = self.pop_point()?;
+                let (zp1, zp2) = (self.zp(1), self.zp(2));
+                self.point(zp1, p2)?;
+                self.point(zp2, p1)?;
+                let perpendicular = opcode & 1 != 0;
+                let (z1, z2) = (&self.zones[zp1], &self.zones[zp2]);
+                self.gs.dual_vector = line_vector(z1.org[p2].sub(z2.org[p1]), perpendicular);
+                self.gs.proj_vector = line_vector(z1.cur[p2].sub(z2.cur[p1]), perpendicular);
+                self.compute_f_dot_p();
+            }
+            // GETINFO
+            0x88 => {
+                let selector = self.pop()?;
+                let mut info = 0;
+                if selector & 1 != 0 {
+                    // interpreter version
+                    info = 35;
+                }
+                if selector & 32 != 0 {
+                    // grayscale rendering
+                    info |= 1 << 12;
+                }
+                self.push(info)?;
+            }
+            // ROLL
+            0x8A => {
+                let a = self.pop()?;
+                let b = self.pop()?;
+                let c = self.pop()?;
+                self.push(b)?;
+                self.push(a)?;
+                self.push(c)?;
+            }
+            // MAX, MIN
+            0x8B | 0x8C => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.push(if opcode == 0x8B { a.max(b) } else { a.min(b) })?;
+            }
+            // INSTCTRL
+            0x8E => {
+                let selector = self.pop()?;
+                let value = self.pop()?;
+                if !(1..=3).contains(&selector) {
+                    return Err(HintError);
+                }
+                if self.range == CodeRange::Prep {
+                    let bit = 1 << (selector - 1);
+                    self.gs.instruct_control &= !bit;
+                    if value != 0 {
+                        self.gs.instruct_control |= bit;
+                    }
+                }
+            }
+            // MDRP
+            0xC0..=0xDF => self.mdrp(opcode)?,
+            // MIRP
+            0xE0..=0xFF => self.mirp(opcode)?,
+            _ => return Err(HintError),
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn push(&mut self, v: i32) -> HintResult {
+        if self.stack.len() >= self.max_stack {
+            return Err(HintError);
+        }
+        self.stack.push(v);
+        Ok(())
+    }
+
+    #[inline]
+    fn pop(&mut self) -> HintResult<i32> {
+        self.stack.pop().ok_or(HintError)
+    }
+
+    /// Pops a point, contour or reference index.
+    #[inline]
+    fn pop_point(&mut self) -> HintResult<usize> {
+        Ok(self.pop()? as u32 as usize)
+    }
+
+    #[inline]
+    fn pop_zone(&mut self) -> HintResult<usize> {
+        match self.pop()? {
+            0 => Ok(0),
+            1 => Ok(1),
+            _ => Err(HintError),
+        }
+    }
+
+    /// Returns the zone index referenced by zone pointer `n`.
+    #[inline]
+    fn zp(&self, n: usize) -> usize {
+        self.gs.gep[n]
+    }
+
+    /// Returns the current position of `point`, or an error if out of bounds.
+    #[inline]
+    fn point(&self, zone: usize, point: usize) -> HintResult<Vector> {
+        self.zones[zone].cur.get(point).copied().ok_or(HintError)
+    }
+
+    /// Returns the loop count for the next instruction, resetting it.
+    #[inline]
+    fn take_loop(&mut self) -> u32 {
+        core::mem::replace(&mut self.gs.loop_count, 1)
+    }
+
+    fn read_cvt(&self, index: i32) -> HintResult<i32> {
+        usize::try_from(index)
+            .ok()
+            .and_then(|i| self.cvt.get(i))
+            .copied()
+            .ok_or(HintError)
+    }
+
+    fn compute_f_dot_p(&mut self) {
+        let (p, f) = (self.gs.proj_vector, self.gs.free_vector);
+        self.f_dot_p = (i64::from(p.x) * i64::from(f.x) + i64::from(p.y) * i64::from(f.y)) >> 14;
+        // at small sizes this can become too small, causing spikes
+        if self.f_dot_p.abs() < 0x400 {
+            self.f_dot_p = 0x4000;
+        }
+    }
+
+    #[inline]
+    fn project(&self, v: Vector) -> i32 {
+        dot_fix14(v, self.gs.proj_vector)
+    }
+
+    #[inline]
+    fn dual_project(&self, v: Vector) -> i32 {
+        dot_fix14(v, self.gs.dual_vector)
+    }
+
+    /// Moves `point` by `distance` along the freedom vector, as measured along the
+    /// projection vector, touching it.
+    fn move_point(&mut self, zone: usize, point: usize, distance: i32) {
+        let fv = self.gs.free_vector;
+        let f_dot_p = self.f_dot_p;
+        let z = &mut self.zones[zone];
+        if point >= z.len() {
+            return;
+        }
+        if fv.x != 0 {
+            let dx = mul_div_i64(distance.into(), fv.x.into(), f_dot_p);
+            z.cur[point].x = z.cur[point].x.wrapping_add(dx);
+            z.flags[point] |= TOUCH_X;
+        }
+        if fv.y != 0 {
+            let dy = mul_div_i64(distance.into(), fv.y.into(), f_dot_p);
+            z.cur[point].y = z.cur[point].y.wrapping_add(dy);
+            z.flags[point] |= TOUCH_Y;
+        }
+    }
+
+    /// Like [`Self::move_point`] for original positions, without touching.
+    fn move_original(&mut self, zone: usize, point: usize, distance: i32) {
+        let fv = self.gs.free_vector;
+        let f_dot_p = self.f_dot_p;
+        let org = &mut self.zones[zone].org[point];
+        if fv.x != 0 {
+            org.x = org
+                .x
+                .wrapping_add(mul_div_i64(distance.into(), fv.x.into(), f_dot_p));
+        }
+        if fv.y != 0 {
+            org.y = org
+                .y
+                .wrapping_add(mul_div_i64(distance.into(), fv.y.into(), f_dot_p));
+        }
+    }
+
+    /// Shifts `point` by an already computed displacement.
+    fn shift_point(&mut self, zone: usize, point: usize, dx: i32, dy: i32, touch: bool) {
+        let fv = self.gs.free_vector;
+        let z = &mut self.zones[zone];
+        if fv.x != 0 {
+            z.cur[point].x = z.cur[point].x.wrapping_add(dx);
+            if touch {
+                z.flags[point] |= TOUCH_X;
+            }
+        }
+        if fv.y != 0 {
+            z.cur[point].y = z.cur[point].y.wrapping_add(dy);
+            if touch {
+                z.flags[point] |= TOUCH_Y;
+            }
+        }
+    }
+
+    /// Returns the displacement of the reference point used by SHP, SHC & SHZ,
+    /// with the reference zone & point.
+    fn point_displacement(&self, opcode: u8) -> HintResult<(i32, i32, usize, usize)> {
+        let (zone, p) = match opcode & 1 {
+            1 => (self.zp(0), self.gs.rp[1]),
+            _ => (self.zp(1), self.gs.rp[2]),
+        };
+        let cur = self.point(zone, p)?;
+        let d = self.project(cur.sub(self.zones[zone].org[p]));
+        let fv = self.gs.free_vector;
+        let dx = mul_div_i64(d.into(), fv.x.into(), self.f_dot_p);
+        let dy = mul_div_i64(d.into(), fv.y.into(), self.f_dot_p);
+        Ok((dx, dy, zone, p))
+    }
+
+    fn round(&self, d: i32) -> i32 {
+        match self.gs.round_state {
+            RoundState::Grid => {
+                if d >= 0 {
+                    pix_round(d).max(0)
+                } else {
+                    (-pix_round(-d)).min(0)
+                }
+            }
+            RoundState::HalfGrid => {
+                if d >= 0 {
+                    let v = pix_floor(d) + 32;
+                    if d != 0 && v < 0 {
+                        32
+                    } else {
+                        v
+                    }
+                } else {
+                    (-(pix_floor(-d) + 32)).min(-32)
+                }
+            }
+            RoundState::DoubleGrid => {
+                if d >= 0 {
+                    ((d + 16) & !31).max(0)
+                } else {
+                    (-((16 - d) & !31)).min(0)
+                }
+            }
+            RoundState::DownToGrid => {
+                if d >= 0 {
+                    pix_floor(d).max(0)
+                } else {
+                    (-pix_floor(-d)).min(0)
+                }
+            }
+            RoundState::UpToGrid => {
+                if d >= 0 {
+                    pix_ceil(d).max(0)
+                } else {
+                    (-pix_ceil(-d)).min(0)
+                }
+            }
+            RoundState::Off => d,
+            RoundState::Super => {
+                let GraphicsState {
+                    period,
+                    phase,
+                    threshold,
+                    ..
+                } = self.gs;
+                if d >= 0 {
+                    let v = ((d - phase + threshold) & -period) + phase;
+                    if v < 0 {
+                        phase
+                    } else {
+                        v
+                    }
+                } else {
+                    let v = -((threshold - phase - d) & -period) - phase;
+                    if v > 0 {
+                        -phase
+                    } else {
+                        v
+                    }
+                }
+            }
+            RoundState::Super45 => {
+                let GraphicsState {
+                    period,
+                    phase,
+                    threshold,
+                    ..
+                } = self.gs;
+                if period == 0 {
+                    return d;
+                }
+                if d >= 0 {
+                    let v = ((d - phase + threshold) / period) * period + phase;
+                    if v < 0 {
+                        phase
+                    } else {
+                        v
+                    }
+                } else {
+                    let v = -(((threshold - phase - d) / period) * period) - phase;
+                    if v > 0 {
+                        -phase
+                    } else {
+                        v
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sets super rounding parameters, `grid_period` is 2.14.
+    fn super_round(&mut self, grid_period: i32, selector: i32) {
+        let period = match selector & 0xC0 {
+            0x00 => grid_period / 2,
+            0x40 => grid_period,
+            0x80 => grid_period * 2,
+            _ => grid_period,
+        };
+        let phase = match selector & 0x30 {
+            0x00 => 0,
+            0x10 => period / 4,
+            0x20 => period / 2,
+            _ => period * 3 / 4,
+        };
+        let threshold = match selector & 0x0F {
+            0 => period - 1,
+            n => (n - 4) * period / 8,
+        };
+        // 2.14 -> 26.6
+        self.gs.period = period >> 8;
+        self.gs.phase = phase >> 8;
+        self.gs.threshold = threshold >> 8;
+    }
+
+    fn isect(&mut self) -> HintResult {
+        let b1 = self.pop_point()?;
+        let b0 = self.pop_point()?;
+        let a1 = self.pop_point()?;
+        let a0 = self.pop_point()?;
+        let p = self.pop_point()?;
+        let (zp0, zp1, zp2) = (self.zp(0), self.zp(1), self.zp(2));
+
+        let (pa0, pa1) = (self.point(zp1, a0)?, self.point(zp1, a1)?);
+        let (pb0, pb1) = (self.point(zp0, b0)?, self.point(zp0, b1)?);
+        self.point(zp2, p)?;
+
+        let db = pb1.sub(pb0);
+        let da = pa1.sub(pa0);
+        let d = pb0.sub(pa0);
+
+        let discriminant = mul_div(da.x, -db.y, 0x40) + mul_div(da.y, db.x, 0x40);
+        let dot_product = mul_div(da.x, db.x, 0x40) + mul_div(da.y, db.y, 0x40);
+
+        let result = if 19 * discriminant.abs() > dot_product.abs() {
+            let v = mul_div(d.x, -db.y, 0x40) + mul_div(d.y, db.x, 0x40);
+            Vector::new(
+                pa0.x + mul_div(v, da.x, discriminant),
+                pa0.y + mul_div(v, da.y, discriminant),
+            )
+        } else {
+            // parallel lines, take the middle of the middles
+            Vector::new(
+                (pa0.x + pa1.x + pb0.x + pb1.x) / 4,
+                (pa0.y + pa1.y + pb0.y + pb1.y) / 4,
+            )
+        };
+        let z = &mut self.zones[zp2];
+        z.cur[p] = result;
+        z.flags[p] |= TOUCH_X | TOUCH_Y;
+        Ok(())
+    }
+
+    fn interpolate_points(&mut self) -> HintResult {
+        let (zp0, zp1, zp2) = (self.zp(0), self.zp(1), self.zp(2));
+        let (rp1, rp2) = (self.gs.rp[1], self.gs.rp[2]);
+        let twilight = self.gs.gep.contains(&0);
+
+        let valid = rp1 < self.zones[zp0].len() && rp2 < self.zones[zp1].len();
+        let (orus_base, cur_base, old_range, cur_range) = if valid {
+            let orus_base = match twilight {
+                true => self.zones[zp0].org[rp1],
+                false => self.zones[zp0].orus[rp1],
+            };
+            let cur_base = self.zones[zp0].cur[rp1];
+            let old_range = match twilight {
+                true => self.dual_project(self.zones[zp1].org[rp2].sub(orus_base)),
+                false => self.dual_project(self.zones[zp1].orus[rp2].sub(orus_base)),
+            };
+            let cur_range = self.project(self.zones[zp1].cur[rp2].sub(cur_base));
+            (orus_base, cur_base, old_range, cur_range)
+        } else {
+            Default::default()
+        };
+
+        for _ in 0..self.take_loop() {
+            let p = self.pop_point()?;
+            if p >= self.zones[zp2].len() {
+                continue;
+            }
+            let original = match twilight {
+                true => self.zones[zp2].org[p],
+                false => self.zones[zp2].orus[p],
+            };
+            let org_dist = self.dual_project(original.sub(orus_base));
+            let cur_dist = self.project(self.zones[zp2].cur[p].sub(cur_base));
+            let new_dist = match (org_dist, old_range) {
+                (0, _) => 0,
+                (_, 0) => org_dist,
+                _ => mul_div(org_dist, cur_range, old_range),
+            };
+            self.move_point(zp2, p, new_dist.wrapping_sub(cur_dist));
+        }
+        Ok(())
+    }
+
+    /// Interpolates untouched glyph points between touched neighbours in each contour.
+    fn iup(&mut self, x_axis: bool) {
+        let mask = if x_axis { TOUCH_X } else { TOUCH_Y };
+        let zone = &mut self.zones[1];
+        let coord = |v: &Vector| if x_axis { v.x } else { v.y };
+
+        let mut point = 0;
+        for contour in 0..zone.contours.len() {
+            let end = zone.contours[contour].min(zone.len().saturating_sub(1));
+            let first = point;
+            while point <= end && zone.flags[point] & mask == 0 {
+                point += 1;
+            }
+            if point <= end {
+                let first_touched = point;
+                let mut cur_touched = point;
+                point += 1;
+                while point <= end {
+                    if zone.flags[point] & mask != 0 {
+                        iup_interpolate(
+                            zone,
+                            x_axis,
+                            cur_touched + 1,
+                            point - 1,
+                            cur_touched,
+                            point,
+                        );
+                        cur_touched = point;
+                    }
+                    point += 1;
+                }
+
+                if cur_touched == first_touched {
+                    // shift all points by the single touched point's movement
+                    let shift = coord(&zone.cur[cur_touched]) - coord(&zone.org[cur_touched]);
+                    if shift != 0 {
+                        for i in (first..=end).filter(|i| *i != cur_touched) {
+                            let v = &mut zone.cur[i];
+                            match x_axis {
+                                true => v.x = v.x.wrapping_add(shift),
+                                false => v.y = v.y.wrapping_add(shift),
+                            }
+                        }
+                    }
+                } else {
+                    iup_interpolate(
+                        zone,
+                        x_axis,
+                        cur_touched + 1,
+                        end,
+                        cur_touched,
+                        first_touched,
+                    );
+                    if first_touched > 0 {
+                        iup_interpolate(
+                            zone,
+                            x_axis,
+                            first,
+                            first_touched - 1,
+                            cur_touched,
+                            first_touched,
+                        );
+                    }
+                }
+            }
+            point = end + 1;
+        }
+    }
+
+    fn mdrp(&mut self, opcode: u8) -> HintResult {
+        let p = self.pop_point()?;
+        let (zp0, zp1) = (self.zp(0), self.zp(1));
+        let rp0 = self.gs.rp[0];
+        let rp0_cur = self.point(zp0, rp0)?;
+        let cur = self.point(zp1, p)?;
+
+        let mut org_dist = if self.gs.gep[0] == 0 || self.gs.gep[1] == 0 {
+            self.dual_project(self.zones[zp1].org[p].sub(self.zones[zp0].org[rp0]))
+        } else {
+            let d = self.dual_project(self.zones[zp1].orus[p].sub(self.zones[zp0].orus[rp0]));
+            mul_fix(d, self.orus_scale)
+        };
+
+        // single width cut-in
+        if (org_dist - self.gs.single_width_value).abs() < self.gs.single_width_cutin {
+            org_dist = match org_dist >= 0 {
+                true => self.gs.single_width_value,
+                false => -self.gs.single_width_value,
+            };
+        }
+
+        let mut distance = match opcode & 4 {
+            0 => round_none(org_dist),
+            _ => self.round(org_dist),
+        };
+
+        if opcode & 8 != 0 {
+            distance = self.min_distance(org_dist, distance);
+        }
+
+        let cur_dist = self.project(cur.sub(rp0_cur));
+        self.move_point(zp1, p, distance.wrapping_sub(cur_dist));
+
+        self.gs.rp[1] = rp0;
+        self.gs.rp[2] = p;
+        if opcode & 16 != 0 {
+            self.gs.rp[0] = p;
+        }
+        Ok(())
+    }
+
+    fn mirp(&mut self, opcode: u8) -> HintResult {
+        let cvt = self.pop()?;
+        let p = self.pop_point()?;
+        let (zp0, zp1) = (self.zp(0), self.zp(1));
+        let rp0 = self.gs.rp[0];
+        self.point(zp0, rp0)?;
+        self.point(zp1, p)?;
+
+        // cvt -1 is always zero
+        let mut cvt_dist = match cvt {
+            -1 => 0,
+            _ => self.read_cvt(cvt)?,
+        };
+
+        // single width cut-in
+        if (cvt_dist - self.gs.single_width_value).abs() < self.gs.single_width_cutin {
+            cvt_dist = match cvt_dist >= 0 {
+                true => self.gs.single_width_value,
+                false => -self.gs.single_width_value,
+            };
+        }
+
+        if self.gs.gep[1] == 0 {
+            let fv = self.gs.free_vector;
+            let base = self.zones[zp0].org[rp0];
+            let org = Vector::new(
+                base.x.wrapping_add(mul_fix14(cvt_dist, fv.x)),
+                base.y.wrapping_add(mul_fix14(cvt_dist, fv.y)),
+            );
+            self.zones[zp1].org[p] = org;
+            self.zones[zp1].cur[p] = org;
+        }
+
+        let org_dist = self.dual_project(self.zones[zp1].org[p].sub(self.zones[zp0].org[rp0]));
+        let cur_dist = self.project(self.zones[zp1].cur[p].sub(self.zones[zp0].cur[rp0]));
+
+        if self.gs.auto_flip && (org_dist ^ cvt_dist) < 0 {
+            cvt_dist = -cvt_dist;
+        }
+
+        let mut distance = if opcode & 4 != 0 {
+            // cut-in only applies when both points are in the same zone
+            if self.gs.gep[0] == self.gs.gep[1]
+                && (cvt_dist - org_dist).abs() > self.gs.control_value_cutin
+            {
+                cvt_dist = org_dist;
+            }
+            self.round(cvt_dist)
+        } else {
+            round_none(cvt_dist)
+        };
+
+        if opcode & 8 != 0 {
+            distance = self.min_distance(org_dist, distance);
+        }
+
+        self.move_point(zp1, p, distance.wrapping_sub(cur_dist));
+
+        self.gs.rp[1] = rp0;
+        if opcode & 16 != 0 {
+            self.gs.rp[0] = p;
+        }
+        self.gs.rp[2] = p;
+        Ok(())
+    }
+
+    /// Applies the minimum distance to `distance` in the direction of `org_dist`.
+    #[inline]
+    fn min_distance(&self, org_dist: i32, distance: i32) -> i32 {
+        let min = self.gs.min_distance;
+        if org_dist >= 0 {
+            distance.max(min)
+        } else {
+            distance.min(-min)
+        }
+    }
+
+    fn delta(&mut self, opcode: u8) -> HintResult {
+        let n = self.pop()?;
+        let base = self.gs.delta_base
+            + match opcode {
+                0x71 | 0x74 => 16,
+                0x72 | 0x75 => 32,
+                _ => 0,
+            };
+        let zp0 = self.zp(0);
+        for _ in 0..n.max(0) {
+            let target = self.pop_point()?;
+            let arg = self.pop()?;
+            if base + ((arg & 0xF0) >> 4) != self.ppem {
+                continue;
+            }
+            let mut step = (arg & 0xF) - 8;
+            if step >= 0 {
+                step += 1;
+            }
+            let amount = step * (1 << (6 - self.gs.delta_shift));
+            match opcode {
+                // DELTAP
+                0x5D | 0x71 | 0x72 => {
+                    if target < self.zones[zp0].len() {
+                        self.move_point(zp0, target, amount);
+                    }
+                }
+                // DELTAC
+                _ => {
+                    if let Some(v) = self.cvt.get_mut(target) {
+                        *v = v.wrapping_add(amount);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Interpolates untouched points `p1..=p2` between touched `ref1` & `ref2` along one axis.
+fn iup_interpolate(zone: &mut Zone, x_axis: bool, p1: usize, p2: usize, ref1: usize, ref2: usize) {
+    if p1 > p2 || ref1 >= zone.len() || ref2 >= zone.len() {
+        return;
+    }
+    let coord = |v: Vector| if x_axis { v.x } else { v.y };
+
+    let (mut ref1, mut ref2) = (ref1, ref2);
+    if coord(zone.orus[ref1]) > coord(zone.orus[ref2]) {
+        core::mem::swap(&mut ref1, &mut ref2);
+    }
+    let (orus1, orus2) = (coord(zone.orus[ref1]), coord(zone.orus[ref2]));
+    let (org1, org2) = (coord(zone.org[ref1]), coord(zone.org[ref2]));
+    let (cur1, cur2) = (coord(zone.cur[ref1]), coord(zone.cur[ref2]));
+    let (delta1, delta2) = (cur1.wrapping_sub(org1), cur2.wrapping_sub(org2));
+
+    let mut scale = None;
+    for i in p1..=p2 {
+        let org = coord(zone.org[i]);
+        let v = if org <= org1 {
+            org.wrapping_add(delta1)
+        } else if org >= org2 {
+            org.wrapping_add(delta2)
+        } else if cur1 == cur2 || orus1 == orus2 {
+            cur1
+        } else {
+            let scale = *scale.get_or_insert_with(|| div_fix(cur2 - cur1, orus2 - orus1));
+            cur1.wrapping_add(mul_fix(coord(zone.orus[i]) - orus1, scale))
+        };
+        match x_axis {
+            true => zone.cur[i].x = v,
+            false => zone.cur[i].y = v,
+        }
+    }
+}
+
+/// Returns the length of the instruction at `ip`, including inline push data.
+fn instruction_len(code: &[u8], ip: usize) -> Option<usize> {
+    let len = match code[ip] {
+        0x40 => 2 + usize::from(*code.get(ip + 1)?),
+        0x41 => 2 + 2 * usize::from(*code.get(ip + 1)?),
+        op @ 0xB0..=0xB7 => 1 + usize::from(op - 0xAF),
+        op @ 0xB8..=0xBF => 1 + 2 * usize::from(op - 0xB7),
+        _ => 1,
+    };
+    match ip + len <= code.len() {
+        true => Some(len),
+        false => None,
+    }
+}
+
+/// Returns the ip following the ELSE or EIF matching the IF/ELSE at `ip`.
+///
+/// ELSE only ends the skip when `to_else` is set, i.e. when skipping a false IF.
+fn skip_conditional(code: &[u8], mut ip: usize, to_else: bool) -> HintResult<usize> {
+    let mut nesting = 1;
+    loop {
+        ip += instruction_len(code, ip).ok_or(HintError)?;
+        match code.get(ip).ok_or(HintError)? {
+            0x58 => nesting += 1,
+            0x1B if nesting == 1 && to_else => return Ok(ip + 1),
+            0x59 => {
+                nesting -= 1;
+                if nesting == 0 {
+                    return Ok(ip + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the ip of the ENDF ending a definition starting at `ip`.
+fn find_endf(code: &[u8], mut ip: usize) -> HintResult<usize> {
+    loop {
+        match code.get(ip).ok_or(HintError)? {
+            0x2D => return Ok(ip),
+            // nested definitions are invalid
+            0x2C | 0x89 => return Err(HintError),
+            _ => ip += instruction_len(code, ip).ok_or(HintError)?,
+        }
+    }
+}
+
+/// Returns the unit vector along `v`, or perpendicular to it (rotated counter-clockwise).
+/// Coincident points give the x-axis.
+fn line_vector(v: Vector, perpendicular: bool) -> Vector {
+    if v.x == 0 && v.y == 0 {
+        return X_AXIS;
+    }
+    let (x, y) = match perpendicular {
+        true => (-v.y, v.x),
+        false => (v.x, v.y),
+    };
+    normalize(x, y).unwrap_or(X_AXIS)
+}
+
+/// Returns the 2.14 unit vector in the direction of `(x, y)`.
+///
+/// Uses FreeType's fixed-point Newton iteration so results are bit identical.
+fn normalize(x: i32, y: i32) -> Option<Vector> {
+    if x == 0 && y == 0 {
+        return None;
+    }
+    let (sx, sy) = (x.signum(), y.signum());
+    let (mut ux, mut uy) = (x.unsigned_abs(), y.unsigned_abs());
+    if ux == 0 {
+        return Some(Vector::new(0, sy * 0x4000));
+    }
+    if uy == 0 {
+        return Some(Vector::new(sx * 0x4000, 0));
+    }
+
+    // prenormalize so the estimated length is between 2/3 & 4/3 in 16.16
+    let estimate = |x: u32, y: u32| if x > y { x + (y >> 1) } else { y + (x >> 1) };
+    let mut l = estimate(ux, uy);
+    let mut shift = l.leading_zeros() as i32;
+    shift -= 15 + i32::from(l >= (0xAAAA_AAAA_u32 >> shift));
+    if shift > 0 {
+        ux <<= shift;
+        uy <<= shift;
+        l = estimate(ux, uy);
+    } else {
+        ux >>= -shift;
+        uy >>= -shift;
+        l >>= -shift;
+    }
+
+    // Newton's iterations for the reciprocal length
+    let mut b = 0x10000 - l as i32;
+    let (x, y) = (ux as i32, uy as i32);
+    let (mut u, mut v);
+    loop {
+        u = (x + ((x * b) >> 16)) as u32;
+        v = (y + ((y * b) >> 16)) as u32;
+        let mut z = -(u.wrapping_mul(u).wrapping_add(v.wrapping_mul(v)) as i32) / 0x200;
+        z = z * ((0x10000 + b) >> 8) / 0x10000;
+        b += z;
+        if z <= 0 {
+            break;
+        }
+    }
+
+    // 16.16 truncated to 2.14
+    Some(Vector::new(sx * (u as i32 / 4), sy * (v as i32 / 4)))
+}
+
+#[inline]
+fn pix_floor(v: i32) -> i32 {
+    v & !63
+}
+
+#[inline]
+pub(super) fn pix_round(v: i32) -> i32 {
+    pix_floor(v.wrapping_add(32))
+}
+
+#[inline]
+fn pix_ceil(v: i32) -> i32 {
+    pix_floor(v.wrapping_add(63))
+}
+
+#[inline]
+fn round_none(d: i32) -> i32 {
+    d
+}
+
+/// `(a * b) / 0x10000` rounded half away from zero.
+#[inline]
+pub(super) fn mul_fix(a: i32, b: i32) -> i32 {
+    let ab = i64::from(a) * i64::from(b);
+    ((ab + 0x8000 - i64::from(ab < 0)) >> 16) as i32
+}
+
+/// `(a * b) / 0x4000` rounded half away from zero.
+#[inline]
+fn mul_fix14(a: i32, b: i32) -> i32 {
+    let ab = i64::from(a) * i64::from(b);
+    ((ab + 0x2000 - i64::from(ab < 0)) >> 14) as i32
+}
+
+/// Dot product of `v` & 2.14 unit vector `unit`.
+#[inline]
+fn dot_fix14(v: Vector, unit: Vector) -> i32 {
+    let d = i64::from(v.x) * i64::from(unit.x) + i64::from(v.y) * i64::from(unit.y);
+    ((d + 0x2000 - i64::from(d < 0)) >> 14) as i32
+}
+
+/// `(a * 0x10000) / b` rounded.
+#[inline]
+pub(super) fn div_fix(a: i32, b: i32) -> i32 {
+    let negative = (a < 0) != (b < 0);
+    let (a, b) = (i64::from(a).abs(), i64::from(b).abs());
+    let q = match b {
+        0 => 0x7FFF_FFFF,
+        _ => ((a << 16) + (b >> 1)) / b,
+    };
+    (if negative { -q } else { q }) as i32
+}
+
+/// `(a * b) / c` rounded.
+#[inline]
+fn mul_div(a: i32, b: i32, c: i32) -> i32 {
+    mul_div_i64(a.into(), b.into(), c.into())
+}
+
+#[inline]
+fn mul_div_i64(a: i64, b: i64, c: i64) -> i32 {
+    let negative = ((a < 0) != (b < 0)) != (c < 0);
+    let (a, b, c) = (a.abs(), b.abs(), c.abs());
+    let d = match c {
+        0 => 0x7FFF_FFFF,
+        _ => (a * b + (c >> 1)) / c,
+    };
+    (if negative { -d } else { d }) as i32
+}
+
+/// `(a * b) / c` truncated.
+#[inline]
+fn mul_div_no_round(a: i32, b: i32, c: i32) -> i32 {
+    let negative = ((a < 0) != (b < 0)) != (c < 0);
+    let (a, b, c) = (i64::from(a).abs(), i64::from(b).abs(), i64::from(c).abs());
+    let d = match c {
+        0 => 0x7FFF_FFFF,
+        _ => a * b / c,
+    };
+    (if negative { -d } else { d }) as i32
+}
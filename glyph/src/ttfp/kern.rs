@@ -0,0 +1,301 @@
+//! `GPOS` pair adjustment kerning, used for fonts without a `kern` table.
+//!
+//! Coverage & class definitions are resolved on load into sorted glyph id ranges,
+//! format 1 pair sets & the value records are read from the table data when kerning.
+//!
+//! See <https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#lookup-type-2-pair-adjustment-positioning-subtable>.
+use super::{read::ReadBe, shaping::lookup_indices};
+use crate::GlyphId;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use owned_ttf_parser::{self as ttfp, Tag};
+
+/// Pair adjustment lookup type.
+const PAIR: u16 = 2;
+/// Extension lookup type, wrapping subtables of another type.
+const EXTENSION: u16 = 9;
+/// `ValueRecord` format flag of the x advance field.
+const X_ADVANCE: u16 = 0x0004;
+
+/// Sorted `(start, end, value)` inclusive glyph id ranges.
+type Ranges = Vec<(u16, u16, u16)>;
+
+/// Pair adjustment subtables of the `kern` feature for the default script,
+/// with cached coverage & class definitions.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct GposKerning {
+    subtables: Vec<PairSubtable>,
+}
+
+#[derive(Clone, Debug)]
+struct PairSubtable {
+    lookup: u16,
+    /// Offset from the start of the `GPOS` table.
+    offset: usize,
+    /// First glyph coverage, with the coverage index of each range start.
+    coverage: Ranges,
+    value_format1: u16,
+    /// Size in bytes of both value records.
+    record_size: usize,
+    kind: PairKind,
+}
+
+#[derive(Clone, Debug)]
+enum PairKind {
+    /// Format 1 pair sets, indexed by first glyph coverage index.
+    Glyphs { pair_set_count: u16 },
+    /// Format 2 class definitions, ranges of class `0` are omitted.
+    Classes {
+        first: Ranges,
+        second: Ranges,
+        first_count: u16,
+        second_count: u16,
+    },
+}
+
+impl GposKerning {
+    pub(crate) fn new(face: &ttfp::Face<'_>) -> Self {
+        let tables = face.tables();
+        let (gpos, data) = match (tables.gpos, face.table_data(Tag::from_bytes(b"GPOS"))) {
+            // fonts with a `kern` table use it exclusively
+            (Some(gpos), Some(data)) if tables.kern.is_none() => (gpos, data),
+            _ => return Self::default(),
+        };
+        let kern = Tag::from_bytes(b"kern");
+
+        let mut subtables = Vec::new();
+        for lookup_idx in lookup_indices(&gpos, Tag::from_bytes(b"DFLT"), None, |t| t == kern) {
+            pair_subtables(data, lookup_idx, |offset| {
+                // malformed subtables never apply
+                if let Some(subtable) = PairSubtable::new(data, lookup_idx, offset) {
+                    subtables.push(subtable);
+                }
+            });
+        }
+        Self { subtables }
+    }
+
+    /// Returns the horizontal advance adjustment of the `first` glyph when followed
+    /// by `second`, or `None` if no subtables apply.
+    pub(crate) fn kern(
+        &self,
+        face: &ttfp::Face<'_>,
+        first: GlyphId,
+        second: GlyphId,
+    ) -> Option<f32> {
+        if self.subtables.is_empty() {
+            return None;
+        }
+        let data = face.table_data(Tag::from_bytes(b"GPOS"))?;
+        let mut kern = None;
+        let mut applied_lookup = None;
+        for subtable in &self.subtables {
+            // only the first applicable subtable of each lookup is used
+            if applied_lookup == Some(subtable.lookup) {
+                continue;
+            }
+            if let Some(value) = subtable.x_advance(data, first.0, second.0) {
+                kern = Some(kern.unwrap_or(0.0) + f32::from(value));
+                applied_lookup = Some(subtable.lookup);
+            }
+        }
+        kern
+    }
+}
+
+impl PairSubtable {
+    /// Reads the pair adjustment subtable at `offset`, resolving its coverage & classes.
+    fn new(data: &[u8], lookup: u16, offset: usize) -> Option<Self> {
+        let format = data.read_u16(offset)?;
+        let coverage = coverage_ranges(data, offset + usize::from(data.read_u16(offset + 2)?))?;
+        let value_format1 = data.read_u16(offset + 4)?;
+        let value_format2 = data.read_u16(offset + 6)?;
+        let kind = match format {
+            1 => PairKind::Glyphs {
+                pair_set_count: data.read_u16(offset + 8)?,
+            },
+            2 => PairKind::Classes {
+                first: class_ranges(data, offset + usize::from(data.read_u16(offset + 8)?))?,
+                second: class_ranges(data, offset + usize::from(data.read_u16(offset + 10)?))?,
+                first_count: data.read_u16(offset + 12)?,
+                second_count: data.read_u16(offset + 14)?,
+            },
+            _ => return None,
+        };
+        Some(Self {
+            lookup,
+            offset,
+            coverage,
+            value_format1,
+            record_size: value_record_size(value_format1) + value_record_size(value_format2),
+            kind,
+        })
+    }
+
+    /// Returns the `first` glyph x advance adjustment, or `None` if the subtable does not apply.
+    fn x_advance(&self, data: &[u8], first: u16, second: u16) -> Option<i16> {
+        let (start, start_index) = range_value(&self.coverage, first)?;
+        let coverage_idx = start_index.checked_add(first - start)?;
+
+        let value_record = match &self.kind {
+            PairKind::Glyphs { pair_set_count } => {
+                if coverage_idx >= *pair_set_count {
+                    return None;
+                }
+                let pair_set = self.offset
+                    + usize::from(data.read_u16(self.offset + 10 + 2 * usize::from(coverage_idx))?);
+                let count = data.read_u16(pair_set)?;
+                // records of second glyph id, value record 1 & value record 2
+                let record =
+                    binary_search(data, pair_set + 2, 2 + self.record_size, count, second)?;
+                record + 2
+            }
+            PairKind::Classes {
+                first: first_classes,
+                second: second_classes,
+                first_count,
+                second_count,
+            } => {
+                let class1 = range_value(first_classes, first).map_or(0, |(_, class)| class);
+                let class2 = range_value(second_classes, second).map_or(0, |(_, class)| class);
+                if class1 >= *first_count || class2 >= *second_count {
+                    // covered glyphs apply the subtable even without a matrix value
+                    return Some(0);
+                }
+                let idx = usize::from(class1) * usize::from(*second_count) + usize::from(class2);
+                self.offset + 16 + idx * self.record_size
+            }
+        };
+
+        if self.value_format1 & X_ADVANCE == 0 {
+            return Some(0);
+        }
+        // x advance follows the optional x & y placement fields
+        let skip = 2 * (self.value_format1 & 0x0003).count_ones() as usize;
+        data.read_i16(value_record + skip)
+    }
+}
+
+/// Calls `f` with the offset of each pair adjustment subtable of a lookup.
+fn pair_subtables(data: &[u8], lookup_idx: u16, mut f: impl FnMut(usize)) -> Option<()> {
+    let lookup_list = usize::from(data.read_u16(8)?);
+    let lookup =
+        lookup_list + usize::from(data.read_u16(lookup_list + 2 + 2 * usize::from(lookup_idx))?);
+    let kind = data.read_u16(lookup)?;
+    let count = data.read_u16(lookup + 4)?;
+    for idx in 0..usize::from(count) {
+        let subtable = lookup + usize::from(data.read_u16(lookup + 6 + 2 * idx)?);
+        let subtable = match kind {
+            PAIR => subtable,
+            EXTENSION if data.read_u16(subtable + 2)? == PAIR => {
+                subtable + data.read_u32(subtable + 4)? as usize
+            }
+            _ => continue,
+        };
+        f(subtable);
+    }
+    Some(())
+}
+
+/// Size in bytes of a `ValueRecord` with `format`, device table offsets included.
+fn value_record_size(format: u16) -> usize {
+    2 * (format & 0x00FF).count_ones() as usize
+}
+
+/// Reads the coverage table at `offset` as ranges of the coverage index of each range start.
+fn coverage_ranges(data: &[u8], offset: usize) -> Option<Ranges> {
+    let count = data.read_u16(offset + 2)?;
+    let mut ranges = Ranges::new();
+    match data.read_u16(offset)? {
+        1 => {
+            for idx in 0..count {
+                let id = data.read_u16(offset + 4 + 2 * usize::from(idx))?;
+                // consecutive glyphs have consecutive coverage indices
+                match ranges.last_mut() {
+                    Some((_, end, _)) if end.checked_add(1) == Some(id) => *end = id,
+                    _ => ranges.push((id, id, idx)),
+                }
+            }
+        }
+        2 => {
+            for idx in 0..usize::from(count) {
+                let record = offset + 4 + 6 * idx;
+                let start = data.read_u16(record)?;
+                ranges.push((
+                    start,
+                    data.read_u16(record + 2)?,
+                    data.read_u16(record + 4)?,
+                ));
+            }
+        }
+        _ => return None,
+    }
+    Some(ranges)
+}
+
+/// Reads the class definition table at `offset` as ranges of non-zero classes.
+fn class_ranges(data: &[u8], offset: usize) -> Option<Ranges> {
+    let mut ranges = Ranges::new();
+    match data.read_u16(offset)? {
+        1 => {
+            let start = data.read_u16(offset + 2)?;
+            let count = data.read_u16(offset + 4)?;
+            for idx in 0..count {
+                let class = data.read_u16(offset + 6 + 2 * usize::from(idx))?;
+                let id = match start.checked_add(idx) {
+                    Some(id) => id,
+                    None => break,
+                };
+                match ranges.last_mut() {
+                    _ if class == 0 => {}
+                    Some((_, end, last)) if *last == class && end.checked_add(1) == Some(id) => {
+                        *end = id
+                    }
+                    _ => ranges.push((id, id, class)),
+                }
+            }
+        }
+        2 => {
+            let count = data.read_u16(offset + 2)?;
+            for idx in 0..usize::from(count) {
+                let record = offset + 4 + 6 * idx;
+                let class = data.read_u16(record + 4)?;
+                if class != 0 {
+                    ranges.push((data.read_u16(record)?, data.read_u16(record + 2)?, class));
+                }
+            }
+        }
+        _ => return None,
+    }
+    Some(ranges)
+}
+
+/// Finds the range containing `id`, returns `(start, value)`.
+fn range_value(ranges: &[(u16, u16, u16)], id: u16) -> Option<(u16, u16)> {
+    let idx = ranges
+        .binary_search_by(|&(start, end, _)| match () {
+            _ if id < start => Ordering::Greater,
+            _ if id > end => Ordering::Less,
+            _ => Ordering::Equal,
+        })
+        .ok()?;
+    let (start, _, value) = ranges[idx];
+    Some((start, value))
+}
+
+/// Binary search of `count` records of `size` bytes starting with a `u16` glyph id,
+/// returns the offset of the record matching `id`.
+fn binary_search(data: &[u8], start: usize, size: usize, count: u16, id: u16) -> Option<usize> {
+    let (mut lo, mut hi) = (0, usize::from(count));
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let record = start + mid * size;
+        match data.read_u16(record)?.cmp(&id) {
+            Ordering::Less => lo = mid + 1,
+            Ordering::Greater => hi = mid,
+            Ordering::Equal => return Some(record),
+        }
+    }
+    None
+}
@@ -0,0 +1,54 @@
+//! Big-endian table data reading.
+
+/// Big-endian reading of table data by offset.
+pub(super) trait ReadBe {
+    fn read_u8(&self, offset: usize) -> Option<u8>;
+    fn read_u16(&self, offset: usize) -> Option<u16>;
+    fn read_u24(&self, offset: usize) -> Option<u32>;
+    fn read_u32(&self, offset: usize) -> Option<u32>;
+
+    #[inline]
+    fn read_i8(&self, offset: usize) -> Option<i8> {
+        self.read_u8(offset).map(|v| v as i8)
+    }
+
+    #[inline]
+    fn read_i16(&self, offset: usize) -> Option<i16> {
+        self.read_u16(offset).map(|v| v as i16)
+    }
+
+    #[inline]
+    fn read_f2dot14(&self, offset: usize) -> Option<f32> {
+        self.read_i16(offset).map(|v| f32::from(v) / 16384.0)
+    }
+
+    #[inline]
+    fn read_fixed(&self, offset: usize) -> Option<f32> {
+        self.read_u32(offset).map(|v| v as i32 as f32 / 65536.0)
+    }
+}
+
+impl ReadBe for [u8] {
+    #[inline]
+    fn read_u8(&self, offset: usize) -> Option<u8> {
+        self.get(offset).copied()
+    }
+
+    #[inline]
+    fn read_u16(&self, offset: usize) -> Option<u16> {
+        let b = self.get(offset..offset.checked_add(2)?)?;
+        Some(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    #[inline]
+    fn read_u24(&self, offset: usize) -> Option<u32> {
+        let b = self.get(offset..offset.checked_add(3)?)?;
+        Some(u32::from_be_bytes([0, b[0], b[1], b[2]]))
+    }
+
+    #[inline]
+    fn read_u32(&self, offset: usize) -> Option<u32> {
+        let b = self.get(offset..offset.checked_add(4)?)?;
+        Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+}
@@ -0,0 +1,388 @@
+//! Text shaping using `GSUB`, `GPOS` & `GDEF` tables.
+//!
+//! See <https://docs.microsoft.com/en-us/typography/opentype/spec/gsub> &
+//! <https://docs.microsoft.com/en-us/typography/opentype/spec/gpos>.
+use crate::{Feature, Font, GlyphId, ShapedGlyph, DEFAULT_FEATURES};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use owned_ttf_parser::{
+    self as ttfp,
+    gdef::GlyphClass,
+    gpos::{Anchor, PairAdjustment, PositioningSubtable, SingleAdjustment, ValueRecord},
+    gsub::{SingleSubstitution, SubstitutionSubtable},
+    opentype_layout::{LayoutTable, Lookup},
+    Tag,
+};
+
+/// Shapes `text` with `font` using the layout tables of its `face`.
+pub(crate) fn shape<F: Font>(
+    font: &F,
+    face: &ttfp::Face<'_>,
+    text: &str,
+    features: &[Feature],
+    script: &[u8; 4],
+    language: Option<&[u8; 4]>,
+) -> Vec<ShapedGlyph> {
+    let mut buffer = Buffer {
+        glyphs: text
+            .char_indices()
+            .map(|(cluster, c)| Info {
+                id: font.glyph_id(c),
+                cluster,
+            })
+            .collect(),
+        gdef: face.tables().gdef,
+    };
+
+    let enabled = |tag: Tag| {
+        let tag = tag.to_bytes();
+        match features.iter().rev().find(|f| f.tag == tag) {
+            Some(feature) => feature.enabled,
+            None => DEFAULT_FEATURES.contains(&tag),
+        }
+    };
+    let script = Tag::from_bytes(script);
+    let language = language.map(Tag::from_bytes);
+
+    if let Some(gsub) = face.tables().gsub {
+        for lookup in lookups(&gsub, script, language, enabled) {
+            buffer.substitute(&lookup);
+        }
+    }
+
+    let mut positions: Vec<_> = buffer
+        .glyphs
+        .iter()
+        .map(|info| Position {
+            // malformed substitutions may produce glyphs missing from the font
+            x_advance: font.try_h_advance_unscaled(info.id).unwrap_or(0.0),
+            ..<_>::default()
+        })
+        .collect();
+
+    let mut gpos_kerning = false;
+    if let Some(gpos) = face.tables().gpos {
+        let kern = Tag::from_bytes(b"kern");
+        gpos_kerning = enabled(kern) && !lookups(&gpos, script, language, |t| t == kern).is_empty();
+        for lookup in lookups(&gpos, script, language, enabled) {
+            buffer.position(&lookup, &mut positions);
+        }
+    }
+    if !gpos_kerning && enabled(Tag::from_bytes(b"kern")) {
+        for (idx, pair) in buffer.glyphs.windows(2).enumerate() {
+            positions[idx].x_advance += font.kern_unscaled(pair[0].id, pair[1].id);
+        }
+    }
+
+    buffer
+        .glyphs
+        .into_iter()
+        .zip(positions)
+        .map(|(info, pos)| ShapedGlyph {
+            id: info.id,
+            cluster: info.cluster,
+            x_advance: pos.x_advance,
+            y_advance: pos.y_advance,
+            x_offset: pos.x_offset,
+            y_offset: pos.y_offset,
+        })
+        .collect()
+}
+
+/// Returns lookups of `enabled` features for the script & language, in lookup order.
+fn lookups<'a>(
+    table: &LayoutTable<'a>,
+    script: Tag,
+    language: Option<Tag>,
+    enabled: impl Fn(Tag) -> bool,
+) -> Vec<Lookup<'a>> {
+    lookup_indices(table, script, language, enabled)
+        .into_iter()
+        .filter_map(|idx| table.lookups.get(idx))
+        .collect()
+}
+
+/// Returns sorted lookup indices of `enabled` features for the script & language.
+///
+/// Unknown scripts fall back to `DFLT` then `latn`, unknown languages to the
+/// script's default language.
+pub(super) fn lookup_indices(
+    table: &LayoutTable<'_>,
+    script: Tag,
+    language: Option<Tag>,
+    enabled: impl Fn(Tag) -> bool,
+) -> Vec<u16> {
+    let script = table
+        .scripts
+        .find(script)
+        .or_else(|| table.scripts.find(Tag::from_bytes(b"DFLT")))
+        .or_else(|| table.scripts.find(Tag::from_bytes(b"latn")));
+    let lang_sys = script.and_then(|script| {
+        language
+            .and_then(|lang| script.languages.find(lang))
+            .or(script.default_language)
+    });
+    let lang_sys = match lang_sys {
+        Some(lang_sys) => lang_sys,
+        None => return Vec::new(),
+    };
+
+    let required = lang_sys
+        .required_feature
+        .and_then(|idx| table.features.get(idx));
+    let mut indices: Vec<u16> = lang_sys
+        .feature_indices
+        .into_iter()
+        .filter_map(|idx| table.features.get(idx))
+        .filter(|feature| enabled(feature.tag))
+        .chain(required)
+        .flat_map(|feature| feature.lookup_indices)
+        .collect();
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+#[derive(Clone, Copy)]
+struct Info {
+    id: GlyphId,
+    cluster: usize,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Position {
+    x_advance: f32,
+    y_advance: f32,
+    x_offset: f32,
+    y_offset: f32,
+}
+
+impl Position {
+    fn add(&mut self, value: &ValueRecord<'_>) {
+        self.x_offset += f32::from(value.x_placement);
+        self.y_offset += f32::from(value.y_placement);
+        self.x_advance += f32::from(value.x_advance);
+        self.y_advance += f32::from(value.y_advance);
+    }
+}
+
+fn is_zero(value: &ValueRecord<'_>) -> bool {
+    value.x_placement == 0 && value.y_placement == 0 && value.x_advance == 0 && value.y_advance == 0
+}
+
+struct Buffer<'a> {
+    glyphs: Vec<Info>,
+    gdef: Option<ttfp::gdef::Table<'a>>,
+}
+
+impl Buffer<'_> {
+    fn glyph_class(&self, id: GlyphId) -> Option<GlyphClass> {
+        self.gdef.and_then(|gdef| gdef.glyph_class(id.into()))
+    }
+
+    fn is_mark(&self, id: GlyphId) -> bool {
+        self.glyph_class(id) == Some(GlyphClass::Mark)
+    }
+
+    /// Whether the glyph should be skipped by the lookup according to its flags.
+    fn skip(&self, lookup: &Lookup<'_>, id: GlyphId) -> bool {
+        let flags = lookup.flags;
+        match self.glyph_class(id) {
+            Some(GlyphClass::Base) => flags.ignore_base_glyphs(),
+            Some(GlyphClass::Ligature) => flags.ignore_ligatures(),
+            Some(GlyphClass::Mark) => {
+                let gdef = match self.gdef {
+                    Some(gdef) => gdef,
+                    None => return false,
+                };
+                flags.ignore_marks()
+                    || (flags.use_mark_filtering_set()
+                        && !gdef.is_mark_glyph(id.into(), lookup.mark_filtering_set))
+                    || (flags.mark_attachment_type() != 0
+                        && gdef.glyph_mark_attachment_class(id.into())
+                            != u16::from(flags.mark_attachment_type()))
+            }
+            _ => false,
+        }
+    }
+
+    /// Index of the next glyph after `idx` not skipped by the lookup.
+    fn next(&self, lookup: &Lookup<'_>, idx: usize) -> Option<usize> {
+        (idx + 1..self.glyphs.len()).find(|&n| !self.skip(lookup, self.glyphs[n].id))
+    }
+
+    fn substitute(&mut self, lookup: &Lookup<'_>) {
+        let mut idx = 0;
+        while idx < self.glyphs.len() {
+            let id = self.glyphs[idx].id;
+            idx = match self.skip(lookup, id) {
+                true => None,
+                false => lookup
+                    .subtables
+                    .into_iter::<SubstitutionSubtable<'_>>()
+                    .find_map(|subtable| self.apply_substitution(lookup, &subtable, idx)),
+            }
+            .unwrap_or(idx + 1);
+        }
+    }
+
+    /// Applies the substitution at `idx` returning the next index to process,
+    /// or `None` if not applicable.
+    fn apply_substitution(
+        &mut self,
+        lookup: &Lookup<'_>,
+        subtable: &SubstitutionSubtable<'_>,
+        idx: usize,
+    ) -> Option<usize> {
+        let id = self.glyphs[idx].id;
+        let coverage_idx = subtable.coverage().get(id.into())?;
+
+        match subtable {
+            SubstitutionSubtable::Single(SingleSubstitution::Format1 { delta, .. }) => {
+                self.glyphs[idx].id = GlyphId(id.0.wrapping_add(*delta as u16));
+                Some(idx + 1)
+            }
+            SubstitutionSubtable::Single(SingleSubstitution::Format2 { substitutes, .. }) => {
+                self.glyphs[idx].id = GlyphId(substitutes.get(coverage_idx)?.0);
+                Some(idx + 1)
+            }
+            SubstitutionSubtable::Multiple(multiple) => {
+                let sequence = multiple.sequences.get(coverage_idx)?;
+                let cluster = self.glyphs[idx].cluster;
+                let replacement = sequence.substitutes.into_iter().map(|id| Info {
+                    id: GlyphId(id.0),
+                    cluster,
+                });
+                self.glyphs.splice(idx..=idx, replacement);
+                Some(idx + usize::from(sequence.substitutes.len()))
+            }
+            SubstitutionSubtable::Ligature(ligatures) => {
+                let set = ligatures.ligature_sets.get(coverage_idx)?;
+                set.into_iter().find_map(|ligature| {
+                    let mut components = Vec::with_capacity(ligature.components.len().into());
+                    let mut last = idx;
+                    for component in ligature.components {
+                        last = self.next(lookup, last)?;
+                        if self.glyphs[last].id.0 != component.0 {
+                            return None;
+                        }
+                        components.push(last);
+                    }
+                    for component in components.into_iter().rev() {
+                        self.glyphs.remove(component);
+                    }
+                    self.glyphs[idx].id = GlyphId(ligature.glyph.0);
+                    Some(idx + 1)
+                })
+            }
+            // contextual, alternate & reverse substitutions are unsupported
+            _ => None,
+        }
+    }
+
+    fn position(&self, lookup: &Lookup<'_>, positions: &mut [Position]) {
+        let mut idx = 0;
+        while idx < self.glyphs.len() {
+            let id = self.glyphs[idx].id;
+            idx = match self.skip(lookup, id) {
+                true => None,
+                false => lookup
+                    .subtables
+                    .into_iter::<PositioningSubtable<'_>>()
+                    .find_map(|subtable| self.apply_position(lookup, &subtable, idx, positions)),
+            }
+            .unwrap_or(idx + 1);
+        }
+    }
+
+    /// Applies the positioning at `idx` returning the next index to process,
+    /// or `None` if not applicable.
+    fn apply_position(
+        &self,
+        lookup: &Lookup<'_>,
+        subtable: &PositioningSubtable<'_>,
+        idx: usize,
+        positions: &mut [Position],
+    ) -> Option<usize> {
+        let id = self.glyphs[idx].id.into();
+        let coverage_idx = subtable.coverage().get(id)?;
+
+        match subtable {
+            PositioningSubtable::Single(SingleAdjustment::Format1 { value, .. }) => {
+                positions[idx].add(value);
+                Some(idx + 1)
+            }
+            PositioningSubtable::Single(SingleAdjustment::Format2 { values, .. }) => {
+                positions[idx].add(&values.get(coverage_idx)?);
+                Some(idx + 1)
+            }
+            PositioningSubtable::Pair(pair) => {
+                let next = self.next(lookup, idx)?;
+                let second = self.glyphs[next].id.into();
+                let (first_value, second_value) = match pair {
+                    PairAdjustment::Format1 { sets, .. } => sets.get(coverage_idx)?.get(second)?,
+                    PairAdjustment::Format2 {
+                        classes, matrix, ..
+                    } => matrix.get((classes.0.get(id), classes.1.get(second)))?,
+                };
+                positions[idx].add(&first_value);
+                positions[next].add(&second_value);
+                // a second glyph that's been adjusted can't also be a first glyph
+                Some(match is_zero(&second_value) {
+                    true => next,
+                    false => next + 1,
+                })
+            }
+            PositioningSubtable::Cursive(cursive) => {
+                let exit = cursive.sets.exit(coverage_idx)?;
+                let next = self.next(lookup, idx)?;
+                let next_idx = cursive.coverage.get(self.glyphs[next].id.into())?;
+                let entry = cursive.sets.entry(next_idx)?;
+
+                // join the exit of this glyph to the entry of the next
+                positions[idx].x_advance = f32::from(exit.x) + positions[idx].x_offset;
+                let d = f32::from(entry.x) + positions[next].x_offset;
+                positions[next].x_advance -= d;
+                positions[next].x_offset -= d;
+
+                let y = f32::from(exit.y) - f32::from(entry.y);
+                if lookup.flags.right_to_left() {
+                    positions[idx].y_offset = positions[next].y_offset - y;
+                } else {
+                    positions[next].y_offset = positions[idx].y_offset + y;
+                }
+                Some(next)
+            }
+            PositioningSubtable::MarkToBase(mark_base) => {
+                // attach to the preceding base glyph, skipping other marks
+                let base = (0..idx).rev().find(|&n| !self.is_mark(self.glyphs[n].id))?;
+                let base_idx = mark_base.base_coverage.get(self.glyphs[base].id.into())?;
+                let (class, mark_anchor) = mark_base.marks.get(coverage_idx)?;
+                let base_anchor = mark_base.anchors.get(base_idx, class)?;
+                attach_mark(positions, base, idx, &base_anchor, &mark_anchor);
+                Some(idx + 1)
+            }
+            // mark-to-ligature, mark-to-mark & contextual positioning are unsupported
+            _ => None,
+        }
+    }
+}
+
+/// Positions the `mark` glyph so its anchor lies on the `base` glyph's anchor.
+fn attach_mark(
+    positions: &mut [Position],
+    base: usize,
+    mark: usize,
+    base_anchor: &Anchor<'_>,
+    mark_anchor: &Anchor<'_>,
+) {
+    let pen_distance: f32 = positions[base..mark].iter().map(|p| p.x_advance).sum();
+    let base_pos = positions[base];
+    let mark_pos = &mut positions[mark];
+    mark_pos.x_offset =
+        base_pos.x_offset + f32::from(base_anchor.x) - f32::from(mark_anchor.x) - pen_distance;
+    mark_pos.y_offset = base_pos.y_offset + f32::from(base_anchor.y) - f32::from(mark_anchor.y);
+    // marks are positioned relative to the base so shouldn't advance the pen
+    mark_pos.x_advance = 0.0;
+    mark_pos.y_advance = 0.0;
+}
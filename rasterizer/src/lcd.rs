@@ -0,0 +1,226 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::{geometry::Point, FillRule, Rasterizer};
+
+/// 5-tap FIR filter applied to horizontal subpixel coverage by [`LcdRasterizer`]
+/// to reduce color fringes.
+///
+/// Weights apply to the subpixels from 2 left to 2 right of each subpixel & should
+/// sum to `1.0` to preserve overall coverage.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LcdFilter(pub [f32; 5]);
+
+impl LcdFilter {
+    /// FreeType's default filter `[0x08, 0x4D, 0x56, 0x4D, 0x08] / 256`.
+    pub const DEFAULT: Self = Self([
+        8.0 / 256.0,
+        77.0 / 256.0,
+        86.0 / 256.0,
+        77.0 / 256.0,
+        8.0 / 256.0,
+    ]);
+
+    /// FreeType's light filter `[0x00, 0x55, 0x56, 0x55, 0x00] / 256`, sharper with more
+    /// color fringes.
+    pub const LIGHT: Self = Self([0.0, 85.0 / 256.0, 86.0 / 256.0, 85.0 / 256.0, 0.0]);
+
+    /// No filtering, each channel is the coverage of its own subpixel.
+    pub const NONE: Self = Self([0.0, 0.0, 1.0, 0.0, 0.0]);
+}
+
+impl Default for LcdFilter {
+    #[inline]
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Coverage rasterizer for horizontal LCD subpixel antialiasing.
+///
+/// Draws at 3x horizontal resolution, one subpixel per color channel, then applies an
+/// [`LcdFilter`]. Points are in output pixel coordinates.
+///
+/// Filtering spreads coverage up to 2 subpixels to either side, so outlines should be
+/// drawn at least 1 pixel from the left & right edges to avoid clipping.
+/// Vertical subpixel layouts can be drawn by swapping x & y coordinates.
+///
+/// ```
+/// use ab_glyph_rasterizer::*;
+/// let mut rasterizer = LcdRasterizer::new(3, 1);
+///
+/// // a rectangle covering 5 subpixels from x = 1
+/// rasterizer.draw_line(point(1.0, 0.0), point(1.0, 1.0));
+/// rasterizer.draw_line(point(8.0 / 3.0, 1.0), point(8.0 / 3.0, 0.0));
+///
+/// let mut pixels = vec![[0.0; 3]; 3];
+/// rasterizer.set_filter(LcdFilter::NONE);
+/// rasterizer.for_each_pixel_rgb(|index, rgb| pixels[index] = rgb);
+/// assert_eq!(pixels, [[0.0; 3], [1.0; 3], [1.0, 1.0, 0.0]]);
+/// ```
+pub struct LcdRasterizer {
+    rasterizer: Rasterizer,
+    width: usize,
+    height: usize,
+    filter: LcdFilter,
+}
+
+impl LcdRasterizer {
+    /// Allocates a new rasterizer that can draw onto a `width` x `height` RGB coverage grid,
+    /// using [`LcdFilter::DEFAULT`].
+    ///
+    /// ```
+    /// use ab_glyph_rasterizer::LcdRasterizer;
+    /// let mut rasterizer = LcdRasterizer::new(14, 38);
+    /// ```
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            rasterizer: Rasterizer::new(width * 3, height),
+            width,
+            height,
+            filter: LcdFilter::DEFAULT,
+        }
+    }
+
+    /// Sets the filter applied to subpixel coverage.
+    ///
+    /// ```
+    /// # use ab_glyph_rasterizer::*;
+    /// # let mut rasterizer = LcdRasterizer::new(14, 38);
+    /// rasterizer.set_filter(LcdFilter::LIGHT);
+    /// assert_eq!(rasterizer.filter(), LcdFilter::LIGHT);
+    /// ```
+    pub fn set_filter(&mut self, filter: LcdFilter) {
+        self.filter = filter;
+    }
+
+    /// Returns the filter applied to subpixel coverage.
+    pub fn filter(&self) -> LcdFilter {
+        self.filter
+    }
+
+    /// Sets the rule used to fill drawn outlines, see [`Rasterizer::set_fill_rule`].
+    pub fn set_fill_rule(&mut self, fill_rule: FillRule) {
+        self.rasterizer.set_fill_rule(fill_rule);
+    }
+
+    /// Returns the rule used to fill drawn outlines.
+    pub fn fill_rule(&self) -> FillRule {
+        self.rasterizer.fill_rule()
+    }
+
+    /// Resets the rasterizer to an empty `width` x `height` grid, see [`Rasterizer::reset`].
+    ///
+    /// ```
+    /// # use ab_glyph_rasterizer::LcdRasterizer;
+    /// # let mut rasterizer = LcdRasterizer::new(14, 38);
+    /// rasterizer.reset(12, 24);
+    /// assert_eq!(rasterizer.dimensions(), (12, 24));
+    /// ```
+    pub fn reset(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.rasterizer.reset(width * 3, height);
+    }
+
+    /// Clears the rasterizer, see [`Rasterizer::clear`].
+    pub fn clear(&mut self) {
+        self.rasterizer.clear();
+    }
+
+    /// Returns the dimensions, in pixels, the rasterizer was built to draw to.
+    ///
+    /// ```
+    /// # use ab_glyph_rasterizer::*;
+    /// let rasterizer = LcdRasterizer::new(9, 8);
+    /// assert_eq!((9, 8), rasterizer.dimensions());
+    /// ```
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Adds a straight line from `p0` to `p1` to the outline.
+    pub fn draw_line(&mut self, p0: Point, p1: Point) {
+        self.rasterizer.draw_line(subpixel(p0), subpixel(p1));
+    }
+
+    /// Adds a quadratic Bézier curve from `p0` to `p2` to the outline using `p1` as the control.
+    pub fn draw_quad(&mut self, p0: Point, p1: Point, p2: Point) {
+        self.rasterizer
+            .draw_quad(subpixel(p0), subpixel(p1), subpixel(p2));
+    }
+
+    /// Adds a cubic Bézier curve from `p0` to `p3` to the outline using `p1` as the control
+    /// at the beginning of the curve and `p2` at the end of the curve.
+    pub fn draw_cubic(&mut self, p0: Point, p1: Point, p2: Point, p3: Point) {
+        self.rasterizer
+            .draw_cubic(subpixel(p0), subpixel(p1), subpixel(p2), subpixel(p3));
+    }
+
+    /// Run a callback for each pixel `index` & filtered `[r, g, b]` subpixel coverage, with
+    /// indices in `0..width * height`. Red is the leftmost subpixel.
+    ///
+    /// Coverage values are in the range `[0.0, 1.0]` for filters with non-negative weights
+    /// summing to `1.0`.
+    pub fn for_each_pixel_rgb<O: FnMut(usize, [f32; 3])>(&self, mut px_fn: O) {
+        let sub_width = self.width * 3;
+        let mut coverage = Vec::with_capacity(sub_width * self.height);
+        self.rasterizer
+            .for_each_pixel(|_, alpha| coverage.push(alpha.min(1.0)));
+
+        let weights = self.filter.0;
+        for (y, row) in coverage.chunks_exact(sub_width.max(1)).enumerate() {
+            let filtered = |sub_x: usize| {
+                weights
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(tap, w)| {
+                        let idx = (sub_x + tap).checked_sub(2)?;
+                        Some(w * row.get(idx)?)
+                    })
+                    .sum::<f32>()
+            };
+            for x in 0..self.width {
+                let sub_x = x * 3;
+                px_fn(
+                    y * self.width + x,
+                    [filtered(sub_x), filtered(sub_x + 1), filtered(sub_x + 2)],
+                );
+            }
+        }
+    }
+
+    /// Run a callback for each pixel x position, y position & `[r, g, b]` coverage.
+    ///
+    /// Convenience wrapper for [`LcdRasterizer::for_each_pixel_rgb`].
+    pub fn for_each_pixel_rgb_2d<O: FnMut(u32, u32, [f32; 3])>(&self, mut px_fn: O) {
+        let width32 = self.width as u32;
+        self.for_each_pixel_rgb(|idx, rgb| px_fn(idx as u32 % width32, idx as u32 / width32, rgb));
+    }
+}
+
+/// ```
+/// let rasterizer = ab_glyph_rasterizer::LcdRasterizer::new(3, 4);
+/// assert_eq!(
+///     &format!("{:?}", rasterizer),
+///     "LcdRasterizer { width: 3, height: 4, filter: LcdFilter([0.03125, 0.30078125, 0.3359375, 0.30078125, 0.03125]) }"
+/// );
+/// ```
+impl core::fmt::Debug for LcdRasterizer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LcdRasterizer")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("filter", &self.filter)
+            .finish()
+    }
+}
+
+/// Converts a pixel coordinate into the 3x horizontal subpixel grid.
+#[inline]
+fn subpixel(p: Point) -> Point {
+    Point {
+        x: p.x * 3.0,
+        y: p.y,
+    }
+}
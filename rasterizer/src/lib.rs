@@ -30,7 +30,11 @@ mod nostd_float;
 compile_error!("You need to activate either the `std` or `libm` feature.");
 
 mod geometry;
+mod lcd;
 mod raster;
+mod stroke;
 
 pub use geometry::{point, Point};
-pub use raster::Rasterizer;
+pub use lcd::{LcdFilter, LcdRasterizer};
+pub use raster::{FillRule, Rasterizer};
+pub use stroke::{LineCap, LineJoin, Stroke, Stroker};
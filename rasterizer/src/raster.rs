@@ -23,11 +23,50 @@ use alloc::vec::Vec;
 
 use crate::geometry::{lerp, Point};
 
+/// Rule deciding which areas enclosed by an outline are filled, using the winding number of
+/// outline contours around each point.
+///
+/// ```
+/// use ab_glyph_rasterizer::*;
+/// let mut rasterizer = Rasterizer::new(1, 1);
+///
+/// // two overlapping, same direction, squares
+/// for _ in 0..2 {
+///     rasterizer.draw_line(point(0.0, 0.0), point(0.0, 1.0));
+///     rasterizer.draw_line(point(1.0, 1.0), point(1.0, 0.0));
+/// }
+///
+/// let mut alpha = 0.0;
+/// rasterizer.for_each_pixel(|_, a| alpha = a);
+/// assert!(alpha >= 1.0);
+///
+/// rasterizer.set_fill_rule(FillRule::EvenOdd);
+/// rasterizer.for_each_pixel(|_, a| alpha = a);
+/// assert_eq!(alpha, 0.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FillRule {
+    /// Fills areas with a non-zero winding number, i.e. areas enclosed by any contour
+    /// unless cancelled out by contours of the opposite direction. Used by font outlines.
+    NonZero,
+    /// Fills areas with an odd winding number, so overlapping contours cut holes regardless
+    /// of their direction. Common in SVG paths & icons.
+    EvenOdd,
+}
+
+impl Default for FillRule {
+    #[inline]
+    fn default() -> Self {
+        Self::NonZero
+    }
+}
+
 /// Coverage rasterizer for lines, quadratic & cubic beziers.
 pub struct Rasterizer {
     width: usize,
     height: usize,
     a: Vec<f32>,
+    fill_rule: FillRule,
 }
 
 impl Rasterizer {
@@ -42,9 +81,28 @@ impl Rasterizer {
             width,
             height,
             a: vec![0.0; width * height + 4],
+            fill_rule: FillRule::NonZero,
         }
     }
 
+    /// Sets the rule used to fill drawn outlines, [`FillRule::NonZero`] by default.
+    /// The fill rule is kept when the rasterizer is [reset](Self::reset) or [cleared](Self::clear).
+    ///
+    /// ```
+    /// # use ab_glyph_rasterizer::*;
+    /// # let mut rasterizer = Rasterizer::new(14, 38);
+    /// rasterizer.set_fill_rule(FillRule::EvenOdd);
+    /// assert_eq!(rasterizer.fill_rule(), FillRule::EvenOdd);
+    /// ```
+    pub fn set_fill_rule(&mut self, fill_rule: FillRule) {
+        self.fill_rule = fill_rule;
+    }
+
+    /// Returns the rule used to fill drawn outlines.
+    pub fn fill_rule(&self) -> FillRule {
+        self.fill_rule
+    }
+
     /// Resets the rasterizer to an empty `width` x `height` alpha grid. This method behaves as if
     /// the Rasterizer were re-created, with the advantage of not allocating if the total number of
     /// pixels of the grid does not increase.
@@ -95,7 +153,7 @@ impl Rasterizer {
     /// rasterizer.draw_line(point(0.0, 0.48), point(1.22, 0.48));
     /// ```
     pub fn draw_line(&mut self, p0: Point, p1: Point) {
-        if (p0.y - p1.y).abs() <= core::f32::EPSILON {
+        if (p0.y - p1.y).abs() <= f32::EPSILON {
             return;
         }
         let (dir, p0, p1) = if p0.y < p1.y {
@@ -234,6 +292,11 @@ impl Rasterizer {
     /// An `alpha` coverage value of `0.0` means the pixel is not covered at all by the glyph,
     /// whereas a value of `1.0` (or greater) means the pixel is totally covered.
     ///
+    /// Coverage depends on the [`FillRule`]. With [`FillRule::NonZero`] it is the absolute
+    /// accumulated winding area, which exceeds `1.0` inside overlapping contours. With
+    /// [`FillRule::EvenOdd`] coverage is in the range `[0.0, 1.0]`, falling back to `0.0` as
+    /// the winding reaches even numbers.
+    ///
     /// ```
     /// # use ab_glyph_rasterizer::*;
     /// # let (width, height) = (1, 1);
@@ -245,13 +308,18 @@ impl Rasterizer {
     /// ```
     pub fn for_each_pixel<O: FnMut(usize, f32)>(&self, mut px_fn: O) {
         let mut acc = 0.0;
-        self.a[..self.width * self.height]
-            .iter()
-            .enumerate()
-            .for_each(|(idx, c)| {
+        let pixels = self.a[..self.width * self.height].iter().enumerate();
+        match self.fill_rule {
+            FillRule::NonZero => pixels.for_each(|(idx, c)| {
                 acc += c;
                 px_fn(idx, acc.abs());
-            });
+            }),
+            FillRule::EvenOdd => pixels.for_each(|(idx, c)| {
+                acc += c;
+                // distance to the nearest even winding number
+                px_fn(idx, (acc - 2.0 * (acc * 0.5).round()).abs());
+            }),
+        }
     }
 
     /// Run a callback for each pixel x position, y position & alpha.
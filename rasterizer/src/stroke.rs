@@ -0,0 +1,481 @@
+#[cfg(all(feature = "libm", not(feature = "std")))]
+use crate::nostd_float::FloatExt;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::{
+    geometry::{lerp, point, Point},
+    Rasterizer,
+};
+
+/// Maximum distance the stroke outline may deviate from true curves & arcs.
+const TOLERANCE: f32 = 0.05;
+
+/// Shape drawn at the corners where stroked path segments meet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineJoin {
+    /// Extends the outer edges to meet at a point, falling back to
+    /// [`Bevel`](LineJoin::Bevel) past the [`Stroke::miter_limit`].
+    Miter,
+    /// Rounds the corner with a circular arc.
+    Round,
+    /// Cuts the corner with a straight line.
+    Bevel,
+}
+
+/// Shape drawn at the ends of open stroked paths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineCap {
+    /// Ends exactly at the end points.
+    Butt,
+    /// Extends past the end points with a semicircle.
+    Round,
+    /// Extends past the end points by half the stroke width.
+    Square,
+}
+
+/// Stroke style of a [`Stroker`].
+///
+/// ```
+/// use ab_glyph_rasterizer::*;
+/// let stroke = Stroke {
+///     join: LineJoin::Round,
+///     cap: LineCap::Round,
+///     ..Stroke::new(1.5)
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stroke {
+    /// Stroke width, centered on the path.
+    pub width: f32,
+    /// Shape of corners.
+    pub join: LineJoin,
+    /// Maximum ratio of miter length to stroke width for [`LineJoin::Miter`] corners.
+    pub miter_limit: f32,
+    /// Shape of open path ends.
+    pub cap: LineCap,
+}
+
+impl Stroke {
+    /// Stroke of `width` with [`LineJoin::Miter`] corners, a `miter_limit` of `4.0` &
+    /// [`LineCap::Butt`] ends.
+    #[inline]
+    pub fn new(width: f32) -> Self {
+        Self {
+            width,
+            join: LineJoin::Miter,
+            miter_limit: 4.0,
+            cap: LineCap::Butt,
+        }
+    }
+
+    /// Returns the maximum distance the stroke outline extends from the path.
+    ///
+    /// ```
+    /// # use ab_glyph_rasterizer::*;
+    /// assert_eq!(Stroke::new(2.0).extent(), 4.0);
+    /// ```
+    pub fn extent(&self) -> f32 {
+        let half_width = self.width.max(0.0) * 0.5;
+        let join = match self.join {
+            LineJoin::Miter => self.miter_limit.max(1.0),
+            _ => 1.0,
+        };
+        let cap = match self.cap {
+            LineCap::Square => core::f32::consts::SQRT_2,
+            _ => 1.0,
+        };
+        half_width * join.max(cap)
+    }
+}
+
+/// A path of connected points, where `corners` marks points joined using the stroke's
+/// [`LineJoin`] rather than smoothly as part of a curve.
+#[derive(Debug, Clone)]
+struct Subpath {
+    points: Vec<Point>,
+    corners: Vec<bool>,
+    closed: bool,
+}
+
+/// Converts paths of lines, quadratic & cubic beziers into fillable outlines of their
+/// [`Stroke`].
+///
+/// Segments continue the current subpath when they start at its end point, otherwise a new
+/// subpath is started. Subpaths ending at their start point are closed & joined there,
+/// others are open & drawn with [`LineCap`] ends.
+///
+/// ```
+/// use ab_glyph_rasterizer::*;
+/// let mut stroker = Stroker::new(Stroke::new(2.0));
+///
+/// // an open "L" shape
+/// stroker.draw_line(point(2.0, 2.0), point(2.0, 10.0));
+/// stroker.draw_line(point(2.0, 10.0), point(10.0, 10.0));
+///
+/// let mut rasterizer = Rasterizer::new(12, 12);
+/// stroker.draw(&mut rasterizer);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Stroker {
+    stroke: Stroke,
+    subpaths: Vec<Subpath>,
+}
+
+impl Stroker {
+    /// Creates an empty stroker drawing paths with a `stroke` style.
+    pub fn new(stroke: Stroke) -> Self {
+        Self {
+            stroke,
+            subpaths: Vec::new(),
+        }
+    }
+
+    /// Returns the stroke style.
+    pub fn stroke(&self) -> Stroke {
+        self.stroke
+    }
+
+    /// Adds a straight line from `p0` to `p1` to the path.
+    ///
+    /// A zero length line starting a subpath is drawn as a dot with
+    /// [`LineCap::Round`] or [`LineCap::Square`] ends.
+    pub fn draw_line(&mut self, p0: Point, p1: Point) {
+        self.extend(p0, core::iter::once(p1));
+    }
+
+    /// Adds a quadratic Bézier curve from `p0` to `p2` to the path using `p1` as the control.
+    pub fn draw_quad(&mut self, p0: Point, p1: Point, p2: Point) {
+        let dd = length(p0 - p1 - p1 + p2);
+        let n = segments(dd / (4.0 * TOLERANCE));
+        self.extend(
+            p0,
+            (1..n + 1).map(|i| {
+                let t = i as f32 / n as f32;
+                lerp(t, lerp(t, p0, p1), lerp(t, p1, p2))
+            }),
+        );
+    }
+
+    /// Adds a cubic Bézier curve from `p0` to `p3` to the path using `p1` as the control
+    /// at the beginning of the curve and `p2` at the end of the curve.
+    pub fn draw_cubic(&mut self, p0: Point, p1: Point, p2: Point, p3: Point) {
+        let dd = length(p0 - p1 - p1 + p2).max(length(p1 - p2 - p2 + p3));
+        let n = segments(3.0 * dd / (4.0 * TOLERANCE));
+        self.extend(
+            p0,
+            (1..n + 1).map(|i| {
+                let t = i as f32 / n as f32;
+                let (p01, p12, p23) = (lerp(t, p0, p1), lerp(t, p1, p2), lerp(t, p2, p3));
+                lerp(t, lerp(t, p01, p12), lerp(t, p12, p23))
+            }),
+        );
+    }
+
+    /// Closes the current subpath, adding a line back to its start point if necessary.
+    pub fn close(&mut self) {
+        if let Some(subpath) = self.subpaths.last_mut().filter(|s| !s.closed) {
+            let start = subpath.points[0];
+            if subpath.points.last() != Some(&start) {
+                subpath.points.push(start);
+                subpath.corners.push(true);
+            }
+            subpath.closed = true;
+        }
+    }
+
+    /// Adds `points` to the subpath starting or continuing from `p0`. The last point is a
+    /// corner, others are smooth.
+    fn extend(&mut self, p0: Point, points: impl ExactSizeIterator<Item = Point>) {
+        let continues = matches!(
+            self.subpaths.last(),
+            Some(s) if !s.closed && s.points.last() == Some(&p0)
+        );
+        if !continues {
+            self.subpaths.push(Subpath {
+                points: vec![p0],
+                corners: vec![true],
+                closed: false,
+            });
+        }
+        let subpath = self.subpaths.last_mut().unwrap();
+        let len = points.len();
+        for (idx, p) in points.enumerate() {
+            subpath.points.push(p);
+            subpath.corners.push(idx + 1 == len);
+        }
+        if subpath.points.len() > 2 && subpath.points.last() == Some(&subpath.points[0]) {
+            subpath.closed = true;
+        }
+    }
+
+    /// Run a callback for each line of the fillable stroke outline.
+    ///
+    /// The lines form closed contours that should be filled using the
+    /// [`FillRule::NonZero`](crate::FillRule::NonZero) rule.
+    pub fn for_each_line<O: FnMut(Point, Point)>(&self, mut line_fn: O) {
+        let mut polygon = Vec::new();
+        for subpath in &self.subpaths {
+            self.stroke_subpath(subpath, &mut polygon, &mut line_fn);
+        }
+    }
+
+    /// Draws the stroke outline into a `rasterizer`.
+    pub fn draw(&self, rasterizer: &mut Rasterizer) {
+        self.for_each_line(|p0, p1| rasterizer.draw_line(p0, p1));
+    }
+
+    fn stroke_subpath<O: FnMut(Point, Point)>(
+        &self,
+        subpath: &Subpath,
+        polygon: &mut Vec<Point>,
+        line_fn: &mut O,
+    ) {
+        let half_width = self.stroke.width * 0.5;
+        if half_width.is_nan() || half_width <= 0.0 {
+            return;
+        }
+
+        // remove zero length segments
+        let mut points: Vec<(Point, bool)> = Vec::with_capacity(subpath.points.len());
+        for (&p, &corner) in subpath.points.iter().zip(&subpath.corners) {
+            match points.last_mut() {
+                Some(last) if length(p - last.0) <= f32::EPSILON => last.1 |= corner,
+                _ => points.push((p, corner)),
+            }
+        }
+        let mut closed = subpath.closed;
+        if closed
+            && points.len() > 1
+            && length(points[points.len() - 1].0 - points[0].0) <= f32::EPSILON
+        {
+            let (_, corner) = points.pop().unwrap();
+            points[0].1 |= corner;
+        }
+        if points.len() < 3 {
+            closed = false;
+        }
+
+        let mut emit = |polygon: &mut Vec<Point>| {
+            for (idx, &p) in polygon.iter().enumerate() {
+                line_fn(p, polygon[(idx + 1) % polygon.len()]);
+            }
+            polygon.clear();
+        };
+
+        if points.len() == 1 {
+            let center = points[0].0;
+            match self.stroke.cap {
+                LineCap::Butt => {}
+                LineCap::Round => {
+                    let quadrants = [
+                        point(1.0, 0.0),
+                        point(0.0, 1.0),
+                        point(-1.0, 0.0),
+                        point(0.0, -1.0),
+                    ];
+                    for (idx, &from) in quadrants.iter().enumerate() {
+                        polygon.push(center + scale(from, half_width));
+                        arc(
+                            center,
+                            from,
+                            quadrants[(idx + 1) % 4],
+                            half_width,
+                            0,
+                            polygon,
+                        );
+                    }
+                }
+                LineCap::Square => {
+                    for &(x, y) in &[(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+                        polygon.push(center + point(x * half_width, y * half_width));
+                    }
+                }
+            }
+            emit(polygon);
+            return;
+        }
+
+        let reversed: Vec<_> = points.iter().rev().copied().collect();
+        if closed {
+            // left side outlines of each direction, one inside & one outside of the path
+            self.offset(&points, true, half_width, polygon);
+            emit(polygon);
+            self.offset(&reversed, true, half_width, polygon);
+            emit(polygon);
+        } else {
+            let (start, end) = (points[0].0, points[points.len() - 1].0);
+            let start_dir = normalize(points[1].0 - start);
+            let end_dir = normalize(end - points[points.len() - 2].0);
+
+            self.offset(&points, false, half_width, polygon);
+            self.cap(end, end_dir, half_width, polygon);
+            self.offset(&reversed, false, half_width, polygon);
+            self.cap(start, scale(start_dir, -1.0), half_width, polygon);
+            emit(polygon);
+        }
+    }
+
+    /// Adds the outline offset `half_width` to the left of the path `points` to `polygon`.
+    fn offset(
+        &self,
+        points: &[(Point, bool)],
+        closed: bool,
+        half_width: f32,
+        polygon: &mut Vec<Point>,
+    ) {
+        let len = points.len();
+        let segment = |idx: usize| points[(idx + 1) % len].0 - points[idx].0;
+
+        if closed {
+            let mut prev = segment(len - 1);
+            for (idx, &p) in points.iter().enumerate() {
+                let next = segment(idx);
+                self.join(p, prev, next, half_width, polygon);
+                prev = next;
+            }
+        } else {
+            let mut prev = segment(0);
+            polygon.push(points[0].0 + scale(normal(normalize(prev)), half_width));
+            for (idx, &p) in points.iter().enumerate().take(len - 1).skip(1) {
+                let next = segment(idx);
+                self.join(p, prev, next, half_width, polygon);
+                prev = next;
+            }
+            polygon.push(points[len - 1].0 + scale(normal(normalize(prev)), half_width));
+        }
+    }
+
+    /// Adds the left side join at `p` between segments `s0` & `s1`.
+    fn join(
+        &self,
+        (p, corner): (Point, bool),
+        s0: Point,
+        s1: Point,
+        half_width: f32,
+        polygon: &mut Vec<Point>,
+    ) {
+        let (d0, d1) = (normalize(s0), normalize(s1));
+        let (n0, n1) = (normal(d0), normal(d1));
+        let (before, after) = (p + scale(n0, half_width), p + scale(n1, half_width));
+        let (cross, dot) = (cross(d0, d1), dot(d0, d1));
+
+        if cross.abs() <= f32::EPSILON && dot > 0.0 {
+            polygon.push(before);
+            return;
+        }
+        let cos_half = ((1.0 + dot) * 0.5).max(0.0).sqrt();
+        if cross > 0.0 {
+            // inner side, join where the offset edges intersect if within both segments
+            let sin_half = ((1.0 - dot) * 0.5).max(0.0).sqrt();
+            if cos_half > 0.0 && half_width * sin_half <= length(s0).min(length(s1)) * cos_half {
+                let miter = normalize(n0 + n1);
+                polygon.push(p + scale(miter, half_width / cos_half));
+            } else {
+                // pivoting on the path point avoids gaps with short segments
+                polygon.extend_from_slice(&[before, p, after]);
+            }
+            return;
+        }
+
+        // curves are smoothly joined
+        let join = if corner {
+            self.stroke.join
+        } else {
+            LineJoin::Round
+        };
+        polygon.push(before);
+        match join {
+            LineJoin::Bevel => {}
+            LineJoin::Miter => {
+                if cos_half > 0.0 && cos_half.recip() <= self.stroke.miter_limit {
+                    let miter = normalize(n0 + n1);
+                    polygon.push(p + scale(miter, half_width / cos_half));
+                }
+            }
+            LineJoin::Round => {
+                if dot <= -1.0 + f32::EPSILON {
+                    // turning back on itself, the arc passes ahead of the path
+                    arc(p, n0, d0, half_width, 0, polygon);
+                    polygon.push(p + scale(d0, half_width));
+                    arc(p, d0, n1, half_width, 0, polygon);
+                } else {
+                    arc(p, n0, n1, half_width, 0, polygon);
+                }
+            }
+        }
+        polygon.push(after);
+    }
+
+    /// Adds the cap at the path end `p` going in direction `dir`, from the left side to the
+    /// right, excluding both.
+    fn cap(&self, p: Point, dir: Point, half_width: f32, polygon: &mut Vec<Point>) {
+        let n = normal(dir);
+        match self.stroke.cap {
+            LineCap::Butt => {}
+            LineCap::Square => {
+                polygon.push(p + scale(n + dir, half_width));
+                polygon.push(p + scale(dir - n, half_width));
+            }
+            LineCap::Round => {
+                arc(p, n, dir, half_width, 0, polygon);
+                polygon.push(p + scale(dir, half_width));
+                arc(p, dir, scale(n, -1.0), half_width, 0, polygon);
+            }
+        }
+    }
+}
+
+/// Adds points of the arc of `radius` around `center` between unit vectors `from` & `to`,
+/// excluding both, by bisection. The angle between `from` & `to` must be less than 180°.
+fn arc(center: Point, from: Point, to: Point, radius: f32, depth: u8, polygon: &mut Vec<Point>) {
+    let mid = normalize(from + to);
+    // distance between the chord & the arc
+    if depth >= 16 || radius * (1.0 - dot(from, mid)) <= TOLERANCE {
+        return;
+    }
+    arc(center, from, mid, radius, depth + 1, polygon);
+    polygon.push(center + scale(mid, radius));
+    arc(center, mid, to, radius, depth + 1, polygon);
+}
+
+/// Number of line segments to flatten a curve into, with `n * n` segments required to
+/// keep within the tolerance.
+#[inline]
+fn segments(n_squared: f32) -> usize {
+    (n_squared.sqrt().ceil() as usize).clamp(1, 128)
+}
+
+#[inline]
+fn scale(p: Point, s: f32) -> Point {
+    point(p.x * s, p.y * s)
+}
+
+#[inline]
+fn dot(a: Point, b: Point) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+
+#[inline]
+fn cross(a: Point, b: Point) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+#[inline]
+fn length(p: Point) -> f32 {
+    dot(p, p).sqrt()
+}
+
+#[inline]
+fn normalize(p: Point) -> Point {
+    match length(p) {
+        len if len > 0.0 => scale(p, len.recip()),
+        _ => p,
+    }
+}
+
+/// Unit vector rotated 90° to the left of a unit direction, for y-up coordinates.
+#[inline]
+fn normal(dir: Point) -> Point {
+    point(-dir.y, dir.x)
+}